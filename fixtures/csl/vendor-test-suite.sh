@@ -0,0 +1,8 @@
+#!/bin/sh
+# Copy the fixtures named in test-suite.list from the CSL test suite into test-suite/.
+set -eu
+base=https://raw.githubusercontent.com/citation-style-language/test-suite/master/processor-tests/humans
+here=$(dirname "$0")
+grep -v -e '^#' -e '^$' "$here/test-suite.list" | while read -r name; do
+    curl -fsSL "$base/$name.txt" -o "$here/test-suite/$name.txt"
+done
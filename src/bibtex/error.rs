@@ -0,0 +1,9 @@
+use snafu::Snafu;
+
+/// Errors returned while configuring BibTeX export.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum BibtexError {
+    #[snafu(display("Invalid citation key pattern `{pattern}`: {message}"))]
+    KeyPattern { pattern: String, message: String },
+}
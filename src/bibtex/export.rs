@@ -0,0 +1,541 @@
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+
+use crate::bibtex::key::{CitationKeyGenerator, unique_keys};
+use crate::bibtex::latex::escape;
+use crate::bibtex::{BibDialect, BibEntry, BibValue};
+use crate::citeproc::output::{Markup, Node, rich_text};
+use crate::csl::{CslDate, CslItemType, CslName, CslValue, DoiMetadata};
+
+/// A page range separator: hyphens, en or em dashes and the spaces around them.
+static PAGE_RANGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*[-–—]+\s*").unwrap());
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+type KeyFn = Arc<dyn Fn(&DoiMetadata) -> String + Send + Sync>;
+
+/// Options for [`BibEntry::from_metadata`].
+#[derive(Clone, Default)]
+pub struct BibtexOptions {
+    dialect: BibDialect,
+    citation_key: Option<KeyFn>,
+    key_generator: Option<CitationKeyGenerator>,
+    include_abstract: bool,
+    protect_titles: bool,
+}
+
+impl BibtexOptions {
+    /// BibTeX output with protected title capitalization.
+    pub fn new() -> Self {
+        Self {
+            protect_titles: true,
+            ..Default::default()
+        }
+    }
+
+    /// Write BibLaTeX instead of BibTeX.
+    pub fn biblatex(mut self) -> Self {
+        self.dialect = BibDialect::Biblatex;
+        self
+    }
+
+    /// Set the dialect.
+    pub fn dialect(mut self, dialect: BibDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Generate citation keys with a function instead of [`default_citation_key`].
+    pub fn citation_key(
+        mut self,
+        key: impl Fn(&DoiMetadata) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.citation_key = Some(Arc::new(key));
+        self
+    }
+
+    /// Generate citation keys from a pattern; [`write_bibtex`] also suffixes
+    /// keys shared by several items.
+    pub fn key_generator(mut self, generator: CitationKeyGenerator) -> Self {
+        self.key_generator = Some(generator);
+        self
+    }
+
+    /// Include the `abstract` field.
+    pub fn include_abstract(mut self) -> Self {
+        self.include_abstract = true;
+        self
+    }
+
+    /// Keep title words with inner capitals (`DNA`, `iPhone`) unbraced.
+    pub fn unprotected_titles(mut self) -> Self {
+        self.protect_titles = false;
+        self
+    }
+}
+
+impl fmt::Debug for BibtexOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BibtexOptions")
+            .field("dialect", &self.dialect)
+            .field("citation_key", &self.citation_key.is_some())
+            .field("key_generator", &self.key_generator)
+            .field("include_abstract", &self.include_abstract)
+            .field("protect_titles", &self.protect_titles)
+            .finish()
+    }
+}
+
+/// The generator behind [`default_citation_key`].
+static DEFAULT_KEYS: LazyLock<CitationKeyGenerator> = LazyLock::new(|| {
+    CitationKeyGenerator::with_pattern("{auth:lower}{year}")
+        .expect("default pattern is valid")
+        .preserve_existing()
+});
+
+/// Return the item's `citation-key`, or the first author's family name and year
+/// folded to ASCII (e.g. `godel1931`), as the `{auth:lower}{year}` key pattern.
+pub fn default_citation_key(metadata: &DoiMetadata) -> String {
+    DEFAULT_KEYS.key(metadata)
+}
+
+/// Write items as a `.bib` file, one blank line between entries, suffixing
+/// keys that would otherwise be shared.
+pub fn write_bibtex<'a>(
+    items: impl IntoIterator<Item = &'a DoiMetadata>,
+    options: &BibtexOptions,
+) -> String {
+    let items: Vec<&DoiMetadata> = items.into_iter().collect();
+    let keys = match &options.citation_key {
+        Some(key) => unique_keys(items.iter().map(|item| (key(item), false)).collect()),
+        None => options
+            .key_generator
+            .as_ref()
+            .unwrap_or(&DEFAULT_KEYS)
+            .keys(items.iter().copied()),
+    };
+    let entries: Vec<String> = items
+        .iter()
+        .zip(keys)
+        .map(|(item, key)| {
+            let mut entry = BibEntry::from_metadata(item, options);
+            entry.key = key;
+            entry.to_string()
+        })
+        .collect();
+    let mut out = entries.join("\n\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+impl BibEntry {
+    /// Convert CSL metadata into a BibTeX or BibLaTeX entry.
+    pub fn from_metadata(metadata: &DoiMetadata, options: &BibtexOptions) -> Self {
+        let biblatex = options.dialect == BibDialect::Biblatex;
+        let key = match (&options.citation_key, &options.key_generator) {
+            (Some(key), _) => key(metadata),
+            (None, Some(generator)) => generator.key(metadata),
+            (None, None) => default_citation_key(metadata),
+        };
+        let (entry_type, subtype) = entry_type(metadata, options.dialect);
+        let mut fields = Fields::default();
+
+        fields.names("author", &metadata.author);
+        fields.names("editor", &metadata.editor);
+        fields.names("translator", &metadata.translator);
+        if let Some(title) = &metadata.title {
+            fields.raw("title", &title_value(title, options.protect_titles));
+        }
+        if let Some(container) = &metadata.container_title {
+            let container = title_value(container, options.protect_titles);
+            let name = match entry_type {
+                "article" if biblatex => "journaltitle",
+                "article" => "journal",
+                "online" | "misc" | "dataset" | "software" | "report" | "techreport" => {
+                    "howpublished"
+                }
+                _ => "booktitle",
+            };
+            if !(biblatex && name == "howpublished") {
+                fields.raw(name, &container);
+            }
+        }
+        if biblatex && let Some(short) = &metadata.container_title_short {
+            fields.raw("shortjournal", &escape(short));
+        }
+        fields.date(metadata.issued.as_ref(), options.dialect);
+        fields.value("volume", metadata.volume.as_ref());
+        fields.value(
+            "number",
+            metadata.issue.as_ref().or(metadata.number.as_ref()),
+        );
+        if let Some(pages) = &metadata.page {
+            let pages = PAGE_RANGE
+                .replace_all(&pages.to_string(), "--")
+                .into_owned();
+            fields.raw("pages", &escape(&pages));
+        }
+        fields.value("edition", metadata.edition.as_ref());
+        if let Some(series) = &metadata.collection_title {
+            fields.text("series", series);
+        }
+        if let Some(publisher) = &metadata.publisher {
+            let name = match entry_type {
+                "techreport" | "report" => "institution",
+                "phdthesis" | "mastersthesis" if !biblatex => "school",
+                "thesis" => "institution",
+                _ => "publisher",
+            };
+            fields.text(name, publisher);
+        }
+        if let Some(place) = &metadata.publisher_place {
+            fields.text(if biblatex { "location" } else { "address" }, place);
+        }
+        if let Some(subtype) = subtype {
+            fields.raw("type", subtype);
+        } else if let Some(genre) = &metadata.genre
+            && matches!(entry_type, "techreport" | "report" | "thesis")
+        {
+            fields.text("type", genre);
+        }
+        if let Some(isbn) = metadata.isbn.first() {
+            fields.raw("isbn", isbn);
+        }
+        if let Some(issn) = metadata.issn.first() {
+            fields.raw("issn", issn);
+        }
+        // DOIs and URLs are verbatim in both dialects and must not be escaped.
+        if let Some(doi) = &metadata.doi {
+            fields.raw("doi", doi);
+        }
+        if let Some(url) = &metadata.url {
+            fields.raw("url", url);
+        }
+        if biblatex && let Some(accessed) = metadata.accessed.as_ref().and_then(CslDate::start) {
+            fields.raw("urldate", &accessed.to_string());
+        }
+        if let Some(language) = &metadata.language {
+            fields.text(if biblatex { "langid" } else { "language" }, language);
+        }
+        if options.include_abstract
+            && let Some(text) = &metadata.abstract_text
+        {
+            fields.text("abstract", text);
+        }
+        if let Some(keyword) = &metadata.keyword {
+            fields.text("keywords", keyword);
+        }
+        if let Some(note) = &metadata.note {
+            fields.text("note", note);
+        }
+
+        BibEntry {
+            entry_type: entry_type.to_string(),
+            key,
+            fields: fields.0,
+        }
+    }
+}
+
+/// Map a CSL item type to an entry type plus an optional `type` field.
+fn entry_type(metadata: &DoiMetadata, dialect: BibDialect) -> (&'static str, Option<&'static str>) {
+    let biblatex = dialect == BibDialect::Biblatex;
+    let masters = metadata
+        .genre
+        .as_deref()
+        .is_some_and(|genre| genre.to_lowercase().contains("master"));
+    match metadata.item_type {
+        CslItemType::Article
+        | CslItemType::ArticleJournal
+        | CslItemType::ArticleMagazine
+        | CslItemType::ArticleNewspaper
+        | CslItemType::Review
+        | CslItemType::ReviewBook => ("article", None),
+        CslItemType::Book | CslItemType::Classic => ("book", None),
+        CslItemType::Collection if biblatex => ("collection", None),
+        CslItemType::Collection => ("book", None),
+        CslItemType::Chapter => ("incollection", None),
+        CslItemType::EntryDictionary | CslItemType::EntryEncyclopedia | CslItemType::Entry
+            if biblatex =>
+        {
+            ("inreference", None)
+        }
+        CslItemType::EntryDictionary | CslItemType::EntryEncyclopedia | CslItemType::Entry => {
+            ("incollection", None)
+        }
+        CslItemType::PaperConference => ("inproceedings", None),
+        CslItemType::Event if biblatex => ("proceedings", None),
+        CslItemType::Report if biblatex => ("report", Some("techreport")),
+        CslItemType::Report => ("techreport", None),
+        CslItemType::Thesis if biblatex && masters => ("thesis", Some("mathesis")),
+        CslItemType::Thesis if biblatex => ("thesis", Some("phdthesis")),
+        CslItemType::Thesis if masters => ("mastersthesis", None),
+        CslItemType::Thesis => ("phdthesis", None),
+        CslItemType::Manuscript => ("unpublished", None),
+        CslItemType::Pamphlet => ("booklet", None),
+        CslItemType::Periodical if biblatex => ("periodical", None),
+        CslItemType::Patent if biblatex => ("patent", None),
+        CslItemType::Dataset if biblatex => ("dataset", None),
+        CslItemType::Software if biblatex => ("software", None),
+        CslItemType::Webpage | CslItemType::Post | CslItemType::PostWeblog if biblatex => {
+            ("online", None)
+        }
+        _ => ("misc", None),
+    }
+}
+
+/// Convert a title's rich text to LaTeX, bracing words with inner capitals
+/// so styles keep their case.
+fn title_value(title: &str, protect: bool) -> String {
+    latex_nodes(&rich_text(title), protect)
+}
+
+fn latex_nodes(nodes: &[Node], protect: bool) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(text) if protect => text
+                .split(' ')
+                .map(|word| {
+                    let escaped = escape(word);
+                    if word.chars().skip(1).any(char::is_uppercase) {
+                        format!("{{{escaped}}}")
+                    } else {
+                        escaped
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            Node::Text(text) => escape(text),
+            Node::Markup(Markup::NoCase, children) => {
+                format!("{{{}}}", latex_nodes(children, false))
+            }
+            Node::Markup(markup, children) => {
+                let command = match markup {
+                    Markup::Italic => "textit",
+                    Markup::Bold => "textbf",
+                    Markup::Superscript => "textsuperscript",
+                    Markup::Subscript => "textsubscript",
+                    Markup::NoCase => unreachable!(),
+                };
+                format!("\\{command}{{{}}}", latex_nodes(children, protect))
+            }
+            Node::Styled(_, children) | Node::Display(_, children) => {
+                latex_nodes(children, protect)
+            }
+        })
+        .collect()
+}
+
+/// Format one name as `von Last, Jr, First`, bracing institutions.
+fn format_name(name: &CslName) -> Option<String> {
+    let Some(family) = name.family.as_deref().filter(|family| !family.is_empty()) else {
+        let literal = name.literal.as_deref().or(name.given.as_deref())?;
+        return Some(format!("{{{}}}", escape(literal)));
+    };
+    let last: Vec<&str> = [
+        name.dropping_particle.as_deref(),
+        name.non_dropping_particle.as_deref(),
+        Some(family),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let mut parts = vec![escape(&last.join(" "))];
+    if let Some(suffix) = name.suffix.as_deref() {
+        parts.push(escape(suffix));
+    }
+    if let Some(given) = name.given.as_deref() {
+        parts.push(escape(given));
+    }
+    Some(parts.join(", "))
+}
+
+/// Fields collected in output order.
+#[derive(Default)]
+struct Fields(Vec<(String, BibValue)>);
+
+impl Fields {
+    /// Add an already-escaped braced value.
+    fn raw(&mut self, name: &str, value: &str) {
+        if !value.trim().is_empty() {
+            self.0
+                .push((name.to_string(), BibValue::Braced(value.trim().to_string())));
+        }
+    }
+
+    /// Add a plain text value.
+    fn text(&mut self, name: &str, value: &str) {
+        self.raw(name, &escape(value));
+    }
+
+    fn value(&mut self, name: &str, value: Option<&CslValue>) {
+        match value {
+            Some(CslValue::Number(number)) => self
+                .0
+                .push((name.to_string(), BibValue::Bare(number.to_string()))),
+            Some(value) => self.raw(name, &escape(&value.to_string())),
+            None => {}
+        }
+    }
+
+    fn names(&mut self, name: &str, names: &[CslName]) {
+        let names: Vec<String> = names.iter().filter_map(format_name).collect();
+        if !names.is_empty() {
+            self.raw(name, &names.join(" and "));
+        }
+    }
+
+    fn date(&mut self, date: Option<&CslDate>, dialect: BibDialect) {
+        let Some(date) = date else {
+            return;
+        };
+        let Some(start) = date.start() else {
+            if let Some(literal) = date.literal.as_deref().or(date.raw.as_deref()) {
+                let name = match dialect {
+                    BibDialect::Bibtex => "year",
+                    BibDialect::Biblatex => "date",
+                };
+                self.raw(name, &escape(literal));
+            }
+            return;
+        };
+        match dialect {
+            BibDialect::Biblatex => {
+                let value = match date.end().filter(|end| *end != start) {
+                    Some(end) => format!("{start}/{end}"),
+                    None => start.to_string(),
+                };
+                self.raw("date", &value);
+            }
+            BibDialect::Bibtex => {
+                self.raw("year", &start.year().to_string());
+                if let Some(month) = start.month().filter(|month| (1..=12).contains(month)) {
+                    let month = MONTHS[usize::from(month) - 1];
+                    self.0
+                        .push(("month".to_string(), BibValue::Bare(month.to_string())));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article() -> DoiMetadata {
+        serde_json::from_value(serde_json::json!({
+            "type": "article-journal",
+            "title": "DNA repair in Gödel's iPhone & 100% more",
+            "author": [
+                {"family": "Gödel", "given": "Kurt"},
+                {"family": "Beethoven", "given": "Ludwig", "non-dropping-particle": "van", "suffix": "Jr."},
+                {"literal": "World Health Organization"}
+            ],
+            "container-title": "Journal of Studies",
+            "volume": "12",
+            "issue": 3,
+            "page": "45–67",
+            "issued": {"date-parts": [[1931, 3, 15]]},
+            "DOI": "10.1000/abc_def",
+            "ISSN": ["1234-5678"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    /// Writes a BibTeX article with escaped names, protected title words and a month macro.
+    fn bibtex_article() {
+        let entry = BibEntry::from_metadata(&article(), &BibtexOptions::new());
+        assert_eq!(
+            entry.to_string(),
+            "@article{godel1931,\n\
+             \x20 author = {G{\\\"o}del, Kurt and van Beethoven, Jr., Ludwig and {World Health Organization}},\n\
+             \x20 title = {{DNA} repair in G{\\\"o}del's {iPhone} \\& 100\\% more},\n\
+             \x20 journal = {Journal of Studies},\n\
+             \x20 year = {1931},\n\
+             \x20 month = mar,\n\
+             \x20 volume = {12},\n\
+             \x20 number = 3,\n\
+             \x20 pages = {45--67},\n\
+             \x20 issn = {1234-5678},\n\
+             \x20 doi = {10.1000/abc_def},\n\
+             }"
+        );
+    }
+
+    #[test]
+    /// Maps BibLaTeX field and entry names and uses a custom key function.
+    fn biblatex_mapping() {
+        let options = BibtexOptions::new().biblatex().citation_key(|item| {
+            format!(
+                "doc{}",
+                item.volume
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            )
+        });
+        let entry = BibEntry::from_metadata(&article(), &options);
+        assert_eq!(entry.key, "doc12");
+        assert_eq!(
+            entry.field("journaltitle"),
+            Some(&BibValue::Braced("Journal of Studies".to_string()))
+        );
+        assert_eq!(
+            entry.field("date"),
+            Some(&BibValue::Braced("1931-03-15".to_string()))
+        );
+        assert!(entry.field("month").is_none());
+
+        let mut thesis = DoiMetadata {
+            item_type: CslItemType::Thesis,
+            publisher: Some("MIT".to_string()),
+            ..Default::default()
+        };
+        let entry = BibEntry::from_metadata(&thesis, &options);
+        assert_eq!(entry.entry_type, "thesis");
+        assert_eq!(
+            entry.field("type"),
+            Some(&BibValue::Braced("phdthesis".to_string()))
+        );
+        thesis.genre = Some("Master's thesis".to_string());
+        let entry = BibEntry::from_metadata(&thesis, &BibtexOptions::new());
+        assert_eq!(entry.entry_type, "mastersthesis");
+        assert_eq!(
+            entry.field("school"),
+            Some(&BibValue::Braced("MIT".to_string()))
+        );
+    }
+
+    #[test]
+    /// Writes rich text title markup as LaTeX commands and normalizes page ranges.
+    fn rich_text_title() {
+        let metadata = DoiMetadata {
+            title: Some(
+                "CO<sub>2</sub> uptake by <i>E. coli</i> at <span class=\"nocase\">pH</span> 7"
+                    .to_string(),
+            ),
+            page: Some(CslValue::String("10 - 20".to_string())),
+            ..Default::default()
+        };
+        let entry = BibEntry::from_metadata(&metadata, &BibtexOptions::new());
+        assert_eq!(
+            entry.field("title"),
+            Some(&BibValue::Braced(
+                "{CO}\\textsubscript{2} uptake by \\textit{E. coli} at {pH} 7".to_string()
+            ))
+        );
+        assert_eq!(
+            entry.field("pages"),
+            Some(&BibValue::Braced("10--20".to_string()))
+        );
+    }
+}
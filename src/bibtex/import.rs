@@ -0,0 +1,430 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::Doi;
+use crate::bibtex::latex::unescape;
+use crate::bibtex::parse::{BibFile, BibWarning, value_text};
+use crate::bibtex::{BibEntry, BibValue};
+use crate::csl::{CslDate, CslItemType, CslName, CslValue, DateParts, DoiMetadata};
+
+/// CSL items read from a `.bib` file, with everything that needed attention.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BibImport {
+    pub items: Vec<DoiMetadata>,
+    pub warnings: Vec<BibWarning>,
+}
+
+/// Parse BibTeX or BibLaTeX source into CSL items.
+pub fn parse_bibtex(input: &str) -> BibImport {
+    let file = BibFile::parse(input);
+    let mut warnings = file.warnings.clone();
+    let mut items = Vec::with_capacity(file.entries.len());
+    for (entry, line) in file.entries.iter().zip(&file.lines) {
+        let mut messages = Vec::new();
+        items.push(entry.convert(&mut messages));
+        warnings.extend(messages.into_iter().map(|message| BibWarning {
+            line: *line,
+            key: Some(entry.key.clone()),
+            message,
+        }));
+    }
+    warnings.sort_by_key(|warning| warning.line);
+    BibImport { items, warnings }
+}
+
+impl BibEntry {
+    /// Convert the entry into a CSL item, ignoring conversion problems.
+    pub fn to_metadata(&self) -> DoiMetadata {
+        self.convert(&mut Vec::new())
+    }
+
+    fn convert(&self, warnings: &mut Vec<String>) -> DoiMetadata {
+        let (item_type, genre) = item_type(&self.entry_type);
+        if matches!(item_type, CslItemType::Document) && self.entry_type != "misc" {
+            warnings.push(format!("unknown entry type `@{}`", self.entry_type));
+        }
+        let mut metadata = DoiMetadata {
+            id: Some(self.key.clone()).filter(|key| !key.is_empty()),
+            citation_key: Some(self.key.clone()).filter(|key| !key.is_empty()),
+            item_type,
+            genre: genre.map(str::to_string),
+            ..Default::default()
+        };
+        let text = |name: &str| {
+            self.field(name)
+                .map(|value| unescape(value_text(value)))
+                .filter(|value| !value.is_empty())
+        };
+        let number = |name: &str| match self.field(name)? {
+            BibValue::Bare(value) => value.parse().ok().map(CslValue::Number),
+            BibValue::Braced(value) => Some(CslValue::String(unescape(value)))
+                .filter(|value| !value.to_string().is_empty()),
+        };
+
+        metadata.author = self.names("author");
+        metadata.editor = self.names("editor");
+        metadata.translator = self.names("translator");
+        metadata.title = text("title");
+        metadata.title_short = text("shorttitle");
+        metadata.container_title = text("journaltitle")
+            .or_else(|| text("journal"))
+            .or_else(|| text("booktitle"))
+            .or_else(|| text("maintitle"));
+        metadata.container_title_short = text("shortjournal");
+        metadata.issued = self.issued(warnings);
+        metadata.volume = number("volume");
+        match metadata.item_type {
+            CslItemType::ArticleJournal => metadata.issue = number("number").or(number("issue")),
+            _ => {
+                metadata.number = number("number");
+                metadata.issue = number("issue");
+            }
+        }
+        metadata.page = self
+            .field("pages")
+            .map(|pages| unescape(&value_text(pages).replace("--", "-")))
+            .filter(|pages| !pages.is_empty())
+            .map(CslValue::String);
+        metadata.edition = number("edition");
+        metadata.collection_title = text("series");
+        metadata.publisher = text("publisher")
+            .or_else(|| text("institution"))
+            .or_else(|| text("school"))
+            .or_else(|| text("organization"));
+        metadata.publisher_place = text("location").or_else(|| text("address"));
+        if let Some(kind) = text("type") {
+            metadata.genre = Some(match kind.to_lowercase().as_str() {
+                "phdthesis" => "PhD thesis".to_string(),
+                "mathesis" | "mastersthesis" => "Master's thesis".to_string(),
+                "techreport" => "Technical report".to_string(),
+                _ => kind,
+            });
+        }
+        metadata.isbn = text("isbn").into_iter().collect();
+        metadata.issn = text("issn").into_iter().collect();
+        // Verbatim fields are not LaTeX-decoded.
+        let verbatim = |name: &str| {
+            self.field(name)
+                .map(|value| value_text(value).trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        // Resolver URLs and `doi:` prefixes are stripped; anything that does
+        // not parse as a DOI is kept as written.
+        metadata.doi = verbatim("doi").map(|doi| match Doi::parse(&doi) {
+            Ok(parsed) => parsed.as_str().to_string(),
+            Err(_) => doi,
+        });
+        metadata.url = verbatim("url");
+        metadata.accessed = verbatim("urldate").and_then(|date| date_range(&date));
+        metadata.language = text("langid").or_else(|| text("language"));
+        metadata.abstract_text = text("abstract");
+        metadata.keyword = text("keywords");
+        metadata.note = text("note").or_else(|| text("annote"));
+
+        // Fields without a CSL variable are kept in `custom`.
+        let known = [
+            "author",
+            "editor",
+            "translator",
+            "title",
+            "shorttitle",
+            "journaltitle",
+            "journal",
+            "booktitle",
+            "maintitle",
+            "shortjournal",
+            "year",
+            "month",
+            "date",
+            "volume",
+            "number",
+            "issue",
+            "pages",
+            "edition",
+            "series",
+            "publisher",
+            "institution",
+            "school",
+            "organization",
+            "location",
+            "address",
+            "type",
+            "isbn",
+            "issn",
+            "doi",
+            "url",
+            "urldate",
+            "langid",
+            "language",
+            "abstract",
+            "keywords",
+            "note",
+            "annote",
+            "crossref",
+        ];
+        let custom: BTreeMap<String, Value> = self
+            .fields
+            .iter()
+            .filter(|(name, _)| !known.contains(&name.as_str()))
+            .map(|(name, value)| (name.clone(), Value::String(unescape(value_text(value)))))
+            .collect();
+        metadata.custom = custom;
+        metadata
+    }
+
+    /// Split a name list on `and` at brace depth zero; braced names are literal.
+    fn names(&self, field: &str) -> Vec<CslName> {
+        let Some(value) = self.field(field) else {
+            return Vec::new();
+        };
+        split_names(value_text(value))
+            .into_iter()
+            .filter(|name| !name.eq_ignore_ascii_case("others"))
+            .map(|name| {
+                let inner = name
+                    .strip_prefix('{')
+                    .and_then(|name| name.strip_suffix('}'))
+                    .filter(|inner| !inner.contains(['{', '}']) || inner.starts_with('\\'));
+                match inner {
+                    Some(literal) if !literal.starts_with('\\') => {
+                        CslName::literal(&unescape(literal))
+                    }
+                    _ => CslName::parse(&unescape(&name)),
+                }
+            })
+            .collect()
+    }
+
+    /// Read `date`, or `year` with `month`.
+    fn issued(&self, warnings: &mut Vec<String>) -> Option<CslDate> {
+        if let Some(date) = self.field("date").map(value_text) {
+            let date = unescape(date);
+            return match date_range(&date) {
+                Some(parsed) => Some(parsed),
+                None => {
+                    warnings.push(format!("unrecognized date `{date}`"));
+                    Some(CslDate::literal(&date))
+                }
+            };
+        }
+        let year = unescape(value_text(self.field("year")?));
+        let Ok(number) = year.trim().parse::<i32>() else {
+            warnings.push(format!("unrecognized year `{year}`"));
+            return Some(CslDate::literal(&year));
+        };
+        let month = self.field("month").map(|month| unescape(value_text(month)));
+        match month.as_deref().map(parse_month) {
+            Some(Some(month)) => Some(CslDate::new(DateParts::year_month(number, month))),
+            Some(None) => {
+                warnings.push(format!(
+                    "unrecognized month `{}`",
+                    month.unwrap_or_default()
+                ));
+                Some(CslDate::new(DateParts::year_only(number)))
+            }
+            None => Some(CslDate::new(DateParts::year_only(number))),
+        }
+    }
+}
+
+/// Map a BibTeX or BibLaTeX entry type to a CSL type and genre.
+fn item_type(entry_type: &str) -> (CslItemType, Option<&'static str>) {
+    match entry_type {
+        "article" => (CslItemType::ArticleJournal, None),
+        "book" | "mvbook" | "proceedings" | "mvproceedings" | "manual" | "collection"
+        | "mvcollection" => (CslItemType::Book, None),
+        "inbook" | "incollection" | "bookinbook" | "suppbook" | "suppcollection" => {
+            (CslItemType::Chapter, None)
+        }
+        "inproceedings" | "conference" => (CslItemType::PaperConference, None),
+        "inreference" => (CslItemType::EntryEncyclopedia, None),
+        "report" | "techreport" => (CslItemType::Report, None),
+        "phdthesis" => (CslItemType::Thesis, Some("PhD thesis")),
+        "mastersthesis" => (CslItemType::Thesis, Some("Master's thesis")),
+        "thesis" => (CslItemType::Thesis, None),
+        "unpublished" => (CslItemType::Manuscript, None),
+        "booklet" => (CslItemType::Pamphlet, None),
+        "periodical" => (CslItemType::Periodical, None),
+        "patent" => (CslItemType::Patent, None),
+        "dataset" => (CslItemType::Dataset, None),
+        "software" => (CslItemType::Software, None),
+        "online" | "electronic" | "www" => (CslItemType::Webpage, None),
+        _ => (CslItemType::Document, None),
+    }
+}
+
+/// Parse an ISO date or a `start/end` range.
+fn date_range(value: &str) -> Option<CslDate> {
+    match value.split_once('/') {
+        Some((start, end)) => Some(CslDate::range(
+            DateParts::parse(start)?,
+            DateParts::parse(end)?,
+        )),
+        None => DateParts::parse(value).map(CslDate::new),
+    }
+}
+
+/// Parse a month number, name or abbreviation.
+fn parse_month(value: &str) -> Option<u8> {
+    let value = value.trim().trim_end_matches('.').to_lowercase();
+    if let Ok(number) = value.parse::<u8>() {
+        return (1..=12).contains(&number).then_some(number);
+    }
+    const NAMES: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let index = NAMES
+        .iter()
+        .position(|name| value.len() >= 3 && name.starts_with(&value))?;
+    u8::try_from(index + 1).ok()
+}
+
+/// Split a BibTeX name list on ` and ` outside braces.
+fn split_names(value: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let words = value.split_whitespace();
+    for word in words {
+        if depth == 0 && word.eq_ignore_ascii_case("and") {
+            names.push(std::mem::take(&mut current));
+            continue;
+        }
+        depth += word.matches('{').count() as i64 - word.matches('}').count() as i64;
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    names.push(current);
+    names.retain(|name| !name.is_empty());
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bibtex::export::BibtexOptions;
+    use crate::bibtex::export::write_bibtex;
+
+    #[test]
+    /// Converts fields, names and dates, and reports conversion problems.
+    fn converts_entries() {
+        let import = parse_bibtex(
+            r#"@phdthesis{doe2019,
+                 author = {Doe, Jane and {Barnes and Noble} and Ludwig van Beethoven and others},
+                 title = {{\"U}ber {DNA}},
+                 school = {MIT},
+                 year = {2019}, month = {Sept.},
+                 pages = {1--10},
+                 eprint = {2101.00001},
+               }
+               @gadget{x, year = {circa 1900}, month = {Smarch}}"#,
+        );
+        let thesis = &import.items[0];
+        assert_eq!(thesis.item_type, CslItemType::Thesis);
+        assert_eq!(thesis.genre.as_deref(), Some("PhD thesis"));
+        assert_eq!(thesis.citation_key.as_deref(), Some("doe2019"));
+        assert_eq!(thesis.title.as_deref(), Some("Über DNA"));
+        assert_eq!(thesis.publisher.as_deref(), Some("MIT"));
+        assert_eq!(thesis.page, Some(CslValue::String("1-10".to_string())));
+        assert_eq!(
+            thesis.issued.as_ref().and_then(CslDate::start),
+            Some(DateParts::year_month(2019, 9))
+        );
+        assert_eq!(thesis.author.len(), 3);
+        assert_eq!(thesis.author[1], CslName::literal("Barnes and Noble"));
+        assert_eq!(thesis.author[2].family.as_deref(), Some("Beethoven"));
+        assert_eq!(
+            thesis.author[2].non_dropping_particle.as_deref(),
+            Some("van")
+        );
+        assert_eq!(
+            thesis.custom.get("eprint"),
+            Some(&Value::from("2101.00001"))
+        );
+
+        let messages: Vec<&str> = import.warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "unknown entry type `@gadget`",
+                "unrecognized year `circa 1900`"
+            ]
+        );
+    }
+
+    #[test]
+    /// Exported entries read back into the same metadata.
+    fn round_trips_with_exporter() {
+        let original: DoiMetadata = serde_json::from_value(serde_json::json!({
+            "type": "article-journal",
+            "citation-key": "godel1931",
+            "title": "Über formal unentscheidbare Sätze & {braces} in DNA_50%",
+            "author": [
+                {"family": "Gödel", "given": "Kurt"},
+                {"family": "Beethoven", "given": "Ludwig", "non-dropping-particle": "van", "suffix": "Jr."},
+                {"literal": "World Health Organization"}
+            ],
+            "container-title": "Monatshefte für Mathematik",
+            "volume": "38",
+            "issue": 1,
+            "page": "173-198",
+            "issued": {"date-parts": [[1931, 12]]},
+            "DOI": "10.1007/bf01700692",
+            "publisher": "Springer"
+        }))
+        .unwrap();
+        for options in [BibtexOptions::new(), BibtexOptions::new().biblatex()] {
+            let bib = write_bibtex([&original], &options);
+            let import = parse_bibtex(&bib);
+            assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+            let item = &import.items[0];
+            assert_eq!(item.citation_key, original.citation_key);
+            assert_eq!(item.item_type, original.item_type);
+            assert_eq!(item.title, original.title, "{bib}");
+            assert_eq!(item.author, original.author);
+            assert_eq!(item.container_title, original.container_title);
+            assert_eq!(item.volume, original.volume);
+            assert_eq!(
+                item.issue.as_ref().map(ToString::to_string),
+                Some("1".to_string())
+            );
+            assert_eq!(item.page, original.page);
+            assert_eq!(item.issued, original.issued);
+            assert_eq!(item.doi, original.doi);
+            assert_eq!(item.publisher, original.publisher);
+        }
+    }
+
+    #[test]
+    /// Strips resolver prefixes from `doi` and keeps unparseable values.
+    fn cleans_dois() {
+        let doi = |value: &str| {
+            let import = parse_bibtex(&format!("@article{{a, doi = {{{value}}}}}"));
+            import.items[0].doi.clone()
+        };
+        assert_eq!(
+            doi("https://dx.doi.org/10.1000/ABC").as_deref(),
+            Some("10.1000/ABC")
+        );
+        assert_eq!(
+            doi("http://doi.org/10.1000/abc").as_deref(),
+            Some("10.1000/abc")
+        );
+        assert_eq!(doi("doi:10.1000/abc").as_deref(), Some("10.1000/abc"));
+        assert_eq!(doi("pending").as_deref(), Some("pending"));
+    }
+}
@@ -0,0 +1,518 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::bibtex::error::{BibtexError, KeyPatternSnafu};
+use crate::bibtex::latex::ascii_fold;
+use crate::citeproc::output::{plain, rich_text};
+use crate::csl::{CslDate, CslName, DoiMetadata};
+
+/// Pattern used by [`CitationKeyGenerator::new`].
+pub const DEFAULT_KEY_PATTERN: &str = "{auth:lower}{year}{shorttitle}";
+
+/// Words skipped when building title parts of a key.
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "by", "for", "from", "in", "into", "is", "of",
+    "on", "or", "over", "the", "through", "to", "towards", "under", "upon", "via", "with",
+    "without",
+];
+
+/// Characters besides ASCII letters and digits allowed in a key.
+const KEY_PUNCTUATION: &[char] = &['-', '_', ':', '.', '+', '/'];
+
+/// A variable in a key pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// First author's family name.
+    Auth,
+    /// Every author's family name.
+    Authors,
+    /// One or two family names, or the first followed by `EtAl`.
+    AuthEtAl,
+    Year,
+    /// The last two digits of the year.
+    ShortYear,
+    /// Every significant title word.
+    Title,
+    /// The first few significant title words.
+    ShortTitle,
+    /// The first significant title word.
+    VeryShortTitle,
+    /// The journal abbreviation, or the initials of the container title.
+    Journal,
+    Volume,
+    /// The first page.
+    Page,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "auth" => Field::Auth,
+            "authors" => Field::Authors,
+            "authEtAl" => Field::AuthEtAl,
+            "year" => Field::Year,
+            "shortyear" => Field::ShortYear,
+            "title" => Field::Title,
+            "shorttitle" => Field::ShortTitle,
+            "veryshorttitle" => Field::VeryShortTitle,
+            "journal" => Field::Journal,
+            "volume" => Field::Volume,
+            "page" => Field::Page,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Keep,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field(Field, Case),
+}
+
+/// Builds citation keys such as `smith2020DeepLearningProteins` from a pattern.
+///
+/// Patterns mix literal text with `{field}` or `{field:lower}`/`{field:upper}`
+/// placeholders; fields are `auth`, `authors`, `authEtAl`, `year`, `shortyear`,
+/// `title`, `shorttitle`, `veryshorttitle`, `journal`, `volume` and `page`.
+/// Names and titles are folded to ASCII and title parts skip stop words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CitationKeyGenerator {
+    parts: Vec<Part>,
+    stop_words: HashSet<String>,
+    title_words: usize,
+    preserve_existing: bool,
+}
+
+impl Default for CitationKeyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CitationKeyGenerator {
+    /// A generator for [`DEFAULT_KEY_PATTERN`] with English stop words.
+    pub fn new() -> Self {
+        Self::with_pattern(DEFAULT_KEY_PATTERN).expect("default pattern is valid")
+    }
+
+    /// A generator for a custom pattern such as `{authEtAl}{shortyear}`.
+    pub fn with_pattern(pattern: &str) -> Result<Self, BibtexError> {
+        Ok(Self {
+            parts: parse_pattern(pattern)?,
+            stop_words: STOP_WORDS.iter().map(|word| word.to_string()).collect(),
+            title_words: 3,
+            preserve_existing: false,
+        })
+    }
+
+    /// Replace the words skipped in title parts; matching ignores case.
+    pub fn stop_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.stop_words = words
+            .into_iter()
+            .map(|word| word.as_ref().to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Set how many words `{shorttitle}` keeps (default 3).
+    pub fn title_words(mut self, count: usize) -> Self {
+        self.title_words = count.max(1);
+        self
+    }
+
+    /// Keep an item's own `citation-key` instead of generating one.
+    pub fn preserve_existing(mut self) -> Self {
+        self.preserve_existing = true;
+        self
+    }
+
+    /// Build the key for one item, without collision suffixes.
+    pub fn key(&self, metadata: &DoiMetadata) -> String {
+        if let Some(key) = self.preserved(metadata) {
+            return key.to_string();
+        }
+        let mut key = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => key.push_str(text),
+                Part::Field(field, case) => {
+                    let value = self.field(metadata, *field);
+                    key.push_str(&match case {
+                        Case::Keep => value,
+                        Case::Lower => value.to_lowercase(),
+                        Case::Upper => value.to_uppercase(),
+                    });
+                }
+            }
+        }
+        if key.is_empty() {
+            "item".to_string()
+        } else {
+            key
+        }
+    }
+
+    /// Build keys for a set of items, in order, appending `a`, `b`, … to every
+    /// generated key that would otherwise be shared. Preserved keys are never
+    /// suffixed.
+    pub fn keys<'a>(&self, items: impl IntoIterator<Item = &'a DoiMetadata>) -> Vec<String> {
+        unique_keys(
+            items
+                .into_iter()
+                .map(|item| (self.key(item), self.preserved(item).is_some()))
+                .collect(),
+        )
+    }
+
+    /// The item's own `citation-key`, when the generator keeps it.
+    fn preserved<'a>(&self, metadata: &'a DoiMetadata) -> Option<&'a str> {
+        metadata
+            .citation_key
+            .as_deref()
+            .filter(|key| self.preserve_existing && !key.is_empty())
+    }
+
+    fn field(&self, metadata: &DoiMetadata, field: Field) -> String {
+        let names = if metadata.author.is_empty() {
+            &metadata.editor
+        } else {
+            &metadata.author
+        };
+        let year = || metadata.issued.as_ref().and_then(CslDate::year);
+        match field {
+            Field::Auth => names
+                .first()
+                .map(|name| self.name_part(name))
+                .unwrap_or_default(),
+            Field::Authors => names.iter().map(|name| self.name_part(name)).collect(),
+            Field::AuthEtAl => match names.as_slice() {
+                [] => String::new(),
+                [first] => self.name_part(first),
+                [first, second] => self.name_part(first) + &self.name_part(second),
+                [first, ..] => self.name_part(first) + "EtAl",
+            },
+            Field::Year => year().map(|year| year.to_string()).unwrap_or_default(),
+            Field::ShortYear => year()
+                .map(|year| format!("{:02}", year.rem_euclid(100)))
+                .unwrap_or_default(),
+            Field::Title => self.title_part(metadata, usize::MAX),
+            Field::ShortTitle => self.title_part(metadata, self.title_words),
+            Field::VeryShortTitle => self.title_part(metadata, 1),
+            Field::Journal => {
+                if let Some(short) = metadata
+                    .container_title_short
+                    .as_deref()
+                    .or(metadata.journal_abbreviation.as_deref())
+                {
+                    words(short).map(capitalize).collect()
+                } else {
+                    metadata
+                        .container_title
+                        .as_deref()
+                        .map(|title| {
+                            self.significant_words(title)
+                                .filter_map(|word| word.chars().next())
+                                .map(|initial| initial.to_ascii_uppercase())
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                }
+            }
+            Field::Volume => metadata
+                .volume
+                .as_ref()
+                .map(|volume| sanitize(&volume.to_string()))
+                .unwrap_or_default(),
+            Field::Page => metadata
+                .page
+                .as_ref()
+                .map(|page| {
+                    let page = page.to_string();
+                    let first = page.split(['-', '–', ',']).next().unwrap_or_default();
+                    sanitize(first)
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    fn title_part(&self, metadata: &DoiMetadata, count: usize) -> String {
+        metadata
+            .title
+            .as_deref()
+            .map(|title| {
+                self.significant_words(title)
+                    .take(count)
+                    .map(capitalize)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The family name of a person, or the first significant word of an organization.
+    fn name_part(&self, name: &CslName) -> String {
+        match name.family.as_deref().filter(|family| !family.is_empty()) {
+            Some(family) => words(family).map(capitalize).collect(),
+            None => name
+                .literal
+                .as_deref()
+                .or(name.given.as_deref())
+                .and_then(|literal| self.significant_words(literal).next())
+                .map(capitalize)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn significant_words<'a>(&'a self, text: &'a str) -> impl Iterator<Item = String> + 'a {
+        words(text).filter(|word| !self.stop_words.contains(&word.to_lowercase()))
+    }
+}
+
+/// Split text into ASCII words, dropping rich text markup such as `<i>`.
+fn words(text: &str) -> impl Iterator<Item = String> {
+    ascii_fold(&plain(&rich_text(text)))
+        .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '\'')
+        .map(|word| word.replace('\'', ""))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+fn capitalize(word: String) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => word,
+    }
+}
+
+/// Keep only characters that are safe in a key.
+fn sanitize(text: &str) -> String {
+    ascii_fold(text)
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric() || KEY_PUNCTUATION.contains(ch))
+        .collect()
+}
+
+/// Make keys unique by appending `a`, `b`, … to every key that would otherwise
+/// be shared. Keys flagged as fixed are reserved first and kept as they are;
+/// only the first of several identical fixed keys is.
+pub(crate) fn unique_keys(keys: Vec<(String, bool)>) -> Vec<String> {
+    let mut used: HashSet<String> = HashSet::new();
+    let fixed: Vec<bool> = keys
+        .iter()
+        .map(|(key, fixed)| *fixed && used.insert(key.clone()))
+        .collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (index, (key, _)) in keys.iter().enumerate() {
+        if !fixed[index] {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+    // Fixed keys and generated keys that are unique and not reserved stay as they are.
+    let keep: Vec<bool> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, (key, _))| fixed[index] || (counts[key.as_str()] == 1 && !used.contains(key)))
+        .collect();
+    for (index, (key, _)) in keys.iter().enumerate() {
+        if keep[index] {
+            used.insert(key.clone());
+        }
+    }
+    let mut next: HashMap<&str, usize> = HashMap::new();
+    keys.iter()
+        .enumerate()
+        .map(|(index, (key, _))| {
+            if keep[index] {
+                return key.clone();
+            }
+            let index = next.entry(key).or_default();
+            loop {
+                let candidate = format!("{key}{}", suffix(*index));
+                *index += 1;
+                if used.insert(candidate.clone()) {
+                    return candidate;
+                }
+            }
+        })
+        .collect()
+}
+
+/// The collision suffix for an index: `a`…`z`, then `aa`, `ab`, ….
+fn suffix(mut index: usize) -> String {
+    let mut out = Vec::new();
+    loop {
+        out.push(b'a' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Part>, BibtexError> {
+    let invalid = |message: String| {
+        KeyPatternSnafu {
+            pattern: pattern.to_string(),
+            message,
+        }
+        .build()
+    };
+    let mut parts = Vec::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('{') {
+            let end = after
+                .find('}')
+                .ok_or_else(|| invalid("unclosed `{`".to_string()))?;
+            let mut pieces = after[..end].split(':');
+            let name = pieces.next().unwrap_or_default().trim();
+            let field =
+                Field::parse(name).ok_or_else(|| invalid(format!("unknown field `{name}`")))?;
+            let mut case = Case::Keep;
+            for modifier in pieces {
+                case = match modifier.trim() {
+                    "lower" => Case::Lower,
+                    "upper" => Case::Upper,
+                    other => return Err(invalid(format!("unknown modifier `{other}`"))),
+                };
+            }
+            parts.push(Part::Field(field, case));
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find('{').unwrap_or(rest.len());
+            let text = &rest[..end];
+            if let Some(bad) = text
+                .chars()
+                .find(|ch| !ch.is_ascii_alphanumeric() && !KEY_PUNCTUATION.contains(ch))
+            {
+                return Err(invalid(format!("`{bad}` is not allowed in keys")));
+            }
+            parts.push(Part::Text(text.to_string()));
+            rest = &rest[end..];
+        }
+    }
+    if parts.is_empty() {
+        return Err(invalid("pattern is empty".to_string()));
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bibtex::export::{BibtexOptions, write_bibtex};
+    use crate::csl::{CslValue, DateParts};
+
+    fn item(family: &str, year: i32, title: &str) -> DoiMetadata {
+        DoiMetadata {
+            author: vec![CslName::new(family, "A.")],
+            title: Some(title.to_string()),
+            issued: Some(CslDate::new(DateParts::year_only(year))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    /// Expands fields with transliteration, stop words and case modifiers.
+    fn expands_patterns() {
+        let mut paper = item(
+            "Gödel",
+            1931,
+            "On <i>formally</i> undecidable propositions of Principia Mathematica",
+        );
+        paper.author.push(CslName::new("Łukasiewicz", "J."));
+        paper.author.push(CslName::literal("The Vienna Circle"));
+        paper.container_title = Some("Monatshefte für Mathematik und Physik".to_string());
+        paper.volume = Some(CslValue::Number(38));
+        paper.page = Some(CslValue::String("173–198".to_string()));
+
+        let key = |pattern: &str| {
+            CitationKeyGenerator::with_pattern(pattern)
+                .unwrap()
+                .key(&paper)
+        };
+        assert_eq!(
+            CitationKeyGenerator::new().key(&paper),
+            "godel1931FormallyUndecidablePropositions"
+        );
+        assert_eq!(key("{authEtAl}{shortyear}"), "GodelEtAl31");
+        assert_eq!(key("{authors:lower}"), "godellukasiewiczvienna");
+        assert_eq!(key("{auth:upper}_{veryshorttitle}"), "GODEL_Formally");
+        assert_eq!(key("{journal}{volume}:{page}"), "MFMUP38:173");
+        assert_eq!(
+            CitationKeyGenerator::new().key(&item("Smith", 2020, "CO<sub>2</sub> capture")),
+            "smith2020CO2Capture"
+        );
+        assert_eq!(
+            CitationKeyGenerator::with_pattern("{shorttitle}")
+                .unwrap()
+                .stop_words(["formally", "undecidable"])
+                .title_words(2)
+                .key(&paper),
+            "OnPropositions"
+        );
+
+        assert!(CitationKeyGenerator::with_pattern("{auth}{bogus}").is_err());
+        assert!(CitationKeyGenerator::with_pattern("{auth:title}").is_err());
+        assert!(CitationKeyGenerator::with_pattern("{auth} {year}").is_err());
+        assert!(CitationKeyGenerator::with_pattern("{auth").is_err());
+    }
+
+    #[test]
+    /// Suffixes colliding generated keys around preserved ones and skips suffixes already taken.
+    fn resolves_collisions() {
+        let items = [
+            item("Smith", 2020, "Alpha"),
+            item("Jones", 2019, "Beta"),
+            item("Smith", 2020, "Gamma"),
+            DoiMetadata {
+                citation_key: Some("smith2020a".to_string()),
+                ..Default::default()
+            },
+            item("Smith", 2020, "Delta"),
+            DoiMetadata {
+                citation_key: Some("jones2019".to_string()),
+                ..Default::default()
+            },
+        ];
+        let generator = CitationKeyGenerator::with_pattern("{auth:lower}{year}")
+            .unwrap()
+            .preserve_existing();
+        assert_eq!(
+            generator.keys(&items),
+            [
+                "smith2020b",
+                "jones2019a",
+                "smith2020c",
+                "smith2020a",
+                "smith2020d",
+                "jones2019"
+            ]
+        );
+        let bib = write_bibtex(&items[..3], &BibtexOptions::new().key_generator(generator));
+        assert!(bib.contains("{smith2020a,\n") && bib.contains("{smith2020b,\n"));
+        let bib = write_bibtex(&items[..3], &BibtexOptions::new());
+        assert!(bib.contains("{smith2020a,\n") && bib.contains("{jones2019,\n"));
+        let bib = write_bibtex(
+            &items[..2],
+            &BibtexOptions::new().citation_key(|_| "same".to_string()),
+        );
+        assert!(bib.contains("{samea,\n") && bib.contains("{sameb,\n"));
+        assert_eq!(suffix(25), "z");
+        assert_eq!(suffix(26), "aa");
+        assert_eq!(suffix(27), "ab");
+    }
+}
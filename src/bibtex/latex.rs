@@ -0,0 +1,320 @@
+//! LaTeX escaping and ASCII folding for BibTeX values.
+
+/// Accent commands with the precomposed characters they produce and their base letters.
+const ACCENTS: &[(&str, &str, &str)] = &[
+    ("`", "ÀÈÌÒÙàèìòùẀẁỲỳ", "AEIOUaeiouWwYy"),
+    (
+        "'",
+        "ÁÉÍÓÚÝáéíóúýĆćĹĺŃńŔŕŚśŹźḰḱḾḿṔṕẂẃ",
+        "AEIOUYaeiouyCcLlNnRrSsZzKkMmPpWw",
+    ),
+    (
+        "^",
+        "ÂÊÎÔÛâêîôûĈĉĜĝĤĥĴĵŜŝŴŵŶŷẐẑ",
+        "AEIOUaeiouCcGgHhJjSsWwYyZz",
+    ),
+    ("~", "ÃÑÕãñõĨĩŨũṼṽẼẽỸỹ", "ANOanoIiUuVvEeYy"),
+    ("=", "ĀāĒēĪīŌōŪūḠḡ", "AaEeIiOoUuGg"),
+    ("u", "ĂăĔĕĞğĬĭŎŏŬŭ", "AaEeGgIiOoUu"),
+    (
+        ".",
+        "ĊċĖėĠġİŻżḂḃḊḋḞḟḢḣṀṁṄṅṖṗṘṙṠṡṪṫẆẇẊẋẎẏ",
+        "CcEeGgIZzBbDdFfHhMmNnPpRrSsTtWwXxYy",
+    ),
+    ("\"", "ÄËÏÖÜäëïöüÿŸḦḧẄẅẌẍẗ", "AEIOUaeiouyYHhWwXxt"),
+    ("r", "ÅåŮůẘẙ", "AaUuwy"),
+    ("H", "ŐőŰű", "OoUu"),
+    ("v", "ČčĎďĚěĽľŇňŘřŠšŤťŽž", "CcDdEeLlNnRrSsTtZz"),
+    ("c", "ÇçĢģĶķĻļŅņŖŗŞşŢţḐḑḨḩ", "CcGgKkLlNnRrSsTtDdHh"),
+    ("k", "ĄąĘęĮįŲų", "AaEeIiUu"),
+    (
+        "d",
+        "ḄḅḌḍḤḥḲḳḶḷṂṃṆṇṚṛṢṣṬṭṾṿẈẉẒẓẠạẸẹỊịỌọỤụỴỵ",
+        "BbDdHhKkLlMmNnRrSsTtVvWwZzAaEeIiOoUuYy",
+    ),
+];
+
+/// Characters written as a LaTeX command without an argument.
+const SYMBOLS: &[(char, &str, &str)] = &[
+    ('ß', "ss", "ss"),
+    ('æ', "ae", "ae"),
+    ('Æ', "AE", "AE"),
+    ('œ', "oe", "oe"),
+    ('Œ', "OE", "OE"),
+    ('ø', "o", "o"),
+    ('Ø', "O", "O"),
+    ('ł', "l", "l"),
+    ('Ł', "L", "L"),
+    ('ı', "i", "i"),
+    ('ð', "dh", "d"),
+    ('Ð', "DH", "D"),
+    ('þ', "th", "th"),
+    ('Þ', "TH", "TH"),
+    // Written as `{\r{a}}`, but read in this common spelling too.
+    ('å', "aa", "a"),
+    ('Å', "AA", "A"),
+];
+
+/// Punctuation with a conventional LaTeX spelling.
+const PUNCTUATION: &[(char, &str, &str)] = &[
+    ('–', "--", "-"),
+    ('—', "---", "-"),
+    ('‘', "`", "'"),
+    ('’', "'", "'"),
+    ('“', "``", "\""),
+    ('”', "''", "\""),
+    ('\u{a0}', "~", " "),
+    ('…', "\\ldots{}", "..."),
+];
+
+/// Return the accent command and base letter of a precomposed character.
+fn accent(ch: char) -> Option<(&'static str, char)> {
+    ACCENTS.iter().find_map(|(command, composed, bases)| {
+        let index = composed.chars().position(|composed| composed == ch)?;
+        Some((*command, bases.chars().nth(index)?))
+    })
+}
+
+/// Escape text for a braced BibTeX value: special characters become
+/// commands and accented letters become `{\"o}`-style groups.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '{' | '}' | '$' | '&' | '%' | '#' | '_' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            _ if ch.is_ascii() => out.push(ch),
+            _ => {
+                if let Some((command, base)) = accent(ch) {
+                    // Letter commands need an argument group; symbol commands do not.
+                    if command.chars().all(char::is_alphabetic) {
+                        out.push_str(&format!("{{\\{command}{{{base}}}}}"));
+                    } else {
+                        out.push_str(&format!("{{\\{command}{base}}}"));
+                    }
+                } else if let Some((_, command, _)) = SYMBOLS.iter().find(|(c, ..)| *c == ch) {
+                    out.push_str(&format!("{{\\{command}}}"));
+                } else if let Some((_, latex, _)) = PUNCTUATION.iter().find(|(c, ..)| *c == ch) {
+                    out.push_str(latex);
+                } else {
+                    out.push(ch);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Return the precomposed character for an accent command and base letter.
+fn compose(command: &str, base: char) -> Option<char> {
+    ACCENTS.iter().find_map(|(name, composed, bases)| {
+        if *name != command {
+            return None;
+        }
+        let index = bases.chars().position(|candidate| candidate == base)?;
+        composed.chars().nth(index)
+    })
+}
+
+/// Decode LaTeX markup in a BibTeX value into Unicode text.
+///
+/// Accents (`{\"o}`, `\v{c}`), symbol commands (`\ss`), escaped specials,
+/// dashes and TeX quotes are converted; protective braces, `$` and other
+/// command names (`\emph`) are dropped, keeping their arguments.
+pub fn unescape(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    while pos < chars.len() {
+        let ch = chars[pos];
+        pos += 1;
+        match ch {
+            '\\' => pos = command(&chars, pos, &mut out),
+            '{' | '}' | '$' => {}
+            '~' => out.push(' '),
+            '-' if chars.get(pos) == Some(&'-') => {
+                if chars.get(pos + 1) == Some(&'-') {
+                    out.push('—');
+                    pos += 2;
+                } else {
+                    out.push('–');
+                    pos += 1;
+                }
+            }
+            '`' if chars.get(pos) == Some(&'`') => {
+                out.push('“');
+                pos += 1;
+            }
+            '`' => out.push('‘'),
+            '\'' if chars.get(pos) == Some(&'\'') => {
+                out.push('”');
+                pos += 1;
+            }
+            _ if ch.is_whitespace() => {
+                if !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            }
+            _ => out.push(ch),
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Decode the command starting after a backslash and return the next position.
+fn command(chars: &[char], mut pos: usize, out: &mut String) -> usize {
+    let Some(&first) = chars.get(pos) else {
+        return pos;
+    };
+    let name: String = if first.is_ascii_alphabetic() {
+        chars[pos..]
+            .iter()
+            .take_while(|ch| ch.is_ascii_alphabetic())
+            .collect()
+    } else {
+        first.to_string()
+    };
+    pos += name.chars().count();
+
+    if ACCENTS.iter().any(|(command, ..)| *command == name) {
+        let (argument, next) = argument(chars, pos);
+        let decoded = unescape(&argument);
+        let mut letters = decoded.chars();
+        match letters.next() {
+            Some(base) => {
+                // `\'{\i}` accents a dotless i.
+                let letter = match base {
+                    'ı' => 'i',
+                    'ȷ' => 'j',
+                    other => other,
+                };
+                out.push(compose(&name, letter).unwrap_or(base));
+                out.extend(letters);
+            }
+            None => out.push_str(&name),
+        }
+        return next;
+    }
+    if let Some((symbol, ..)) = SYMBOLS.iter().find(|(_, command, _)| *command == name) {
+        out.push(*symbol);
+        return skip_terminator(chars, pos);
+    }
+    match name.as_str() {
+        "textbackslash" => out.push('\\'),
+        "textasciitilde" => out.push('~'),
+        "textasciicircum" => out.push('^'),
+        "ldots" | "dots" | "textellipsis" => out.push('…'),
+        "textendash" => out.push('–'),
+        "textemdash" => out.push('—'),
+        _ if !first.is_ascii_alphabetic() => {
+            // Escaped specials such as `\&`, and `\ ` for a space.
+            out.push(first);
+            return pos;
+        }
+        // Other commands (`\emph`, `\textit`, ...) keep only their argument.
+        _ => return pos,
+    }
+    skip_terminator(chars, pos)
+}
+
+/// Read a command argument: a braced group or a single character.
+fn argument(chars: &[char], mut pos: usize) -> (String, usize) {
+    while chars.get(pos).is_some_and(|ch| *ch == ' ') {
+        pos += 1;
+    }
+    match chars.get(pos) {
+        Some('{') => {
+            let mut depth = 0;
+            let mut argument = String::new();
+            for (offset, ch) in chars[pos..].iter().enumerate() {
+                match ch {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    return (argument[1..].to_string(), pos + offset + 1);
+                }
+                argument.push(*ch);
+            }
+            (
+                argument.get(1..).unwrap_or_default().to_string(),
+                chars.len(),
+            )
+        }
+        Some('\\') => {
+            let mut argument = String::from("\\");
+            let name: String = chars[pos + 1..]
+                .iter()
+                .take_while(|ch| ch.is_ascii_alphabetic())
+                .collect();
+            argument.push_str(&name);
+            (argument, pos + 1 + name.chars().count())
+        }
+        Some(ch) => (ch.to_string(), pos + 1),
+        None => (String::new(), pos),
+    }
+}
+
+/// Skip the `{}` or single space that ends a command without arguments.
+fn skip_terminator(chars: &[char], pos: usize) -> usize {
+    match (chars.get(pos), chars.get(pos + 1)) {
+        (Some('{'), Some('}')) => pos + 2,
+        (Some(' '), _) => pos + 1,
+        _ => pos,
+    }
+}
+
+/// Fold text to ASCII by dropping accents and spelling out ligatures;
+/// characters without an ASCII form are dropped.
+pub fn ascii_fold(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch.is_ascii() {
+            out.push(ch);
+        } else if let Some((_, base)) = accent(ch) {
+            out.push(base);
+        } else if let Some((_, _, ascii)) =
+            SYMBOLS.iter().chain(PUNCTUATION).find(|(c, ..)| *c == ch)
+        {
+            out.push_str(ascii);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Escapes specials, accents and ligatures.
+    fn escapes_latex() {
+        assert_eq!(escape("Gödel & 50% #1_a"), "G{\\\"o}del \\& 50\\% \\#1\\_a");
+        assert_eq!(escape("Čech–Straße"), "{\\v{C}}ech--Stra{\\ss}e");
+        assert_eq!(
+            escape("{x}~^\\"),
+            "\\{x\\}\\textasciitilde{}\\textasciicircum{}\\textbackslash{}"
+        );
+        assert_eq!(ascii_fold("Łukasz Żółć, Ærø"), "Lukasz Zolc, AEro");
+    }
+
+    #[test]
+    /// Decodes accents in their braced and bare forms, symbols and TeX punctuation.
+    fn unescapes_latex() {
+        assert_eq!(unescape("G{\\\"o}del \\& \\\"{U}ber"), "Gödel & Über");
+        assert_eq!(unescape("{\\v{C}}ech--Stra{\\ss}e"), "Čech–Straße");
+        assert_eq!(unescape("\\'{\\i}ndice \\c c \\aa{}r"), "índice ç år");
+        assert_eq!(
+            unescape("{DNA} \\emph{in   vivo}\n``quoted''"),
+            "DNA in vivo “quoted”"
+        );
+        let text = "Ærøskøbing ~ 50% {x}–y \\ #1";
+        assert_eq!(unescape(&escape(text)), text);
+    }
+}
@@ -0,0 +1,74 @@
+//! BibTeX and BibLaTeX support.
+//!
+//! [`BibEntry::from_metadata`] converts [`DoiMetadata`](crate::DoiMetadata)
+//! into `.bib` entries and [`parse_bibtex`](import::parse_bibtex) reads them
+//! back; [`latex`] holds the escaping rules both directions share and
+//! [`key::CitationKeyGenerator`] builds citation keys from patterns.
+
+pub mod error;
+pub mod export;
+pub mod import;
+pub mod key;
+pub mod latex;
+pub mod parse;
+
+use std::fmt;
+
+/// The `.bib` dialect to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BibDialect {
+    /// Classic BibTeX: `journal`, `year`/`month`, `address`, `@techreport`.
+    #[default]
+    Bibtex,
+    /// BibLaTeX: `journaltitle`, `date`, `location`, `@report`, `@online`.
+    Biblatex,
+}
+
+/// A field value as written in a `.bib` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BibValue {
+    /// A braced value, already LaTeX-escaped: `{...}`.
+    Braced(String),
+    /// A bare macro name or number, such as `mar` or `2020`.
+    Bare(String),
+}
+
+impl fmt::Display for BibValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BibValue::Braced(value) => write!(f, "{{{value}}}"),
+            BibValue::Bare(value) => f.write_str(value),
+        }
+    }
+}
+
+/// One `.bib` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BibEntry {
+    /// Entry type without `@`, e.g. `article`.
+    pub entry_type: String,
+    /// Citation key.
+    pub key: String,
+    /// Fields in output order.
+    pub fields: Vec<(String, BibValue)>,
+}
+
+impl BibEntry {
+    /// Return the value of a field, ignoring case.
+    pub fn field(&self, name: &str) -> Option<&BibValue> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+}
+
+impl fmt::Display for BibEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "@{}{{{},", self.entry_type, self.key)?;
+        for (name, value) in &self.fields {
+            writeln!(f, "  {name} = {value},")?;
+        }
+        write!(f, "}}")
+    }
+}
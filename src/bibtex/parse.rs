@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::bibtex::{BibEntry, BibValue};
+
+/// Month macros predefined by BibTeX.
+const MONTH_MACROS: [(&str, &str); 12] = [
+    ("jan", "January"),
+    ("feb", "February"),
+    ("mar", "March"),
+    ("apr", "April"),
+    ("may", "May"),
+    ("jun", "June"),
+    ("jul", "July"),
+    ("aug", "August"),
+    ("sep", "September"),
+    ("oct", "October"),
+    ("nov", "November"),
+    ("dec", "December"),
+];
+
+/// A problem found while reading a `.bib` file; reading continues after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BibWarning {
+    /// 1-based line where the problem was found.
+    pub line: usize,
+    /// Key of the entry concerned, when known.
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for BibWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "line {} ({key}): {}", self.line, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// The entries of a `.bib` file, with `@string` macros expanded and
+/// `crossref` fields inherited.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BibFile {
+    pub entries: Vec<BibEntry>,
+    /// Contents of `@preamble` blocks.
+    pub preambles: Vec<String>,
+    pub warnings: Vec<BibWarning>,
+    /// Line of each entry, for warnings raised after parsing.
+    pub(crate) lines: Vec<usize>,
+}
+
+impl BibFile {
+    /// Parse BibTeX or BibLaTeX source, recovering from malformed entries.
+    pub fn parse(input: &str) -> Self {
+        let mut parser = Parser::new(input);
+        parser.run();
+        let mut file = BibFile {
+            entries: parser.entries,
+            preambles: parser.preambles,
+            warnings: parser.warnings,
+            lines: parser.lines,
+        };
+        file.inherit_crossrefs();
+        file.warnings.sort_by_key(|warning| warning.line);
+        file
+    }
+
+    /// Copy missing fields from the entries named in `crossref` fields.
+    fn inherit_crossrefs(&mut self) {
+        let index: HashMap<String, usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| (entry.key.to_lowercase(), position))
+            .collect();
+        for position in 0..self.entries.len() {
+            let Some(BibValue::Braced(parent) | BibValue::Bare(parent)) =
+                self.entries[position].field("crossref").cloned()
+            else {
+                continue;
+            };
+            let Some(&parent) = index.get(&parent.trim().to_lowercase()) else {
+                self.warnings.push(BibWarning {
+                    line: self.lines[position],
+                    key: Some(self.entries[position].key.clone()),
+                    message: format!("crossref target `{parent}` not found"),
+                });
+                continue;
+            };
+            let inherited = self.entries[parent].fields.clone();
+            let child = &mut self.entries[position];
+            for (name, value) in inherited {
+                // A parent's title is the container title of its children.
+                let name = match name.as_str() {
+                    "title" => "booktitle".to_string(),
+                    _ => name,
+                };
+                if name != "crossref" && child.field(&name).is_none() {
+                    child.fields.push((name, value));
+                }
+            }
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    strings: HashMap<String, String>,
+    entries: Vec<BibEntry>,
+    lines: Vec<usize>,
+    preambles: Vec<String>,
+    warnings: Vec<BibWarning>,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+            line: 1,
+            strings: MONTH_MACROS
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            entries: Vec::new(),
+            lines: Vec::new(),
+            preambles: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        if ch == '\n' {
+            self.line += 1;
+        }
+        Some(ch)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn warn(&mut self, key: Option<&str>, message: impl Into<String>) {
+        self.warnings.push(BibWarning {
+            line: self.line,
+            key: key.map(str::to_string),
+            message: message.into(),
+        });
+    }
+
+    /// Read an identifier: a type, field or macro name.
+    fn identifier(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(ch) = self
+            .peek()
+            .filter(|ch| ch.is_alphanumeric() || "_-:.+/'".contains(*ch))
+        {
+            name.push(ch);
+            self.bump();
+        }
+        name
+    }
+
+    fn run(&mut self) {
+        loop {
+            // Text between entries is a comment.
+            while self.peek().is_some_and(|ch| ch != '@') {
+                self.bump();
+            }
+            if self.bump().is_none() {
+                return;
+            }
+            let line = self.line;
+            self.skip_whitespace();
+            let kind = self.identifier().to_lowercase();
+            if kind.is_empty() {
+                self.warn(None, "expected an entry type after `@`");
+                continue;
+            }
+            self.skip_whitespace();
+            let close = match self.peek() {
+                Some('{') => '}',
+                Some('(') => ')',
+                _ => {
+                    self.warn(None, format!("expected `{{` after `@{kind}`"));
+                    continue;
+                }
+            };
+            self.bump();
+            match kind.as_str() {
+                "comment" => self.skip_block(close),
+                "preamble" => {
+                    if let Some(value) = self.value(None) {
+                        self.preambles.push(value_text(&value).to_string());
+                    }
+                    self.finish_block(close, None);
+                }
+                "string" => self.string(close),
+                _ => self.entry(kind, close, line),
+            }
+        }
+    }
+
+    /// Skip to the end of a block, honoring nested braces.
+    fn skip_block(&mut self, close: char) {
+        let mut depth = 0;
+        while let Some(ch) = self.bump() {
+            match ch {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                _ if ch == close && depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Skip the rest of a malformed entry, stopping before an `@` that starts
+    /// a line so an unclosed entry does not swallow the next one.
+    fn skip_entry(&mut self, close: char) {
+        let mut depth = 0;
+        while let Some(ch) = self.peek() {
+            if self.at_entry_start() {
+                return;
+            }
+            self.bump();
+            match ch {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                _ if ch == close && depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether the next character is an `@` with only whitespace before it on its line.
+    fn at_entry_start(&self) -> bool {
+        self.peek() == Some('@')
+            && self.chars[..self.pos]
+                .iter()
+                .rev()
+                .take_while(|ch| **ch != '\n')
+                .all(|ch| ch.is_whitespace())
+    }
+
+    /// Expect the closing delimiter, skipping anything before it.
+    fn finish_block(&mut self, close: char, key: Option<&str>) {
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.bump();
+        } else {
+            self.warn(key, format!("expected `{close}`"));
+            self.skip_block(close);
+        }
+    }
+
+    fn string(&mut self, close: char) {
+        self.skip_whitespace();
+        let name = self.identifier().to_lowercase();
+        self.skip_whitespace();
+        if name.is_empty() || self.peek() != Some('=') {
+            self.warn(None, "malformed @string");
+            self.skip_block(close);
+            return;
+        }
+        self.bump();
+        if let Some(value) = self.value(None) {
+            self.strings.insert(name, value_text(&value).to_string());
+        }
+        self.finish_block(close, None);
+    }
+
+    fn entry(&mut self, kind: String, close: char, line: usize) {
+        self.skip_whitespace();
+        let mut key = String::new();
+        while let Some(ch) = self
+            .peek()
+            .filter(|ch| !ch.is_whitespace() && *ch != ',' && *ch != close)
+        {
+            key.push(ch);
+            self.bump();
+        }
+        if key.is_empty() {
+            self.warn(None, format!("@{kind} entry without a key"));
+        }
+        let mut entry = BibEntry {
+            entry_type: kind,
+            key,
+            fields: Vec::new(),
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.at_entry_start() {
+                self.warn(
+                    Some(&entry.key),
+                    format!("expected `{close}` before the next entry"),
+                );
+                break;
+            }
+            match self.bump() {
+                Some(',') => {}
+                Some(ch) if ch == close => break,
+                None => {
+                    self.warn(Some(&entry.key), "unterminated entry");
+                    break;
+                }
+                Some(ch) => {
+                    self.warn(Some(&entry.key), format!("unexpected `{ch}`"));
+                    self.skip_entry(close);
+                    break;
+                }
+            }
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.bump();
+                break;
+            }
+            let name = self.identifier().to_lowercase();
+            self.skip_whitespace();
+            if name.is_empty() || self.peek() != Some('=') {
+                self.warn(Some(&entry.key), "expected `field = value`");
+                self.skip_entry(close);
+                break;
+            }
+            self.bump();
+            let Some(value) = self.value(Some(&entry.key)) else {
+                self.skip_block(close);
+                break;
+            };
+            if entry.field(&name).is_some() {
+                self.warn(
+                    Some(&entry.key),
+                    format!("duplicate field `{name}` ignored"),
+                );
+            } else {
+                entry.fields.push((name, value));
+            }
+        }
+        self.entries.push(entry);
+        self.lines.push(line);
+    }
+
+    /// Read a value: braced or quoted strings, numbers and macros joined by `#`.
+    fn value(&mut self, key: Option<&str>) -> Option<BibValue> {
+        let mut pieces = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let piece = match self.peek() {
+                Some('{') => {
+                    self.bump();
+                    BibValue::Braced(self.delimited('}', key)?)
+                }
+                Some('"') => {
+                    self.bump();
+                    BibValue::Braced(self.delimited('"', key)?)
+                }
+                Some(ch) if ch.is_ascii_digit() => {
+                    let mut number = String::new();
+                    while let Some(digit) = self.peek().filter(char::is_ascii_digit) {
+                        number.push(digit);
+                        self.bump();
+                    }
+                    BibValue::Bare(number)
+                }
+                Some(ch) if ch.is_alphabetic() => {
+                    let name = self.identifier();
+                    match self.strings.get(&name.to_lowercase()) {
+                        Some(value) => BibValue::Braced(value.clone()),
+                        None => {
+                            self.warn(key, format!("undefined string macro `{name}`"));
+                            BibValue::Braced(String::new())
+                        }
+                    }
+                }
+                _ => {
+                    self.warn(key, "expected a value");
+                    return None;
+                }
+            };
+            pieces.push(piece);
+            self.skip_whitespace();
+            if self.peek() == Some('#') {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        if let [BibValue::Bare(number)] = pieces.as_slice() {
+            return Some(BibValue::Bare(number.clone()));
+        }
+        Some(BibValue::Braced(pieces.iter().map(value_text).collect()))
+    }
+
+    /// Read up to an unnested closing delimiter, keeping inner braces.
+    fn delimited(&mut self, close: char, key: Option<&str>) -> Option<String> {
+        let line = self.line;
+        let mut depth = 0;
+        let mut text = String::new();
+        while let Some(ch) = self.bump() {
+            match ch {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                _ if ch == close && depth == 0 => return Some(text),
+                '}' => {
+                    self.warn(key, "unbalanced `}`");
+                    return Some(text);
+                }
+                _ => {}
+            }
+            text.push(ch);
+        }
+        self.warnings.push(BibWarning {
+            line,
+            key: key.map(str::to_string),
+            message: "unterminated value".to_string(),
+        });
+        None
+    }
+}
+
+/// Return the raw text of a value.
+pub(crate) fn value_text(value: &BibValue) -> &str {
+    match value {
+        BibValue::Braced(text) | BibValue::Bare(text) => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Expands macros and concatenation, skips comments and inherits crossref fields.
+    fn parses_structure() {
+        let input = r#"
+            % a comment line
+            @string{ acm = "ACM Press" }
+            @preamble{ "\newcommand{\noop}[1]{}" }
+            @comment{ ignored {nested} }
+            @InProceedings{child,
+              author = {Doe, Jane},
+              title = "A {GPU} paper",
+              pages = 12 # "--" # 20,
+              month = mar,
+              crossref = {proc},
+            }
+            @proceedings(proc,
+              title = {Proceedings of Things},
+              publisher = acm # { Inc.},
+              year = 2021
+            )
+        "#;
+        let file = BibFile::parse(input);
+        assert!(file.warnings.is_empty(), "{:?}", file.warnings);
+        assert_eq!(file.preambles, ["\\newcommand{\\noop}[1]{}"]);
+        let child = &file.entries[0];
+        assert_eq!(child.entry_type, "inproceedings");
+        let text = |name: &str| child.field(name).map(value_text);
+        assert_eq!(text("title"), Some("A {GPU} paper"));
+        assert_eq!(text("pages"), Some("12--20"));
+        assert_eq!(text("month"), Some("March"));
+        assert_eq!(text("booktitle"), Some("Proceedings of Things"));
+        assert_eq!(text("publisher"), Some("ACM Press Inc."));
+        assert_eq!(
+            child.field("year"),
+            Some(&BibValue::Bare("2021".to_string()))
+        );
+    }
+
+    #[test]
+    /// Reports problems with line numbers and keeps reading later entries.
+    fn recovers_with_warnings() {
+        let input = "@article{a,\n  title = {One},\n  title = {Two},\n  journal = jnl,\n}\n\n\
+                     @misc{c, crossref = {zzz}, note = {ok}}\n\
+                     @article{bad, title={X}\n\
+                     @article{after, title = {Y}}\n\
+                     @book{b,\n  title = {Unclosed\n";
+        let file = BibFile::parse(input);
+        let messages: Vec<String> = file.warnings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "line 3 (a): duplicate field `title` ignored",
+                "line 4 (a): undefined string macro `jnl`",
+                "line 7 (c): crossref target `zzz` not found",
+                "line 9 (bad): expected `}` before the next entry",
+                "line 11 (b): unterminated value",
+            ]
+        );
+        assert_eq!(file.entries.len(), 5);
+        assert_eq!(file.entries[0].field("title").map(value_text), Some("One"));
+        assert_eq!(file.entries[2].field("title").map(value_text), Some("X"));
+        assert_eq!(file.entries[3].key, "after");
+        assert_eq!(file.entries[3].field("title").map(value_text), Some("Y"));
+    }
+}
@@ -0,0 +1,440 @@
+//! Command-line access to DOI extraction and doi.org lookups.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use serde_json::json;
+
+use doi::{
+    BatchOptions, BibEntry, BibtexOptions, BundledStyle, Doi, DoiMetadata, DoiOrgClient,
+    DoiOrgConfig, OutputFormat, Processor, Style, dublin_core_tags, extract_doi_from_url,
+    highwire_tags, json_ld_script, write_ris,
+};
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "doi",
+    version,
+    about = "Extract DOIs and look up their metadata"
+)]
+struct Cli {
+    #[command(flatten)]
+    client: ClientArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// doi.org client settings, from flags or environment variables.
+#[derive(Args)]
+struct ClientArgs {
+    /// Base URL for doi.org.
+    #[arg(
+        long,
+        global = true,
+        env = "DOI_BASE_URL",
+        default_value = "https://doi.org"
+    )]
+    base_url: String,
+    /// Contact email for the polite User-Agent.
+    #[arg(long, global = true, env = "DOI_MAILTO")]
+    mailto: Option<String>,
+    /// Application name for the User-Agent.
+    #[arg(long, global = true, env = "DOI_USER_AGENT")]
+    user_agent: Option<String>,
+    /// Request timeout in seconds.
+    #[arg(long, global = true, env = "DOI_TIMEOUT", default_value_t = 30)]
+    timeout: u64,
+    /// Maximum requests per second [default: 5, or 10 with --mailto; 0 disables].
+    #[arg(long, global = true, env = "DOI_RATE_LIMIT")]
+    rate_limit: Option<u32>,
+}
+
+impl ClientArgs {
+    fn config(&self) -> DoiOrgConfig {
+        DoiOrgConfig {
+            base_url: self.base_url.clone(),
+            timeout: Duration::from_secs(self.timeout),
+            mailto: self.mailto.clone(),
+            user_agent: self.user_agent.clone(),
+            rate_limit_per_sec: self.rate_limit,
+            concurrency: None,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the DOIs found in text, files or stdin, one per line.
+    Extract {
+        /// Text to scan instead of files or stdin.
+        #[arg(short, long)]
+        text: Option<String>,
+        /// Files to scan; stdin is read when none are given.
+        files: Vec<PathBuf>,
+    },
+    /// Fetch and print metadata for a DOI.
+    Meta {
+        doi: String,
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+        /// Bundled style name or path to a `.csl` file, for `--format citation`.
+        #[arg(short, long, default_value = "apa")]
+        style: String,
+        /// Ask doi.org for the format via content negotiation instead of
+        /// converting CSL-JSON locally; `--style` is then a CSL style ID.
+        #[arg(long)]
+        negotiate: bool,
+    },
+    /// Print the landing page URL a DOI resolves to.
+    Resolve { doi: String },
+    /// Print the registration agency of a DOI.
+    Ra { doi: String },
+    /// Read a DOI list and write one JSON object per line.
+    Batch {
+        /// DOI list, one per line; stdin when omitted.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// JSONL output; stdout when omitted.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Requests in flight.
+        #[arg(short, long, default_value_t = 8)]
+        concurrency: usize,
+        /// Write results as they complete instead of in input order.
+        #[arg(long)]
+        unordered: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// CSL-JSON.
+    Json,
+    /// A bibliography entry in the style chosen with `--style`.
+    Citation,
+    /// A BibTeX entry.
+    Bibtex,
+    /// A BibLaTeX entry.
+    Biblatex,
+    /// A RIS record.
+    Ris,
+    /// A schema.org JSON-LD `<script>` element.
+    JsonLd,
+    /// Highwire `citation_*` and Dublin Core `<meta>` tags.
+    MetaTags,
+}
+
+#[tokio::main]
+async fn main() -> CliResult {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Extract { text, files } => extract(text, &files),
+        Command::Meta {
+            doi,
+            format,
+            style,
+            negotiate,
+        } => {
+            let client = client(&cli.client)?;
+            if negotiate {
+                negotiated_meta(&client, &doi, format, &style).await
+            } else {
+                meta(&client, &doi, format, &style).await
+            }
+        }
+        Command::Resolve { doi } => {
+            match client(&cli.client)?.landing_url(&Doi::parse(&doi)?).await? {
+                Some(url) => println!("{url}"),
+                None => return Err(format!("DOI not found: {doi}").into()),
+            }
+            Ok(())
+        }
+        Command::Ra { doi } => {
+            let agency = client(&cli.client)?
+                .registration_agency(&Doi::parse(&doi)?)
+                .await?;
+            match agency {
+                Some(agency) => println!("{agency}"),
+                None => return Err(format!("DOI not found: {doi}").into()),
+            }
+            Ok(())
+        }
+        Command::Batch {
+            input,
+            output,
+            concurrency,
+            unordered,
+        } => {
+            let mut options = BatchOptions::new().concurrency(concurrency);
+            if unordered {
+                options = options.unordered();
+            }
+            batch(&client(&cli.client)?, input, output, options).await
+        }
+    }
+}
+
+fn client(args: &ClientArgs) -> CliResult<DoiOrgClient> {
+    Ok(DoiOrgClient::new(args.config())?)
+}
+
+/// Read a file, or stdin when no path is given.
+fn read_input(path: Option<&PathBuf>) -> CliResult<String> {
+    match path {
+        Some(path) => Ok(fs::read_to_string(path)?),
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+    }
+}
+
+fn extract(text: Option<String>, files: &[PathBuf]) -> CliResult {
+    let inputs = match (text, files) {
+        (Some(text), _) => vec![text],
+        (None, []) => vec![read_input(None)?],
+        (None, files) => files
+            .iter()
+            .map(|file| read_input(Some(file)))
+            .collect::<CliResult<_>>()?,
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for doi in extract_dois(&inputs) {
+        writeln!(out, "{}", doi.as_str())?;
+    }
+    Ok(())
+}
+
+/// Return the DOIs found in the inputs, first occurrence of each canonical DOI only.
+fn extract_dois(inputs: &[String]) -> Vec<Doi> {
+    let mut seen = HashSet::new();
+    inputs
+        .iter()
+        .flat_map(|input| input.split_whitespace())
+        .filter_map(extract_doi_from_url)
+        .filter(|doi| seen.insert(doi.canonical()))
+        .collect()
+}
+
+async fn meta(client: &DoiOrgClient, doi: &str, format: Format, style: &str) -> CliResult {
+    let metadata = client.metadata(&Doi::parse(doi)?).await?;
+    println!("{}", render(&metadata, format, style)?);
+    Ok(())
+}
+
+/// Fetch a format from doi.org via content negotiation and print it unchanged.
+async fn negotiated_meta(
+    client: &DoiOrgClient,
+    doi: &str,
+    format: Format,
+    style: &str,
+) -> CliResult {
+    let accept = negotiated_accept(format, style)?;
+    let response = client.fetch_raw(&Doi::parse(doi)?, &accept, None).await?;
+    match response.status {
+        200..=299 => {
+            println!("{}", response.body.trim_end());
+            Ok(())
+        }
+        404 => Err(format!("DOI not found: {doi}").into()),
+        status => Err(format!("doi.org answered HTTP {status} for {accept}").into()),
+    }
+}
+
+/// Return the `Accept` header doi.org uses for a format.
+fn negotiated_accept(format: Format, style: &str) -> CliResult<String> {
+    let accept = match format {
+        Format::Json => "application/vnd.citationstyles.csl+json",
+        Format::Bibtex => "application/x-bibtex",
+        Format::Ris => "application/x-research-info-systems",
+        Format::JsonLd => "application/vnd.schemaorg.ld+json",
+        Format::Citation => {
+            // doi.org takes style IDs from the CSL style repository.
+            let id = match style.parse::<BundledStyle>() {
+                Ok(BundledStyle::Mla) => "modern-language-association",
+                Ok(bundled) => bundled.as_str(),
+                Err(_) => style,
+            };
+            return Ok(format!("text/x-bibliography; style={id}"));
+        }
+        Format::Biblatex | Format::MetaTags => {
+            return Err("doi.org does not serve this format; omit --negotiate".into());
+        }
+    };
+    Ok(accept.to_string())
+}
+
+fn render(metadata: &DoiMetadata, format: Format, style: &str) -> CliResult<String> {
+    match format {
+        Format::Json => Ok(serde_json::to_string_pretty(metadata)?),
+        Format::Citation => {
+            let mut processor = Processor::new(load_style(style)?);
+            processor.add_item(metadata)?;
+            Ok(processor.bibliography(OutputFormat::Text)?.to_string())
+        }
+        Format::Bibtex => Ok(BibEntry::from_metadata(metadata, &BibtexOptions::new()).to_string()),
+        Format::Biblatex => {
+            let options = BibtexOptions::new().biblatex();
+            Ok(BibEntry::from_metadata(metadata, &options).to_string())
+        }
+        Format::Ris => Ok(write_ris([metadata]).trim_end().to_string()),
+        Format::JsonLd => Ok(json_ld_script(metadata)),
+        Format::MetaTags => {
+            let tags: Vec<String> = highwire_tags(metadata)
+                .into_iter()
+                .chain(dublin_core_tags(metadata))
+                .map(|tag| tag.to_string())
+                .collect();
+            Ok(tags.join("\n"))
+        }
+    }
+}
+
+/// Load a bundled style by name, or a CSL file when `style` is an existing path.
+fn load_style(style: &str) -> CliResult<Style> {
+    let path = PathBuf::from(style);
+    if path.is_file() {
+        return Ok(Style::parse(&fs::read_to_string(path)?)?);
+    }
+    Ok(Style::bundled(style.parse::<BundledStyle>()?))
+}
+
+async fn batch(
+    client: &DoiOrgClient,
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    options: BatchOptions,
+) -> CliResult {
+    let list = read_input(input.as_ref())?;
+    let dois: Vec<Doi> = list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| match Doi::parse(line) {
+            Ok(doi) => Some(doi),
+            Err(error) => {
+                eprintln!("skipping {line}: {error}");
+                None
+            }
+        })
+        .collect();
+
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let options = options.on_progress(|progress| {
+        eprint!(
+            "\r{}/{} ({} failed)",
+            progress.completed, progress.total, progress.failed
+        );
+    });
+    let mut results = client.metadata_many_with(dois, options);
+    while let Some((doi, result)) = results.next().await {
+        let line = match result {
+            Ok(metadata) => json!({ "doi": doi.as_str(), "metadata": metadata }),
+            Err(error) => json!({ "doi": doi.as_str(), "error": error.to_string() }),
+        };
+        writeln!(out, "{line}")?;
+    }
+    eprintln!();
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> DoiMetadata {
+        serde_json::from_value(json!({
+            "type": "book",
+            "DOI": "10.1000/182",
+            "title": "The DOI Handbook",
+            "author": [{"family": "Paskin", "given": "Norman"}],
+            "publisher": "International DOI Foundation",
+            "issued": {"date-parts": [[2006]]}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    /// Finds DOIs in text and URLs once per canonical DOI, in order.
+    fn extract_dedups_by_canonical_doi() {
+        let inputs = vec![
+            "see https://doi.org/10.1000/182 and doi:10.1038/nature12373".to_string(),
+            "again 10.1000/182, also 10.1038/NATURE12373 but not 10.nope".to_string(),
+        ];
+        let dois: Vec<String> = extract_dois(&inputs)
+            .iter()
+            .map(|doi| doi.as_str().to_string())
+            .collect();
+        assert_eq!(dois, ["10.1000/182", "10.1038/nature12373"]);
+    }
+
+    #[test]
+    /// Renders metadata in the local formats.
+    fn render_formats() {
+        let metadata = book();
+        let json = render(&metadata, Format::Json, "apa").unwrap();
+        assert_eq!(
+            serde_json::from_str::<DoiMetadata>(&json).unwrap(),
+            metadata
+        );
+        assert_eq!(
+            render(&metadata, Format::Citation, "apa").unwrap(),
+            "Paskin, N. (2006). The DOI Handbook. International DOI Foundation. \
+             https://doi.org/10.1000/182"
+        );
+        assert!(
+            render(&metadata, Format::Bibtex, "apa")
+                .unwrap()
+                .starts_with("@book{")
+        );
+        let ris = render(&metadata, Format::Ris, "apa").unwrap();
+        assert!(ris.starts_with("TY  - BOOK"));
+        assert!(ris.ends_with("ER  -"));
+        assert!(
+            render(&metadata, Format::MetaTags, "apa")
+                .unwrap()
+                .contains(r#"<meta name="citation_title" content="The DOI Handbook">"#)
+        );
+        assert!(render(&metadata, Format::Citation, "no-such-style").is_err());
+    }
+
+    #[test]
+    /// Maps formats to doi.org media types and rejects ones doi.org lacks.
+    fn negotiated_media_types() {
+        assert_eq!(
+            negotiated_accept(Format::Bibtex, "apa").unwrap(),
+            "application/x-bibtex"
+        );
+        assert_eq!(
+            negotiated_accept(Format::Ris, "apa").unwrap(),
+            "application/x-research-info-systems"
+        );
+        assert_eq!(
+            negotiated_accept(Format::Citation, "mla").unwrap(),
+            "text/x-bibliography; style=modern-language-association"
+        );
+        assert_eq!(
+            negotiated_accept(Format::Citation, "harvard-cite-them-right").unwrap(),
+            "text/x-bibliography; style=harvard-cite-them-right"
+        );
+        assert!(negotiated_accept(Format::Biblatex, "apa").is_err());
+    }
+}
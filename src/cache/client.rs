@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
+use snafu::ResultExt;
+
+use crate::Doi;
+use crate::cache::config::CacheConfig;
+use crate::cache::error::*;
+use crate::cache::{CacheEntry, CacheKey, CacheStore};
+use crate::csl::DoiMetadata;
+use crate::doi_org::client::DoiOrgClient;
+use crate::source::error::{CacheSnafu, NotFoundSnafu as SourceNotFoundSnafu, SourceError};
+use crate::source::{MetadataSource, Record};
+
+/// A [`DoiOrgClient`] that reads and writes responses through a [`CacheStore`].
+pub struct CachedDoiOrgClient {
+    client: DoiOrgClient,
+    store: Arc<dyn CacheStore>,
+    config: CacheConfig,
+}
+
+impl CachedDoiOrgClient {
+    /// Wrap a client with a store.
+    pub fn new(
+        client: DoiOrgClient,
+        store: impl CacheStore + 'static,
+        config: CacheConfig,
+    ) -> Self {
+        Self {
+            client,
+            store: Arc::new(store),
+            config,
+        }
+    }
+
+    /// Return the wrapped client.
+    pub fn client(&self) -> &DoiOrgClient {
+        &self.client
+    }
+
+    /// Fetch CSL-JSON metadata, from the cache when fresh.
+    pub async fn metadata(&self, doi: &Doi) -> Result<DoiMetadata, CacheError> {
+        let entry = self.fetch(doi, DoiOrgClient::CSL_JSON_ACCEPT).await?;
+        if entry.is_not_found() {
+            return NotFoundSnafu { doi: doi.as_str() }.fail();
+        }
+
+        let mut deserializer = serde_json::Deserializer::from_str(&entry.body);
+        serde_path_to_error::deserialize(&mut deserializer).context(SerializePathSnafu {
+            stage: "parse-json",
+        })
+    }
+
+    /// Return the cached response for a DOI and content type, fetching when needed.
+    ///
+    /// Stale entries with an `ETag` are revalidated with `If-None-Match`.
+    /// `404` answers are cached for `negative_ttl`; other errors are not cached.
+    pub async fn fetch(&self, doi: &Doi, accept: &str) -> Result<CacheEntry, CacheError> {
+        let key = CacheKey::new(doi, accept);
+        let cached = {
+            let key = key.clone();
+            self.blocking(move |store| store.get(&key)).await?
+        };
+        if let Some(entry) = &cached
+            && (self.config.offline || self.is_fresh(entry))
+        {
+            return Ok(entry.clone());
+        }
+        if self.config.offline {
+            return OfflineSnafu { doi: doi.as_str() }.fail();
+        }
+
+        let etag = cached
+            .as_ref()
+            .filter(|entry| !entry.is_not_found())
+            .and_then(|entry| entry.etag.as_deref());
+        let raw = self
+            .client
+            .fetch_raw(doi, accept, etag)
+            .await
+            .context(FetchSnafu { stage: "fetch" })?;
+
+        let entry = match (raw.status, cached) {
+            (304, Some(mut entry)) => {
+                entry.fetched_at = Utc::now();
+                entry
+            }
+            (200..=299 | 404, _) => CacheEntry::from_raw(key, raw),
+            (status, _) => {
+                return StatusSnafu {
+                    doi: doi.as_str(),
+                    status,
+                }
+                .fail();
+            }
+        };
+        self.blocking(move |store| store.put(&entry).map(|()| entry))
+            .await
+    }
+
+    /// Drop the cached response for a DOI and content type.
+    pub fn invalidate(&self, doi: &Doi, accept: &str) -> Result<(), CacheError> {
+        self.store.remove(&CacheKey::new(doi, accept))
+    }
+
+    /// Run a store call on the blocking thread pool, off the async runtime.
+    async fn blocking<T, F>(&self, call: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn CacheStore) -> T + Send + 'static,
+    {
+        let store = self.store.clone();
+        match tokio::task::spawn_blocking(move || call(store.as_ref())).await {
+            Ok(value) => value,
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        }
+    }
+
+    /// Return true when an entry is within its TTL.
+    fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        let ttl = if entry.is_not_found() {
+            self.config.negative_ttl
+        } else {
+            self.config.ttl
+        };
+        let ttl = TimeDelta::from_std(ttl).unwrap_or(TimeDelta::MAX);
+        Utc::now().signed_duration_since(entry.fetched_at) < ttl
+    }
+}
+
+#[async_trait]
+impl MetadataSource for CachedDoiOrgClient {
+    fn name(&self) -> &str {
+        "doi.org"
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        match CachedDoiOrgClient::metadata(self, doi).await {
+            Ok(metadata) => Ok(Record::new(metadata, MetadataSource::name(self))),
+            Err(CacheError::NotFound { .. }) => SourceNotFoundSnafu {
+                source_name: MetadataSource::name(self),
+                doi: doi.as_str(),
+            }
+            .fail(),
+            Err(error) => Err(error).context(CacheSnafu { stage: "metadata" }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::fs::FsCacheStore;
+    use crate::doi_org::config::DoiOrgConfig;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn cached(
+        server: &MockServer,
+        dir: &std::path::Path,
+        config: CacheConfig,
+    ) -> CachedDoiOrgClient {
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            ..Default::default()
+        })
+        .unwrap();
+        CachedDoiOrgClient::new(client, FsCacheStore::new(dir), config)
+    }
+
+    #[tokio::test]
+    /// Serves repeat lookups from disk, caches 404s and works offline afterwards.
+    async fn caches_hits_and_misses() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/cached"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"type":"article","title":"Cached"}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let client = cached(&server, dir.path(), CacheConfig::default());
+
+        let doi = Doi::parse("10.5555/cached").unwrap();
+        let missing = Doi::parse("10.5555/missing").unwrap();
+        for _ in 0..2 {
+            let metadata = client.metadata(&doi).await.unwrap();
+            assert_eq!(metadata.title.as_deref(), Some("Cached"));
+            let error = MetadataSource::metadata(&client, &missing)
+                .await
+                .unwrap_err();
+            assert!(error.is_not_found());
+        }
+
+        let offline = cached(&server, dir.path(), CacheConfig::default().offline());
+        assert!(offline.metadata(&doi).await.is_ok());
+        let unknown = Doi::parse("10.5555/unknown").unwrap();
+        assert!(matches!(
+            offline.metadata(&unknown).await,
+            Err(CacheError::Offline { .. })
+        ));
+    }
+
+    #[tokio::test]
+    /// Revalidates stale entries with their ETag and keeps the body on 304.
+    async fn revalidates_with_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/etag"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/etag"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .set_body_string(r#"{"title":"Tagged"}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = CacheConfig {
+            ttl: std::time::Duration::ZERO,
+            ..Default::default()
+        };
+        let client = cached(&server, dir.path(), config);
+
+        let doi = Doi::parse("10.5555/etag").unwrap();
+        let first = client.fetch(&doi, "application/json").await.unwrap();
+        assert_eq!(first.etag.as_deref(), Some("\"v1\""));
+        let second = client.fetch(&doi, "application/json").await.unwrap();
+        assert_eq!(second.body, first.body);
+        assert!(second.fetched_at >= first.fetched_at);
+    }
+}
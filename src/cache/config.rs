@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// Configuration for [`CachedDoiOrgClient`](crate::cache::client::CachedDoiOrgClient).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheConfig {
+    /// How long a successful response is served without revalidation.
+    pub ttl: Duration,
+    /// How long a `404` answer is remembered.
+    pub negative_ttl: Duration,
+    /// Serve only from the cache, even stale entries, and never hit the network.
+    pub offline: bool,
+}
+
+impl Default for CacheConfig {
+    /// Return a 7 day TTL and a 1 day negative TTL.
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(7 * 24 * 60 * 60),
+            negative_ttl: Duration::from_secs(24 * 60 * 60),
+            offline: false,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Switch to offline mode.
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use snafu::Snafu;
+
+use crate::doi_org::error::DoiOrgError;
+
+/// Errors returned by the metadata cache.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum CacheError {
+    #[snafu(display("Cache I/O failed at {stage} for {}: {source}", path.display()))]
+    Io {
+        stage: &'static str,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Cache entry encoding failed at {stage}: {source}"))]
+    Encode {
+        stage: &'static str,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Failed to serialize at {stage}: {source}"))]
+    SerializePath {
+        source: serde_path_to_error::Error<serde_json::Error>,
+        stage: &'static str,
+    },
+
+    #[snafu(display("doi.org fetch failed at {stage}: {source}"))]
+    Fetch {
+        stage: &'static str,
+        source: DoiOrgError,
+    },
+
+    #[snafu(display("doi.org returned HTTP {status} for {doi}"))]
+    Status { doi: String, status: u16 },
+
+    #[snafu(display("DOI not found: {doi}"))]
+    NotFound { doi: String },
+
+    #[snafu(display("DOI not cached and offline mode is on: {doi}"))]
+    Offline { doi: String },
+}
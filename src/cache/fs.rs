@@ -0,0 +1,158 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use snafu::ResultExt;
+
+use crate::cache::error::*;
+use crate::cache::{CacheEntry, CacheKey, CacheStore};
+
+/// Distinguishes the temporary files of concurrent writes within a process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A cache store keeping one JSON file per entry under a directory.
+///
+/// Files are named by [`CacheKey::digest`] and sharded by its first two characters.
+#[derive(Debug, Clone)]
+pub struct FsCacheStore {
+    root: PathBuf,
+}
+
+impl FsCacheStore {
+    /// Use a directory, created on first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Return the cache directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Return the file holding a key.
+    pub fn path_for(&self, key: &CacheKey) -> PathBuf {
+        let digest = key.digest();
+        self.root.join(&digest[..2]).join(format!("{digest}.json"))
+    }
+}
+
+impl CacheStore for FsCacheStore {
+    fn get(&self, key: &CacheKey) -> Result<Option<CacheEntry>, CacheError> {
+        let path = self.path_for(key);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(error).context(IoSnafu {
+                    stage: "read",
+                    path,
+                });
+            }
+        };
+        let entry: CacheEntry =
+            serde_json::from_slice(&bytes).context(EncodeSnafu { stage: "decode" })?;
+        // Guard against digest collisions and hand-edited files.
+        Ok(Some(entry).filter(|entry| entry.key == *key))
+    }
+
+    fn put(&self, entry: &CacheEntry) -> Result<(), CacheError> {
+        let path = self.path_for(&entry.key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(IoSnafu {
+                stage: "create-dir",
+                path: parent,
+            })?;
+        }
+        let bytes = serde_json::to_vec(entry).context(EncodeSnafu { stage: "encode" })?;
+
+        // Write then rename, so readers never see a partial file.
+        let temp = path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, bytes).context(IoSnafu {
+            stage: "write",
+            path: &temp,
+        })?;
+        fs::rename(&temp, &path).context(IoSnafu {
+            stage: "rename",
+            path: &path,
+        })
+    }
+
+    fn remove(&self, key: &CacheKey) -> Result<(), CacheError> {
+        let path = self.path_for(key);
+        match fs::remove_file(&path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error).context(IoSnafu {
+                stage: "remove",
+                path,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Doi;
+    use chrono::Utc;
+
+    #[test]
+    /// Stores, reads back case-insensitively and removes entries.
+    fn fs_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FsCacheStore::new(dir.path());
+        let key = CacheKey::new(&Doi::parse("10.5555/ABC").unwrap(), "Application/JSON");
+        let entry = CacheEntry {
+            key: key.clone(),
+            status: 200,
+            headers: [("etag".to_string(), "\"v1\"".to_string())].into(),
+            etag: Some("\"v1\"".to_string()),
+            body: "{}".to_string(),
+            fetched_at: Utc::now(),
+        };
+
+        assert_eq!(store.get(&key).unwrap(), None);
+        store.put(&entry).unwrap();
+        assert!(store.path_for(&key).starts_with(dir.path()));
+
+        let lower = CacheKey::new(&Doi::parse("10.5555/abc").unwrap(), "application/json");
+        assert_eq!(store.get(&lower).unwrap(), Some(entry));
+
+        store.remove(&key).unwrap();
+        store.remove(&key).unwrap();
+        assert_eq!(store.get(&key).unwrap(), None);
+    }
+
+    #[test]
+    /// Concurrent writes of the same key each use their own temporary file.
+    fn fs_store_concurrent_puts() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FsCacheStore::new(dir.path());
+        let key = CacheKey::new(&Doi::parse("10.5555/abc").unwrap(), "application/json");
+        let entry = CacheEntry {
+            key: key.clone(),
+            status: 200,
+            headers: Default::default(),
+            etag: None,
+            body: "{}".to_string(),
+            fetched_at: Utc::now(),
+        };
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..8 {
+                        store.put(&entry).unwrap();
+                    }
+                });
+            }
+        });
+        let shard = store.path_for(&key).parent().unwrap().to_path_buf();
+        assert_eq!(fs::read_dir(shard).unwrap().count(), 1);
+        assert_eq!(store.get(&key).unwrap(), Some(entry));
+    }
+}
@@ -0,0 +1,102 @@
+//! Optional on-disk caching of doi.org responses.
+//!
+//! [`CachedDoiOrgClient`](client::CachedDoiOrgClient) wraps a
+//! [`DoiOrgClient`](crate::DoiOrgClient) and stores raw responses in any
+//! [`CacheStore`], such as the filesystem-backed [`FsCacheStore`](fs::FsCacheStore).
+
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod fs;
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::Doi;
+use crate::cache::error::CacheError;
+use crate::doi_org::client::RawResponse;
+
+/// Identifies a cached response: canonical DOI plus requested content type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CacheKey {
+    /// Lowercased DOI.
+    pub doi: String,
+    /// Lowercased `Accept` type, e.g. `application/vnd.citationstyles.csl+json`.
+    pub content_type: String,
+}
+
+impl CacheKey {
+    /// Build a key for a DOI and content type.
+    pub fn new(doi: &Doi, content_type: &str) -> Self {
+        Self {
+            doi: doi.canonical(),
+            content_type: content_type.trim().to_lowercase(),
+        }
+    }
+
+    /// Return a hex SHA-256 digest, usable as a file name.
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.doi.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.content_type.as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+/// A stored response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CacheEntry {
+    /// The key the entry is stored under.
+    pub key: CacheKey,
+    /// HTTP status code; `404` entries are negative cache hits.
+    pub status: u16,
+    /// Response headers with lowercase names.
+    pub headers: BTreeMap<String, String>,
+    /// The `ETag` header, used for revalidation.
+    pub etag: Option<String>,
+    /// The raw response body.
+    pub body: String,
+    /// When the response was fetched or last revalidated.
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    /// Build an entry fetched now from a raw response.
+    pub fn from_raw(key: CacheKey, raw: RawResponse) -> Self {
+        Self {
+            key,
+            status: raw.status,
+            etag: raw.header("etag").map(str::to_string),
+            headers: raw.headers,
+            body: raw.body,
+            fetched_at: Utc::now(),
+        }
+    }
+
+    /// Return true for a cached "DOI not found" answer.
+    pub fn is_not_found(&self) -> bool {
+        self.status == 404
+    }
+}
+
+/// Storage backend for cached responses.
+pub trait CacheStore: Send + Sync {
+    /// Return the entry for a key, if stored.
+    fn get(&self, key: &CacheKey) -> Result<Option<CacheEntry>, CacheError>;
+
+    /// Store or replace an entry under its key.
+    fn put(&self, entry: &CacheEntry) -> Result<(), CacheError>;
+
+    /// Remove the entry for a key; missing entries are not an error.
+    fn remove(&self, key: &CacheKey) -> Result<(), CacheError>;
+}
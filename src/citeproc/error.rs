@@ -0,0 +1,30 @@
+use snafu::Snafu;
+
+/// Errors returned while loading styles and locales or rendering citations.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum CiteprocError {
+    #[snafu(display("Failed to parse XML at {stage}: {source}"))]
+    Xml {
+        stage: &'static str,
+        source: roxmltree::Error,
+    },
+
+    #[snafu(display("Invalid CSL at {stage}: {message}"))]
+    InvalidStyle {
+        stage: &'static str,
+        message: String,
+    },
+
+    #[snafu(display("Failed to read item at {stage}: {source}"))]
+    Item {
+        stage: &'static str,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Unknown citation item: {id}"))]
+    UnknownItem { id: String },
+
+    #[snafu(display("Style has no {section} section"))]
+    MissingSection { section: &'static str },
+}
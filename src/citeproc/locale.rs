@@ -0,0 +1,243 @@
+//! CSL locales: terms, date formats and ordinals.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use roxmltree::{Document, Node as XmlNode};
+use snafu::ResultExt;
+
+use crate::citeproc::error::*;
+use crate::citeproc::style::{DatePart, attr, children, parse_date_part, parsing_options};
+
+static EN_US: LazyLock<Locale> = LazyLock::new(|| {
+    Locale::parse(include_str!("../../locales/locales-en-US.xml")).expect("bundled en-US locale")
+});
+
+/// Form of a locale term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum TermForm {
+    Long,
+    Short,
+    Verb,
+    VerbShort,
+    Symbol,
+}
+
+impl TermForm {
+    pub(crate) fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("short") => TermForm::Short,
+            Some("verb") => TermForm::Verb,
+            Some("verb-short") => TermForm::VerbShort,
+            Some("symbol") => TermForm::Symbol,
+            _ => TermForm::Long,
+        }
+    }
+
+    /// The next form to try when a term is missing in this one.
+    fn fallback(self) -> Option<Self> {
+        match self {
+            TermForm::Long => None,
+            TermForm::Short | TermForm::Verb => Some(TermForm::Long),
+            TermForm::VerbShort => Some(TermForm::Verb),
+            TermForm::Symbol => Some(TermForm::Short),
+        }
+    }
+}
+
+/// Singular and plural forms of a term.
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    single: String,
+    multiple: String,
+}
+
+/// A CSL locale: terms, localized date formats and punctuation options.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    lang: Option<String>,
+    terms: HashMap<(String, TermForm), Term>,
+    dates: HashMap<String, Vec<DatePart>>,
+    punctuation_in_quote: Option<bool>,
+    limit_day_ordinals_to_day_1: Option<bool>,
+}
+
+impl Locale {
+    /// Parse a CSL locale file (`locales-xx-XX.xml`).
+    pub fn parse(xml: &str) -> Result<Self, CiteprocError> {
+        let document = Document::parse_with_options(xml, parsing_options()).context(XmlSnafu {
+            stage: "parse-locale",
+        })?;
+        let root = document.root_element();
+        if root.tag_name().name() != "locale" {
+            return InvalidStyleSnafu {
+                stage: "parse-locale",
+                message: format!("expected <locale>, found <{}>", root.tag_name().name()),
+            }
+            .fail();
+        }
+        Ok(Self::from_node(root))
+    }
+
+    /// Return the bundled `en-US` locale.
+    pub fn en_us() -> Self {
+        EN_US.clone()
+    }
+
+    /// Return the language tag, e.g. `en-US`.
+    pub fn lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    /// Read a `<locale>` element, either a locale file root or embedded in a style.
+    pub(crate) fn from_node(node: XmlNode<'_, '_>) -> Self {
+        let mut locale = Locale {
+            lang: node
+                .attribute(("http://www.w3.org/XML/1998/namespace", "lang"))
+                .map(str::to_string),
+            ..Default::default()
+        };
+        for child in node.children().filter(XmlNode::is_element) {
+            match child.tag_name().name() {
+                "style-options" => {
+                    locale.punctuation_in_quote =
+                        attr(child, "punctuation-in-quote").map(|value| value == "true");
+                    locale.limit_day_ordinals_to_day_1 =
+                        attr(child, "limit-day-ordinals-to-day-1").map(|value| value == "true");
+                }
+                "date" => {
+                    if let Some(form) = attr(child, "form") {
+                        let parts = children(child, "date-part").map(parse_date_part).collect();
+                        locale.dates.insert(form.to_string(), parts);
+                    }
+                }
+                "terms" => {
+                    for term in children(child, "term") {
+                        let Some(name) = attr(term, "name") else {
+                            continue;
+                        };
+                        let form = TermForm::parse(attr(term, "form"));
+                        let single = children(term, "single").next().map(text);
+                        let multiple = children(term, "multiple").next().map(text);
+                        let value = match (single, multiple) {
+                            (Some(single), Some(multiple)) => Term { single, multiple },
+                            (Some(single), None) => Term {
+                                multiple: single.clone(),
+                                single,
+                            },
+                            _ => {
+                                let value = text(term);
+                                Term {
+                                    single: value.clone(),
+                                    multiple: value,
+                                }
+                            }
+                        };
+                        locale.terms.insert((name.to_string(), form), value);
+                    }
+                }
+                _ => {}
+            }
+        }
+        locale
+    }
+
+    /// Layer `other` over this locale; its terms and options take precedence.
+    pub(crate) fn overlay(&mut self, other: &Locale) {
+        for (key, term) in &other.terms {
+            self.terms.insert(key.clone(), term.clone());
+        }
+        for (form, parts) in &other.dates {
+            self.dates.insert(form.clone(), parts.clone());
+        }
+        if other.punctuation_in_quote.is_some() {
+            self.punctuation_in_quote = other.punctuation_in_quote;
+        }
+        if other.limit_day_ordinals_to_day_1.is_some() {
+            self.limit_day_ordinals_to_day_1 = other.limit_day_ordinals_to_day_1;
+        }
+    }
+
+    /// Look up a term, falling back to longer forms.
+    pub(crate) fn term(&self, name: &str, form: TermForm, plural: bool) -> Option<&str> {
+        let mut form = Some(form);
+        while let Some(current) = form {
+            if let Some(term) = self.terms.get(&(name.to_string(), current)) {
+                return Some(if plural { &term.multiple } else { &term.single });
+            }
+            form = current.fallback();
+        }
+        None
+    }
+
+    /// Return the ordinal suffix form of a number, e.g. `21st`.
+    pub(crate) fn ordinal(&self, number: i64) -> String {
+        let two = number.rem_euclid(100);
+        let one = number.rem_euclid(10);
+        let suffix = (two >= 10)
+            .then(|| self.term(&format!("ordinal-{two:02}"), TermForm::Long, false))
+            .flatten()
+            .or_else(|| self.term(&format!("ordinal-{one:02}"), TermForm::Long, false))
+            .or_else(|| self.term("ordinal", TermForm::Long, false))
+            .unwrap_or_default();
+        format!("{number}{suffix}")
+    }
+
+    /// Return the long ordinal of 1 to 10 (`first`), or the short ordinal otherwise.
+    pub(crate) fn long_ordinal(&self, number: i64) -> String {
+        self.term(&format!("long-ordinal-{number:02}"), TermForm::Long, false)
+            .map(str::to_string)
+            .unwrap_or_else(|| self.ordinal(number))
+    }
+
+    /// Return the parts of a localized date format (`text` or `numeric`).
+    pub(crate) fn date_format(&self, form: &str) -> Option<&[DatePart]> {
+        self.dates.get(form).map(Vec::as_slice)
+    }
+
+    pub(crate) fn punctuation_in_quote(&self) -> bool {
+        self.punctuation_in_quote.unwrap_or(false)
+    }
+
+    pub(crate) fn limit_day_ordinals_to_day_1(&self) -> bool {
+        self.limit_day_ordinals_to_day_1.unwrap_or(false)
+    }
+}
+
+/// Return the concatenated text of an element.
+fn text(node: XmlNode<'_, '_>) -> String {
+    node.descendants()
+        .filter(XmlNode::is_text)
+        .filter_map(|node| node.text())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Reads terms with form fallback, ordinals and date formats from the bundled locale.
+    fn bundled_en_us() {
+        let locale = Locale::en_us();
+        assert_eq!(locale.lang(), Some("en-US"));
+        assert_eq!(locale.term("editor", TermForm::Short, true), Some("eds."));
+        assert_eq!(locale.term("page", TermForm::Short, true), Some("pp."));
+        assert_eq!(
+            locale.term("et-al", TermForm::VerbShort, false),
+            Some("et al.")
+        );
+        let ordinals: Vec<_> = [1, 2, 3, 4, 11, 12, 21, 102, 113]
+            .map(|number| locale.ordinal(number))
+            .to_vec();
+        assert_eq!(
+            ordinals,
+            [
+                "1st", "2nd", "3rd", "4th", "11th", "12th", "21st", "102nd", "113th"
+            ]
+        );
+        assert_eq!(locale.long_ordinal(2), "second");
+        assert_eq!(locale.date_format("text").map(<[_]>::len), Some(3));
+        assert!(locale.punctuation_in_quote());
+    }
+}
@@ -0,0 +1,514 @@
+//! A built-in CSL 1.0.2 citation processor.
+//!
+//! [`Processor`] renders [`DoiMetadata`](crate::DoiMetadata) items as
+//! bibliographies and in-text citations with any CSL [`Style`], such as the
+//! bundled APA, MLA, Chicago author-date, IEEE and Vancouver styles.
+
+pub mod error;
+pub mod locale;
+pub mod output;
+mod render;
+pub mod style;
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+use snafu::{OptionExt, ResultExt};
+
+use crate::citeproc::error::*;
+use crate::citeproc::locale::Locale;
+use crate::citeproc::output::{Node, OutputFormat, append, render};
+use crate::citeproc::render::{ItemContext, Renderer};
+use crate::citeproc::style::{Section, Style};
+use crate::csl::DoiMetadata;
+
+/// One cite inside a citation cluster.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CitationItem {
+    /// Id of an item added to the processor.
+    pub id: String,
+    /// Pinpoint locator, e.g. `12-14`.
+    pub locator: Option<String>,
+    /// Locator label term, e.g. `page` or `chapter` (defaults to `page`).
+    pub label: Option<String>,
+    /// Text placed before the rendered cite.
+    pub prefix: Option<String>,
+    /// Text placed after the rendered cite.
+    pub suffix: Option<String>,
+}
+
+impl CitationItem {
+    /// Cite an item by id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set a locator with its label term.
+    pub fn locator(mut self, label: &str, locator: &str) -> Self {
+        self.label = Some(label.to_string());
+        self.locator = Some(locator.to_string());
+        self
+    }
+}
+
+/// One rendered bibliography entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BibliographyEntry {
+    /// Id of the item.
+    pub id: String,
+    /// The entry in the requested output format.
+    pub text: String,
+}
+
+/// A rendered bibliography, in sorted order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bibliography {
+    pub entries: Vec<BibliographyEntry>,
+    format: OutputFormat,
+}
+
+impl fmt::Display for Bibliography {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.format {
+            OutputFormat::Html => {
+                writeln!(f, "<div class=\"csl-bib-body\">")?;
+                for entry in &self.entries {
+                    writeln!(f, "  <div class=\"csl-entry\">{}</div>", entry.text)?;
+                }
+                write!(f, "</div>")
+            }
+            OutputFormat::Text | OutputFormat::Markdown => {
+                let separator = match self.format {
+                    OutputFormat::Markdown => "\n\n",
+                    _ => "\n",
+                };
+                let entries: Vec<&str> = self.entries.iter().map(|e| e.text.as_str()).collect();
+                write!(f, "{}", entries.join(separator))
+            }
+        }
+    }
+}
+
+/// Renders citations and bibliographies for a set of items.
+#[derive(Debug, Clone)]
+pub struct Processor {
+    style: Style,
+    locale: Locale,
+    items: Vec<(String, Map<String, Value>)>,
+}
+
+impl Processor {
+    /// Create a processor using the bundled `en-US` locale and the style's own locale overrides.
+    pub fn new(style: Style) -> Self {
+        Self::with_locale(style, Locale::en_us())
+    }
+
+    /// Create a processor with a specific base locale.
+    pub fn with_locale(style: Style, locale: Locale) -> Self {
+        let mut locale = locale;
+        let lang = locale.lang().unwrap_or("en-US").to_string();
+        let language = lang.split('-').next().unwrap_or_default().to_string();
+        // Style locales apply from least to most specific: no xml:lang, language, full tag.
+        let rank = |candidate: &Locale| match candidate.lang() {
+            None => Some(0),
+            Some(tag) if tag == lang => Some(2),
+            Some(tag) if tag == language => Some(1),
+            Some(_) => None,
+        };
+        let mut overrides: Vec<_> = style
+            .locales
+            .iter()
+            .filter_map(|candidate| Some((rank(candidate)?, candidate)))
+            .collect();
+        overrides.sort_by_key(|(rank, _)| *rank);
+        for (_, candidate) in overrides {
+            locale.overlay(candidate);
+        }
+        Self {
+            style,
+            locale,
+            items: Vec::new(),
+        }
+    }
+
+    /// Return the style.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Add an item and return its id: the CSL `id`, else the DOI, else `item-N`.
+    pub fn add_item(&mut self, item: &DoiMetadata) -> Result<String, CiteprocError> {
+        let value = serde_json::to_value(item).context(ItemSnafu { stage: "add-item" })?;
+        let Value::Object(map) = value else {
+            return Ok(String::new());
+        };
+        let id = item
+            .id
+            .clone()
+            .or_else(|| item.doi.clone())
+            .unwrap_or_else(|| format!("item-{}", self.items.len() + 1));
+        self.items.retain(|(existing, _)| *existing != id);
+        self.items.push((id.clone(), map));
+        Ok(id)
+    }
+
+    /// Add several items, returning their ids.
+    pub fn add_items<'a>(
+        &mut self,
+        items: impl IntoIterator<Item = &'a DoiMetadata>,
+    ) -> Result<Vec<String>, CiteprocError> {
+        items.into_iter().map(|item| self.add_item(item)).collect()
+    }
+
+    /// Render the bibliography of every added item.
+    pub fn bibliography(&self, format: OutputFormat) -> Result<Bibliography, CiteprocError> {
+        let section = self
+            .style
+            .bibliography
+            .as_ref()
+            .context(MissingSectionSnafu {
+                section: "bibliography",
+            })?;
+        let renderer = Renderer::new(&self.style, &self.locale, section);
+        let order = self.sorted(section, &renderer);
+        let entries = order
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &item)| {
+                let (id, map) = &self.items[item];
+                let ctx = ItemContext::new(map, Some(index + 1));
+                let nodes = renderer.render(&section.layout.elements, &ctx);
+                let nodes = renderer.finish(nodes, &section.layout.rendering);
+                let text = self.output(&nodes, format);
+                (!text.is_empty()).then(|| BibliographyEntry {
+                    id: id.clone(),
+                    text,
+                })
+            })
+            .collect();
+        Ok(Bibliography { entries, format })
+    }
+
+    /// Render one citation cluster.
+    pub fn citation(
+        &self,
+        cites: &[CitationItem],
+        format: OutputFormat,
+    ) -> Result<String, CiteprocError> {
+        let section = self.style.citation.as_ref().context(MissingSectionSnafu {
+            section: "citation",
+        })?;
+        let renderer = Renderer::new(&self.style, &self.locale, section);
+        let numbers = self.citation_numbers();
+
+        let mut resolved = Vec::with_capacity(cites.len());
+        for cite in cites {
+            let index = self
+                .items
+                .iter()
+                .position(|(id, _)| *id == cite.id)
+                .context(UnknownItemSnafu { id: &cite.id })?;
+            resolved.push((index, cite));
+        }
+        if !section.sort.is_empty() {
+            resolved.sort_by(|(left, _), (right, _)| {
+                let left = ItemContext::new(&self.items[*left].1, Some(numbers[*left]));
+                let right = ItemContext::new(&self.items[*right].1, Some(numbers[*right]));
+                renderer.compare(&section.sort, &left, &right)
+            });
+        }
+
+        let mut nodes = Vec::new();
+        for (index, cite) in resolved {
+            let mut ctx = ItemContext::new(&self.items[index].1, Some(numbers[index]));
+            ctx.locator = cite
+                .locator
+                .as_deref()
+                .map(|locator| (cite.label.as_deref().unwrap_or("page"), locator));
+            let mut rendered = renderer.render(&section.layout.elements, &ctx);
+            if rendered.is_empty() {
+                continue;
+            }
+            if let Some(prefix) = &cite.prefix {
+                rendered.insert(0, Node::Text(prefix.clone()));
+            }
+            if let Some(suffix) = &cite.suffix {
+                rendered.push(Node::Text(suffix.clone()));
+            }
+            if let Some(delimiter) = &section.layout.delimiter
+                && !nodes.is_empty()
+            {
+                append(&mut nodes, vec![Node::Text(delimiter.clone())]);
+            }
+            append(&mut nodes, rendered);
+        }
+        let nodes = renderer.finish(nodes, &section.layout.rendering);
+        Ok(self.output(&nodes, format))
+    }
+
+    /// Item indices in bibliography order.
+    fn sorted(&self, section: &Section, renderer: &Renderer<'_>) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        if section.sort.is_empty() {
+            return order;
+        }
+        // Sort keys may use citation-number, which is the insertion order at this point.
+        let contexts: Vec<_> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, (_, map))| ItemContext::new(map, Some(index + 1)))
+            .collect();
+        order.sort_by(|left, right| {
+            renderer.compare(&section.sort, &contexts[*left], &contexts[*right])
+        });
+        order
+    }
+
+    /// Citation numbers per item index, following bibliography order when there is one.
+    fn citation_numbers(&self) -> Vec<usize> {
+        let mut numbers: Vec<usize> = (1..=self.items.len()).collect();
+        if let Some(section) = &self.style.bibliography {
+            let renderer = Renderer::new(&self.style, &self.locale, section);
+            for (position, item) in self.sorted(section, &renderer).into_iter().enumerate() {
+                numbers[item] = position + 1;
+            }
+        }
+        numbers
+    }
+
+    fn output(&self, nodes: &[Node], format: OutputFormat) -> String {
+        let text = render(nodes, format);
+        if !self.locale.punctuation_in_quote() {
+            return text;
+        }
+        text.replace("”.", ".”").replace("”,", ",”")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::citeproc::style::BundledStyle;
+
+    /// Return the body of a `>>===== NAME =====>>` section of a test-suite fixture.
+    fn section<'a>(fixture: &'a str, name: &str) -> Option<&'a str> {
+        let open = format!(">>===== {name} =====>>");
+        let close = format!("<<===== {name} =====<<");
+        let start = fixture.find(&open)? + open.len();
+        let end = fixture[start..].find(&close)? + start;
+        Some(fixture[start..end].trim())
+    }
+
+    fn article() -> DoiMetadata {
+        serde_json::from_value(serde_json::json!({
+            "id": "smith2020",
+            "type": "article-journal",
+            "title": "A study of things: why it matters",
+            "author": [
+                {"family": "Smith", "given": "John Adam"},
+                {"family": "Jones", "given": "Kate"}
+            ],
+            "container-title": "Journal of Studies",
+            "container-title-short": "J. Stud.",
+            "volume": "12",
+            "issue": "3",
+            "page": "45-67",
+            "issued": {"date-parts": [[2020, 3, 15]]},
+            "DOI": "10.1000/xyz123"
+        }))
+        .unwrap()
+    }
+
+    fn authorless() -> DoiMetadata {
+        serde_json::from_value(serde_json::json!({
+            "id": "anon",
+            "type": "article-journal",
+            "title": "Anonymous notes",
+            "container-title": "Journal of Studies",
+            "issued": {"date-parts": [[2020]], "season": 1}
+        }))
+        .unwrap()
+    }
+
+    /// Render a fixture and return its actual and expected output.
+    fn run_fixture(path: &Path) -> (String, String) {
+        let fixture = std::fs::read_to_string(path).unwrap();
+        let style = Style::parse(section(&fixture, "CSL").unwrap()).unwrap();
+        let items: Vec<DoiMetadata> =
+            serde_json::from_str(section(&fixture, "INPUT").unwrap()).unwrap();
+        let mut processor = Processor::new(style);
+        let ids = processor.add_items(&items).unwrap();
+
+        let actual = match section(&fixture, "MODE").unwrap() {
+            "citation" => {
+                let cites: Vec<_> = ids.into_iter().map(CitationItem::new).collect();
+                processor.citation(&cites, OutputFormat::Html).unwrap()
+            }
+            _ => processor
+                .bibliography(OutputFormat::Html)
+                .unwrap()
+                .to_string(),
+        };
+        (actual, section(&fixture, "RESULT").unwrap().to_string())
+    }
+
+    #[test]
+    /// Runs the fixtures in `fixtures/csl`; known test-suite failures must still fail.
+    fn csl_fixtures() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/csl");
+        let known = std::fs::read_to_string(root.join("test-suite/known-failures.txt")).unwrap();
+        let known: Vec<&str> = known
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        let mut paths = Vec::new();
+        for dir in ["local", "test-suite"] {
+            let entries = std::fs::read_dir(root.join(dir)).unwrap();
+            paths.extend(
+                entries
+                    .map(|entry| entry.unwrap().path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+                    .filter(|path| !path.ends_with("known-failures.txt")),
+            );
+        }
+        paths.sort();
+        assert!(!paths.is_empty());
+
+        for path in paths {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let (actual, expected) = run_fixture(&path);
+            if known.contains(&name.as_str()) {
+                assert_ne!(
+                    actual, expected,
+                    "{name} passes; remove it from known-failures.txt"
+                );
+            } else {
+                assert_eq!(actual, expected, "{name}");
+            }
+        }
+        for name in known {
+            assert!(
+                root.join(format!("test-suite/{name}.txt")).exists(),
+                "unknown fixture {name}"
+            );
+        }
+    }
+
+    #[test]
+    /// Renders a journal article in every bundled style.
+    fn bundled_styles() {
+        let expected = [
+            (
+                BundledStyle::Apa,
+                "Smith, J. A., & Jones, K. (2020). A study of things: why it matters. \
+                 Journal of Studies, 12(3), 45–67. https://doi.org/10.1000/xyz123",
+                "(Smith & Jones, 2020, p. 46)",
+            ),
+            (
+                BundledStyle::Mla,
+                "Smith, John Adam, and Kate Jones. “A study of things: why it matters.” \
+                 Journal of Studies, vol. 12, no. 3, 15 Mar. 2020, pp. 45–67, \
+                 https://doi.org/10.1000/xyz123.",
+                "(Smith and Jones 46)",
+            ),
+            (
+                BundledStyle::ChicagoAuthorDate,
+                "Smith, John Adam, and Kate Jones. 2020. “A Study of Things: Why It Matters.” \
+                 Journal of Studies 12 (3): 45–67. https://doi.org/10.1000/xyz123.",
+                "(Smith and Jones 2020, 46)",
+            ),
+            (
+                BundledStyle::Ieee,
+                "[1] J. A. Smith and K. Jones, “A study of things: why it matters,” J. Stud., \
+                 vol. 12, no. 3, pp. 45–67, Mar. 2020, doi: 10.1000/xyz123.",
+                "[1, p. 46]",
+            ),
+            (
+                BundledStyle::Vancouver,
+                "1. Smith JA, Jones K. A study of things: why it matters. J Stud. \
+                 2020;12(3):45–67. doi:10.1000/xyz123",
+                "(1)",
+            ),
+        ];
+        for (bundled, entry, cite) in expected {
+            let mut processor = Processor::new(Style::bundled(bundled));
+            let id = processor.add_item(&article()).unwrap();
+            let bibliography = processor.bibliography(OutputFormat::Text).unwrap();
+            assert_eq!(bibliography.to_string(), entry, "{}", bundled.as_str());
+            let citation = processor
+                .citation(
+                    &[CitationItem::new(id).locator("page", "46")],
+                    OutputFormat::Text,
+                )
+                .unwrap();
+            assert_eq!(citation, cite, "{}", bundled.as_str());
+        }
+    }
+
+    #[test]
+    /// Prints the title once when it stands in for a missing author.
+    fn bundled_styles_without_author() {
+        let expected = [
+            (
+                BundledStyle::Apa,
+                "Anonymous notes. (2020). Journal of Studies.",
+                "(Anonymous notes, 2020)",
+            ),
+            (
+                BundledStyle::Mla,
+                "“Anonymous notes.” Journal of Studies, Spring 2020.",
+                "(“Anonymous notes”)",
+            ),
+            (
+                BundledStyle::ChicagoAuthorDate,
+                "“Anonymous Notes.” 2020. Journal of Studies.",
+                "(Anonymous notes 2020)",
+            ),
+            (
+                BundledStyle::Ieee,
+                "[1] “Anonymous notes,” Journal of Studies, Spring 2020.",
+                "[1]",
+            ),
+            (
+                BundledStyle::Vancouver,
+                "1. Anonymous notes. Journal of Studies. 2020.",
+                "(1)",
+            ),
+        ];
+        for (bundled, entry, cite) in expected {
+            let mut processor = Processor::new(Style::bundled(bundled));
+            let id = processor.add_item(&authorless()).unwrap();
+            let bibliography = processor.bibliography(OutputFormat::Text).unwrap();
+            assert_eq!(bibliography.to_string(), entry, "{}", bundled.as_str());
+            let citation = processor
+                .citation(&[CitationItem::new(id)], OutputFormat::Text)
+                .unwrap();
+            assert_eq!(citation, cite, "{}", bundled.as_str());
+        }
+    }
+
+    #[test]
+    /// Emits italics as Markdown and reports unknown cites.
+    fn markdown_and_unknown_items() {
+        let mut processor = Processor::new(Style::bundled(BundledStyle::Apa));
+        processor.add_item(&article()).unwrap();
+        let bibliography = processor.bibliography(OutputFormat::Markdown).unwrap();
+        assert!(
+            bibliography
+                .to_string()
+                .contains("*Journal of Studies*, *12*(3)")
+        );
+        let error = processor
+            .citation(&[CitationItem::new("missing")], OutputFormat::Text)
+            .unwrap_err();
+        assert!(matches!(error, CiteprocError::UnknownItem { .. }));
+    }
+}
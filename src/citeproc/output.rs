@@ -0,0 +1,356 @@
+//! Formatted output trees and their plain text, HTML and Markdown forms.
+
+/// Output markup for rendered citations and bibliographies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Plain text without markup.
+    #[default]
+    Text,
+    /// HTML, using the markup of the CSL test suite.
+    Html,
+    /// Markdown (`*italic*`, `**bold**`).
+    Markdown,
+}
+
+/// CSL formatting attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Formatting {
+    pub italic: bool,
+    pub bold: bool,
+    pub small_caps: bool,
+    pub underline: bool,
+    pub superscript: bool,
+    pub subscript: bool,
+}
+
+impl Formatting {
+    pub(crate) fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A rendered fragment.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    Text(String),
+    Styled(Formatting, Vec<Node>),
+    /// A `display` block, e.g. `block` or `indent`.
+    Display(String, Vec<Node>),
+    /// Markup from the rich text of a variable value.
+    Markup(Markup, Vec<Node>),
+}
+
+/// The HTML-like tags CSL-JSON allows inside string values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Markup {
+    /// `<i>`, flipped to upright inside italics.
+    Italic,
+    /// `<b>`, flipped to normal weight inside bold.
+    Bold,
+    /// `<sup>`.
+    Superscript,
+    /// `<sub>`.
+    Subscript,
+    /// `<span class="nocase">`, exempt from text-case.
+    NoCase,
+}
+
+impl Markup {
+    const TAGS: [(&'static str, &'static str, Markup); 5] = [
+        ("<i>", "</i>", Markup::Italic),
+        ("<b>", "</b>", Markup::Bold),
+        ("<sup>", "</sup>", Markup::Superscript),
+        ("<sub>", "</sub>", Markup::Subscript),
+        ("<span class=\"nocase\">", "</span>", Markup::NoCase),
+    ];
+
+    fn open_tag(self) -> &'static str {
+        Self::TAGS.iter().find(|tag| tag.2 == self).unwrap().0
+    }
+
+    fn close_tag(self) -> &'static str {
+        Self::TAGS.iter().find(|tag| tag.2 == self).unwrap().1
+    }
+}
+
+/// Parse the rich text of a variable value into nodes.
+///
+/// Unknown tags and tags left unbalanced stay literal text, so values such
+/// as SICI DOIs (`…5:1<1::AID-JIB1>3.0.CO;2-8`) pass through unchanged.
+pub(crate) fn rich_text(text: &str) -> Vec<Node> {
+    // Each frame is an open tag and the nodes collected since it.
+    let mut stack: Vec<(Option<Markup>, Vec<Node>)> = vec![(None, Vec::new())];
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        literal.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let top = stack.last().and_then(|(markup, _)| *markup);
+        if let Some(markup) = top.filter(|markup| rest.starts_with(markup.close_tag())) {
+            push_text(&mut stack, &mut literal);
+            let (_, children) = stack.pop().unwrap();
+            let parent = &mut stack.last_mut().unwrap().1;
+            parent.push(Node::Markup(markup, children));
+            rest = &rest[markup.close_tag().len()..];
+        } else if let Some(&(open, _, markup)) = Markup::TAGS
+            .iter()
+            .find(|(open, _, _)| rest.starts_with(open))
+        {
+            push_text(&mut stack, &mut literal);
+            stack.push((Some(markup), Vec::new()));
+            rest = &rest[open.len()..];
+        } else {
+            literal.push('<');
+            rest = &rest[1..];
+        }
+    }
+    literal.push_str(rest);
+    push_text(&mut stack, &mut literal);
+    // Unclosed tags fall back to their literal text.
+    while stack.len() > 1 {
+        let (markup, children) = stack.pop().unwrap();
+        let parent = &mut stack.last_mut().unwrap().1;
+        let tag = markup.map(Markup::open_tag).unwrap_or_default();
+        parent.push(Node::Text(tag.to_string()));
+        parent.extend(children);
+    }
+    stack.pop().unwrap().1
+}
+
+fn push_text(stack: &mut [(Option<Markup>, Vec<Node>)], literal: &mut String) {
+    if !literal.is_empty() {
+        let nodes = &mut stack.last_mut().unwrap().1;
+        nodes.push(Node::Text(std::mem::take(literal)));
+    }
+}
+
+/// Return the plain text of nodes.
+pub(crate) fn plain(nodes: &[Node]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Styled(_, children) | Node::Display(_, children) | Node::Markup(_, children) => {
+                out.push_str(&plain(children))
+            }
+        }
+    }
+    out
+}
+
+/// Append `next` to `nodes`, dropping doubled punctuation at the seam
+/// (e.g. `Smith, J.` followed by `.` or `Why?` followed by `.`).
+pub(crate) fn append(nodes: &mut Vec<Node>, mut next: Vec<Node>) {
+    if next.is_empty() {
+        return;
+    }
+    let left = plain(nodes);
+    let right = plain(&next);
+    if let (Some(end), Some('.')) = (left.chars().last(), right.chars().next())
+        && matches!(end, '.' | '?' | '!')
+    {
+        strip_first_char(&mut next);
+    }
+    nodes.extend(next);
+}
+
+/// Remove the first character of the first non-empty text node.
+fn strip_first_char(nodes: &mut [Node]) -> bool {
+    for node in nodes {
+        let done = match node {
+            Node::Text(text) if text.is_empty() => false,
+            Node::Text(text) => {
+                text.remove(0);
+                true
+            }
+            Node::Styled(_, children) | Node::Display(_, children) | Node::Markup(_, children) => {
+                strip_first_char(children)
+            }
+        };
+        if done {
+            return true;
+        }
+    }
+    false
+}
+
+/// Apply a function to every text leaf.
+pub(crate) fn map_text(nodes: &mut [Node], f: &mut impl FnMut(&str) -> String) {
+    map_text_where(nodes, &mut |_| true, &mut |text, _| f(text));
+}
+
+/// Apply a function to every text leaf, telling it whether the leaf is
+/// inside markup the filter rejects (e.g. `nocase` spans).
+pub(crate) fn map_text_where(
+    nodes: &mut [Node],
+    keep: &mut impl FnMut(Markup) -> bool,
+    f: &mut impl FnMut(&str, bool) -> String,
+) {
+    fn walk(
+        nodes: &mut [Node],
+        keep: &mut impl FnMut(Markup) -> bool,
+        f: &mut impl FnMut(&str, bool) -> String,
+        excluded: bool,
+    ) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => *text = f(text, excluded),
+                Node::Styled(_, children) | Node::Display(_, children) => {
+                    walk(children, keep, f, excluded)
+                }
+                Node::Markup(markup, children) => {
+                    let excluded = excluded || !keep(*markup);
+                    walk(children, keep, f, excluded)
+                }
+            }
+        }
+    }
+    walk(nodes, keep, f, false);
+}
+
+/// Render nodes in an output format.
+pub(crate) fn render(nodes: &[Node], format: OutputFormat) -> String {
+    render_within(nodes, format, Formatting::default())
+}
+
+/// Render nodes inside the formatting of their ancestors, which decides how
+/// italic and bold markup flip.
+fn render_within(nodes: &[Node], format: OutputFormat, outer: Formatting) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match (node, format) {
+            (Node::Text(text), OutputFormat::Text) => out.push_str(text),
+            (Node::Text(text), OutputFormat::Html) => out.push_str(&escape_html(text)),
+            (Node::Text(text), OutputFormat::Markdown) => out.push_str(&escape_markdown(text)),
+            (Node::Styled(formatting, children), _) => {
+                let inner = Formatting {
+                    italic: outer.italic || formatting.italic,
+                    bold: outer.bold || formatting.bold,
+                    ..outer
+                };
+                let inner = render_within(children, format, inner);
+                out.push_str(&style(*formatting, inner, format));
+            }
+            (Node::Markup(markup, children), _) => {
+                out.push_str(&markup_style(*markup, children, format, outer));
+            }
+            (Node::Display(class, children), OutputFormat::Html) => {
+                out.push_str(&format!(
+                    "<div class=\"csl-{class}\">{}</div>",
+                    render_within(children, format, outer)
+                ));
+            }
+            (Node::Display(_, children), _) => {
+                out.push_str(&render_within(children, format, outer))
+            }
+        }
+    }
+    out
+}
+
+/// Render rich-text markup; italics inside italics turn upright and bold
+/// inside bold turns normal, as citeproc-js does.
+fn markup_style(
+    markup: Markup,
+    children: &[Node],
+    format: OutputFormat,
+    outer: Formatting,
+) -> String {
+    let (formatting, flipped) = match markup {
+        Markup::Italic => (
+            Formatting {
+                italic: !outer.italic,
+                ..outer
+            },
+            outer.italic.then_some("font-style:normal;"),
+        ),
+        Markup::Bold => (
+            Formatting {
+                bold: !outer.bold,
+                ..outer
+            },
+            outer.bold.then_some("font-weight:normal;"),
+        ),
+        Markup::Superscript | Markup::Subscript | Markup::NoCase => (outer, None),
+    };
+    let inner = render_within(children, format, formatting);
+    if inner.is_empty() {
+        return inner;
+    }
+    match (markup, format, flipped) {
+        (_, OutputFormat::Text, _) | (Markup::NoCase, _, _) => inner,
+        (_, OutputFormat::Html, Some(css)) => format!("<span style=\"{css}\">{inner}</span>"),
+        (_, OutputFormat::Markdown, Some(_)) => inner,
+        (Markup::Italic, OutputFormat::Html, None) => format!("<i>{inner}</i>"),
+        (Markup::Bold, OutputFormat::Html, None) => format!("<b>{inner}</b>"),
+        (Markup::Superscript, OutputFormat::Html, _) => format!("<sup>{inner}</sup>"),
+        (Markup::Subscript, OutputFormat::Html, _) => format!("<sub>{inner}</sub>"),
+        (Markup::Italic, OutputFormat::Markdown, None) => format!("*{inner}*"),
+        (Markup::Bold, OutputFormat::Markdown, None) => format!("**{inner}**"),
+        (Markup::Superscript | Markup::Subscript, OutputFormat::Markdown, _) => inner,
+    }
+}
+
+fn style(formatting: Formatting, mut inner: String, format: OutputFormat) -> String {
+    if inner.is_empty() {
+        return inner;
+    }
+    match format {
+        OutputFormat::Text => inner,
+        OutputFormat::Html => {
+            // Nested from the inside out, matching citeproc-js output.
+            if formatting.small_caps {
+                inner = format!("<span style=\"font-variant:small-caps;\">{inner}</span>");
+            }
+            if formatting.underline {
+                inner = format!("<span style=\"text-decoration:underline;\">{inner}</span>");
+            }
+            if formatting.italic {
+                inner = format!("<i>{inner}</i>");
+            }
+            if formatting.bold {
+                inner = format!("<b>{inner}</b>");
+            }
+            if formatting.superscript {
+                inner = format!("<sup>{inner}</sup>");
+            }
+            if formatting.subscript {
+                inner = format!("<sub>{inner}</sub>");
+            }
+            inner
+        }
+        OutputFormat::Markdown => {
+            if formatting.italic {
+                inner = format!("*{inner}*");
+            }
+            if formatting.bold {
+                inner = format!("**{inner}**");
+            }
+            inner
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&#38;"),
+            '<' => out.push_str("&#60;"),
+            '>' => out.push_str("&#62;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '[' | ']' | '`') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
@@ -0,0 +1,1064 @@
+//! Evaluation of style elements against one item.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::citeproc::locale::{Locale, TermForm};
+use crate::citeproc::output::{Markup, Node, append, map_text, map_text_where, plain, rich_text};
+use crate::citeproc::style::*;
+use crate::csl::{CslDate, CslName, DateParts, PageRange};
+
+static NUMERIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\p{L}*\d+\p{L}*(?:\s*(?:[,&–-]|and)\s*\p{L}*\d+\p{L}*)*$").unwrap()
+});
+
+/// Words kept lowercase by `text-case="title"` unless first or last.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "down", "for", "from", "in", "into", "nor", "of",
+    "on", "onto", "or", "over", "so", "the", "till", "to", "up", "via", "with", "yet",
+];
+
+const NAME_VARIABLES: &[&str] = &[
+    "author",
+    "chair",
+    "collection-editor",
+    "compiler",
+    "composer",
+    "container-author",
+    "contributor",
+    "curator",
+    "director",
+    "editor",
+    "editorial-director",
+    "executive-producer",
+    "guest",
+    "host",
+    "illustrator",
+    "interviewer",
+    "narrator",
+    "organizer",
+    "original-author",
+    "performer",
+    "producer",
+    "recipient",
+    "reviewed-author",
+    "script-writer",
+    "series-creator",
+    "translator",
+];
+
+const DATE_VARIABLES: &[&str] = &[
+    "accessed",
+    "available-date",
+    "event-date",
+    "issued",
+    "original-date",
+    "submitted",
+];
+
+/// One item being rendered, with citation-specific values.
+pub(crate) struct ItemContext<'a> {
+    pub item: &'a Map<String, Value>,
+    pub citation_number: Option<usize>,
+    pub locator: Option<(&'a str, &'a str)>,
+    /// Variables already used by a `<substitute>`, which render empty afterwards.
+    pub suppressed: RefCell<HashSet<String>>,
+    /// Variables that returned a value while rendering, in order.
+    pub used: RefCell<Vec<String>>,
+}
+
+impl<'a> ItemContext<'a> {
+    pub(crate) fn new(item: &'a Map<String, Value>, citation_number: Option<usize>) -> Self {
+        Self {
+            item,
+            citation_number,
+            locator: None,
+            suppressed: RefCell::default(),
+            used: RefCell::default(),
+        }
+    }
+}
+
+/// Rendered nodes plus the variable bookkeeping needed for group suppression.
+#[derive(Debug, Default)]
+struct Out {
+    nodes: Vec<Node>,
+    called: bool,
+    rendered: bool,
+}
+
+impl Out {
+    fn variable(nodes: Vec<Node>) -> Self {
+        let rendered = !nodes.is_empty();
+        Self {
+            nodes,
+            called: true,
+            rendered,
+        }
+    }
+
+    fn plain(nodes: Vec<Node>) -> Self {
+        Self {
+            nodes,
+            ..Default::default()
+        }
+    }
+}
+
+/// Renders elements of one style section.
+pub(crate) struct Renderer<'a> {
+    pub style: &'a Style,
+    pub locale: &'a Locale,
+    pub name_options: NameOptions,
+}
+
+impl<'a> Renderer<'a> {
+    pub(crate) fn new(style: &'a Style, locale: &'a Locale, section: &Section) -> Self {
+        Self {
+            style,
+            locale,
+            name_options: style.name_options.overlay(&section.name_options),
+        }
+    }
+
+    /// Render a sequence of elements without delimiters.
+    pub(crate) fn render(&self, elements: &[Element], ctx: &ItemContext<'_>) -> Vec<Node> {
+        self.sequence(elements, ctx).nodes
+    }
+
+    /// Apply rendering attributes around nodes.
+    pub(crate) fn finish(&self, mut nodes: Vec<Node>, rendering: &Rendering) -> Vec<Node> {
+        if plain(&nodes).is_empty() {
+            return Vec::new();
+        }
+        if rendering.strip_periods {
+            map_text(&mut nodes, &mut |text| text.replace('.', ""));
+        }
+        if let Some(case) = rendering.text_case {
+            apply_case(&mut nodes, case);
+        }
+        if rendering.quotes {
+            let open = self.term_or("open-quote", "“");
+            let close = self.term_or("close-quote", "”");
+            nodes.insert(0, Node::Text(open));
+            nodes.push(Node::Text(close));
+        }
+        if !rendering.formatting.is_plain() {
+            nodes = vec![Node::Styled(rendering.formatting, nodes)];
+        }
+        let mut out = Vec::new();
+        if let Some(prefix) = &rendering.prefix {
+            out.push(Node::Text(prefix.clone()));
+        }
+        append(&mut out, nodes);
+        if let Some(suffix) = &rendering.suffix {
+            append(&mut out, vec![Node::Text(suffix.clone())]);
+        }
+        match &rendering.display {
+            Some(display) => vec![Node::Display(display.clone(), out)],
+            None => out,
+        }
+    }
+
+    /// Compare two items by sort keys.
+    pub(crate) fn compare(
+        &self,
+        keys: &[SortKey],
+        left: &ItemContext<'_>,
+        right: &ItemContext<'_>,
+    ) -> Ordering {
+        for key in keys {
+            let ascending = match key {
+                SortKey::Variable { ascending, .. } | SortKey::Macro { ascending, .. } => {
+                    *ascending
+                }
+            };
+            let (a, b) = (self.sort_value(key, left), self.sort_value(key, right));
+            // Empty values sort last in both directions.
+            let ordering = match (a.is_empty(), b.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                _ if ascending => a.cmp(&b),
+                _ => b.cmp(&a),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+
+    fn sort_value(&self, key: &SortKey, ctx: &ItemContext<'_>) -> String {
+        match key {
+            SortKey::Macro { name, .. } => {
+                let elements = self.style.macros.get(name).map(Vec::as_slice);
+                let text = plain(&self.render(elements.unwrap_or_default(), ctx));
+                sort_text(&text)
+            }
+            SortKey::Variable { variable, .. } => {
+                if NAME_VARIABLES.contains(&variable.as_str()) {
+                    self.names(ctx, variable)
+                        .iter()
+                        .map(|name| {
+                            let parts = [&name.family, &name.given, &name.literal];
+                            parts
+                                .into_iter()
+                                .flatten()
+                                .cloned()
+                                .collect::<Vec<_>>()
+                                .join(" ")
+                        })
+                        .map(|name| sort_text(&name))
+                        .collect::<Vec<_>>()
+                        .join("  ")
+                } else if DATE_VARIABLES.contains(&variable.as_str()) {
+                    self.date(ctx, variable)
+                        .and_then(|date| date.start())
+                        .map(|parts| {
+                            format!(
+                                "{:06}{:02}{:02}",
+                                i64::from(parts.year()) + 100_000,
+                                parts.month().unwrap_or_default(),
+                                parts.day().unwrap_or_default()
+                            )
+                        })
+                        .unwrap_or_default()
+                } else {
+                    let value = self.variable(ctx, variable, false).unwrap_or_default();
+                    match value.trim().parse::<i64>() {
+                        Ok(number) => format!("{:020}", i128::from(number) + i128::from(i64::MAX)),
+                        Err(_) => sort_text(&value),
+                    }
+                }
+            }
+        }
+    }
+
+    fn sequence(&self, elements: &[Element], ctx: &ItemContext<'_>) -> Out {
+        let mut out = Out::default();
+        for element in elements {
+            let next = self.element(element, ctx);
+            out.called |= next.called;
+            out.rendered |= next.rendered;
+            append(&mut out.nodes, next.nodes);
+        }
+        out
+    }
+
+    fn element(&self, element: &Element, ctx: &ItemContext<'_>) -> Out {
+        match element {
+            Element::Text(text) => self.text(text, ctx),
+            Element::Number(number) => {
+                let value = self.variable(ctx, &number.variable, false);
+                let nodes = value
+                    .map(|value| self.number(&value, number.form))
+                    .map(|value| self.finish(vec![Node::Text(value)], &number.rendering))
+                    .unwrap_or_default();
+                Out::variable(nodes)
+            }
+            Element::Label(label) => {
+                let nodes = label
+                    .variable
+                    .as_deref()
+                    .and_then(|variable| self.label(label, variable, ctx, None))
+                    .unwrap_or_default();
+                Out::plain(nodes)
+            }
+            Element::Date(date) => Out::variable(self.render_date(date, ctx)),
+            Element::Names(names) => self.render_names(names, ctx, None),
+            Element::Group(group) => {
+                let mut out = Out::default();
+                for child in &group.children {
+                    let next = self.element(child, ctx);
+                    out.called |= next.called;
+                    out.rendered |= next.rendered;
+                    if next.nodes.is_empty() {
+                        continue;
+                    }
+                    if let Some(delimiter) = &group.delimiter
+                        && !out.nodes.is_empty()
+                    {
+                        append(&mut out.nodes, vec![Node::Text(delimiter.clone())]);
+                    }
+                    append(&mut out.nodes, next.nodes);
+                }
+                if out.called && !out.rendered {
+                    return Out {
+                        nodes: Vec::new(),
+                        ..out
+                    };
+                }
+                out.nodes = self.finish(out.nodes, &group.rendering);
+                out
+            }
+            Element::Choose(branches) => branches
+                .iter()
+                .find(|branch| self.matches(branch, ctx))
+                .map(|branch| self.sequence(&branch.children, ctx))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn text(&self, text: &TextElement, ctx: &ItemContext<'_>) -> Out {
+        match &text.source {
+            TextSource::Variable { name, short } => {
+                let nodes = self
+                    .variable(ctx, name, *short)
+                    .map(|value| self.finish(rich_text(&value), &text.rendering))
+                    .unwrap_or_default();
+                Out::variable(nodes)
+            }
+            TextSource::Macro(name) => {
+                let elements = self.style.macros.get(name).map(Vec::as_slice);
+                let out = self.sequence(elements.unwrap_or_default(), ctx);
+                Out {
+                    nodes: self.finish(out.nodes, &text.rendering),
+                    ..out
+                }
+            }
+            TextSource::Term { name, form, plural } => {
+                let value = self.locale.term(name, *form, *plural).unwrap_or_default();
+                Out::plain(self.finish(vec![Node::Text(value.to_string())], &text.rendering))
+            }
+            TextSource::Value(value) => {
+                Out::plain(self.finish(vec![Node::Text(value.clone())], &text.rendering))
+            }
+        }
+    }
+
+    /// Return a string or number variable.
+    fn variable(&self, ctx: &ItemContext<'_>, name: &str, short: bool) -> Option<String> {
+        if ctx.suppressed.borrow().contains(name) {
+            return None;
+        }
+        let value = match name {
+            "citation-number" => ctx.citation_number.map(|number| number.to_string()),
+            "locator" => ctx.locator.map(|(_, value)| value.to_string()),
+            "page" | "locator-page" => self.raw(ctx, "page").map(|page| {
+                let delimiter = self.term_or("page-range-delimiter", "–");
+                PageRange::parse(&page)
+                    .map(|range| range.format(self.style.page_range_format, &delimiter))
+                    .unwrap_or(page)
+            }),
+            "title" if short => self
+                .raw(ctx, "title-short")
+                .or_else(|| self.raw(ctx, "shortTitle"))
+                .or_else(|| self.raw(ctx, "title")),
+            "container-title" if short => self
+                .raw(ctx, "container-title-short")
+                .or_else(|| self.raw(ctx, "journalAbbreviation"))
+                .or_else(|| self.raw(ctx, "container-title")),
+            _ => self.raw(ctx, name),
+        };
+        let value = value.filter(|value| !value.trim().is_empty());
+        if value.is_some() {
+            ctx.used.borrow_mut().push(name.to_string());
+        }
+        value
+    }
+
+    /// Return an item field as a string, taking the first entry of arrays.
+    fn raw(&self, ctx: &ItemContext<'_>, name: &str) -> Option<String> {
+        match ctx.item.get(name)? {
+            Value::String(text) => Some(text.trim().to_string()),
+            Value::Number(number) => Some(number.to_string()),
+            Value::Array(values) => values.iter().find_map(|value| match value {
+                Value::String(text) => Some(text.trim().to_string()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    fn names(&self, ctx: &ItemContext<'_>, variable: &str) -> Vec<CslName> {
+        if ctx.suppressed.borrow().contains(variable) {
+            return Vec::new();
+        }
+        let names: Vec<CslName> = ctx
+            .item
+            .get(variable)
+            .cloned()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+        if !names.is_empty() {
+            ctx.used.borrow_mut().push(variable.to_string());
+        }
+        names
+    }
+
+    fn date(&self, ctx: &ItemContext<'_>, variable: &str) -> Option<CslDate> {
+        if ctx.suppressed.borrow().contains(variable) {
+            return None;
+        }
+        let value = ctx.item.get(variable)?.clone();
+        let date: CslDate = serde_json::from_value(value).ok()?;
+        ctx.used.borrow_mut().push(variable.to_string());
+        Some(date)
+    }
+
+    fn term_or(&self, name: &str, fallback: &str) -> String {
+        self.locale
+            .term(name, TermForm::Long, false)
+            .unwrap_or(fallback)
+            .to_string()
+    }
+
+    fn number(&self, value: &str, form: NumberForm) -> String {
+        let Ok(number) = value.trim().parse::<i64>() else {
+            return value.to_string();
+        };
+        match form {
+            NumberForm::Numeric => number.to_string(),
+            NumberForm::Ordinal => self.locale.ordinal(number),
+            NumberForm::LongOrdinal => self.locale.long_ordinal(number),
+            NumberForm::Roman => roman(number).unwrap_or_else(|| number.to_string()),
+        }
+    }
+
+    /// Render a `<label>` for a variable; `count` overrides plural detection for names.
+    fn label(
+        &self,
+        label: &LabelElement,
+        variable: &str,
+        ctx: &ItemContext<'_>,
+        count: Option<usize>,
+    ) -> Option<Vec<Node>> {
+        let (term, plural) = match count {
+            Some(count) => (variable.to_string(), count > 1),
+            None => {
+                let value = self.variable(ctx, variable, false)?;
+                let term = match variable {
+                    "locator" => ctx.locator.map_or("page", |(label, _)| label).to_string(),
+                    "number-of-pages" | "page" => "page".to_string(),
+                    "number-of-volumes" => "volume".to_string(),
+                    other => other.to_string(),
+                };
+                let plural = match variable {
+                    "number-of-pages" | "number-of-volumes" => {
+                        value.trim().parse::<i64>().is_ok_and(|number| number > 1)
+                    }
+                    _ => value.contains([',', '&', '-', '–']) || value.contains(" and "),
+                };
+                (term, plural)
+            }
+        };
+        let plural = match label.plural {
+            Plural::Always => true,
+            Plural::Never => false,
+            Plural::Contextual => plural,
+        };
+        let text = self.locale.term(&term, label.form, plural)?;
+        Some(self.finish(vec![Node::Text(text.to_string())], &label.rendering))
+    }
+
+    fn matches(&self, branch: &Branch, ctx: &ItemContext<'_>) -> bool {
+        if branch.tests.is_empty() {
+            return true;
+        }
+        // Testing a variable does not count as using it.
+        let used = ctx.used.borrow().len();
+        let mut results = branch.tests.iter().map(|test| self.test(test, ctx));
+        let matched = match branch.match_mode {
+            Match::All => results.all(|result| result),
+            Match::Any => results.any(|result| result),
+            Match::None => !results.any(|result| result),
+        };
+        ctx.used.borrow_mut().truncate(used);
+        matched
+    }
+
+    fn test(&self, test: &Test, ctx: &ItemContext<'_>) -> bool {
+        match test {
+            Test::Type(item_type) => {
+                ctx.item.get("type").and_then(Value::as_str) == Some(item_type.as_str())
+            }
+            Test::Variable(variable) => {
+                if NAME_VARIABLES.contains(&variable.as_str()) {
+                    !self.names(ctx, variable).is_empty()
+                } else if DATE_VARIABLES.contains(&variable.as_str()) {
+                    self.date(ctx, variable).is_some()
+                } else {
+                    self.variable(ctx, variable, false).is_some()
+                }
+            }
+            Test::IsNumeric(variable) => self
+                .variable(ctx, variable, false)
+                .is_some_and(|value| NUMERIC.is_match(value.trim())),
+            Test::IsUncertainDate(variable) => {
+                self.date(ctx, variable).is_some_and(|date| date.is_circa())
+            }
+            // Citations are not tracked across a document, so every cite is a first cite.
+            Test::Position(position) => position == "first",
+            Test::Locator(expected) => ctx.locator.is_some_and(|(label, _)| label == expected),
+            Test::Disambiguate(value) => !*value,
+        }
+    }
+
+    fn render_date(&self, element: &DateElement, ctx: &ItemContext<'_>) -> Vec<Node> {
+        let Some(date) = self.date(ctx, &element.variable) else {
+            return Vec::new();
+        };
+        if let Some(literal) = date.literal.as_deref().filter(|value| !value.is_empty()) {
+            return self.finish(vec![Node::Text(literal.to_string())], &element.rendering);
+        }
+        let Some(start) = date.start() else {
+            return Vec::new();
+        };
+
+        let (parts, delimiter) = match &element.form {
+            Some(form) => {
+                let localized = self.locale.date_format(form).unwrap_or_default();
+                let parts: Vec<DatePart> = localized
+                    .iter()
+                    .filter(|part| match part.name.as_str() {
+                        "month" => element.parts_filter >= DatePartsFilter::YearMonth,
+                        "day" => element.parts_filter >= DatePartsFilter::YearMonthDay,
+                        _ => true,
+                    })
+                    .map(|part| override_part(part, &element.parts))
+                    .collect();
+                (parts, None)
+            }
+            None => (element.parts.clone(), element.delimiter.as_deref()),
+        };
+
+        let season = date.season();
+        let render_parts = |date_parts: &DateParts, only: Option<&[&str]>| {
+            let mut nodes = Vec::new();
+            for part in &parts {
+                if only.is_some_and(|only| !only.contains(&part.name.as_str())) {
+                    continue;
+                }
+                let rendered = self.date_part(part, date_parts, season);
+                if rendered.is_empty() {
+                    continue;
+                }
+                if let Some(delimiter) = delimiter
+                    && !nodes.is_empty()
+                {
+                    append(&mut nodes, vec![Node::Text(delimiter.to_string())]);
+                }
+                append(&mut nodes, rendered);
+            }
+            nodes
+        };
+
+        let mut nodes = render_parts(&start, None);
+        if let Some(end) = date.end().filter(|end| *end != start) {
+            let largest = if end.year() != start.year() {
+                "year"
+            } else if end.month() != start.month() {
+                "month"
+            } else {
+                "day"
+            };
+            let delimiter = parts
+                .iter()
+                .find(|part| part.name == largest)
+                .and_then(|part| part.range_delimiter.clone())
+                .unwrap_or_else(|| "–".to_string());
+            append(&mut nodes, vec![Node::Text(delimiter)]);
+            append(&mut nodes, render_parts(&end, None));
+        }
+        self.finish(nodes, &element.rendering)
+    }
+
+    fn date_part(
+        &self,
+        part: &DatePart,
+        date: &DateParts,
+        season: Option<crate::csl::Season>,
+    ) -> Vec<Node> {
+        let form = part.form.as_deref();
+        let text = match part.name.as_str() {
+            "year" => {
+                let year = date.year();
+                match (form, year) {
+                    (_, year) if year < 0 => {
+                        let bc = self.term_or("bc", "BC");
+                        format!("{}{bc}", -year)
+                    }
+                    (Some("short"), year) => format!("{:02}", year % 100),
+                    (_, year) => year.to_string(),
+                }
+            }
+            "month" => match (date.month(), season.or_else(|| date.season())) {
+                (Some(month @ 1..=12), _) => match form {
+                    Some("numeric") => month.to_string(),
+                    Some("numeric-leading-zeros") => format!("{month:02}"),
+                    Some("short") => self.month_term(month, TermForm::Short),
+                    _ => self.month_term(month, TermForm::Long),
+                },
+                (_, Some(season)) => {
+                    let index = match season {
+                        crate::csl::Season::Spring => 1,
+                        crate::csl::Season::Summer => 2,
+                        crate::csl::Season::Autumn => 3,
+                        crate::csl::Season::Winter => 4,
+                    };
+                    self.term_or(&format!("season-{index:02}"), "")
+                }
+                _ => String::new(),
+            },
+            "day" => match date.day() {
+                Some(day) => match form {
+                    Some("numeric-leading-zeros") => format!("{day:02}"),
+                    Some("ordinal") if !self.locale.limit_day_ordinals_to_day_1() || day == 1 => {
+                        self.locale.ordinal(i64::from(day))
+                    }
+                    _ => day.to_string(),
+                },
+                None => String::new(),
+            },
+            _ => String::new(),
+        };
+        if text.is_empty() {
+            return Vec::new();
+        }
+        self.finish(vec![Node::Text(text)], &part.rendering)
+    }
+
+    fn month_term(&self, month: u8, form: TermForm) -> String {
+        self.locale
+            .term(&format!("month-{month:02}"), form, false)
+            .map(str::to_string)
+            .unwrap_or_else(|| month.to_string())
+    }
+
+    /// Render `<names>`; `inherit` carries the parent element inside `<substitute>`.
+    fn render_names(
+        &self,
+        element: &NamesElement,
+        ctx: &ItemContext<'_>,
+        inherit: Option<&NamesElement>,
+    ) -> Out {
+        let name = element
+            .name
+            .as_ref()
+            .or_else(|| inherit.and_then(|parent| parent.name.as_ref()));
+        let et_al = element
+            .et_al
+            .as_ref()
+            .or_else(|| inherit.and_then(|parent| parent.et_al.as_ref()));
+        let (label, label_before_name) = match (&element.label, inherit) {
+            (Some(label), _) => (Some(label), element.label_before_name),
+            (None, Some(parent)) => (parent.label.as_ref(), parent.label_before_name),
+            (None, None) => (None, false),
+        };
+        let default_name = NameElement::default();
+        let name = name.unwrap_or(&default_name);
+        let options = self.name_options.overlay(&name.options);
+
+        let mut lists: Vec<Vec<Node>> = Vec::new();
+        let mut seen: Vec<Vec<CslName>> = Vec::new();
+        for variable in &element.variables {
+            let names = self.names(ctx, variable);
+            if names.is_empty() || seen.contains(&names) {
+                continue;
+            }
+            let (mut nodes, count) = self.name_list(&names, &options, name, et_al);
+            if options.get("name-form") != Some("count")
+                && let Some(label) = label
+            {
+                let label_nodes = self
+                    .label(label, variable, ctx, Some(count))
+                    .unwrap_or_default();
+                if label_before_name {
+                    let mut with_label = label_nodes;
+                    append(&mut with_label, nodes);
+                    nodes = with_label;
+                } else {
+                    append(&mut nodes, label_nodes);
+                }
+            }
+            lists.push(nodes);
+            seen.push(names);
+        }
+
+        if lists.is_empty() {
+            let Some(substitute) = &element.substitute else {
+                return Out {
+                    called: true,
+                    ..Default::default()
+                };
+            };
+            for child in substitute {
+                let used = ctx.used.borrow().len();
+                let out = match child {
+                    Element::Names(names) => self.render_names(names, ctx, Some(element)),
+                    other => self.element(other, ctx),
+                };
+                // Suppress every variable the substitute used, including those
+                // reached through macros, groups and choose.
+                let substituted = ctx.used.borrow()[used..].to_vec();
+                if !out.nodes.is_empty() {
+                    ctx.suppressed.borrow_mut().extend(substituted);
+                    return Out {
+                        nodes: self.finish(out.nodes, &element.rendering),
+                        called: true,
+                        rendered: true,
+                    };
+                }
+            }
+            return Out {
+                called: true,
+                ..Default::default()
+            };
+        }
+
+        let delimiter = element
+            .delimiter
+            .as_deref()
+            .or_else(|| options.get("names-delimiter"))
+            .unwrap_or(", ");
+        let mut nodes = Vec::new();
+        for list in lists {
+            if !nodes.is_empty() {
+                append(&mut nodes, vec![Node::Text(delimiter.to_string())]);
+            }
+            append(&mut nodes, list);
+        }
+        Out::variable(self.finish(nodes, &element.rendering))
+    }
+
+    /// Render a list of names, returning the nodes and the number of names shown.
+    fn name_list(
+        &self,
+        names: &[CslName],
+        options: &NameOptions,
+        element: &NameElement,
+        et_al: Option<&EtAlElement>,
+    ) -> (Vec<Node>, usize) {
+        let parse = |name: &str| {
+            options
+                .get(name)
+                .and_then(|value| value.parse::<usize>().ok())
+        };
+        let count = names.len();
+        let truncated = match (parse("et-al-min"), parse("et-al-use-first")) {
+            (Some(min), Some(first)) => count >= min && first < count,
+            _ => false,
+        };
+        let shown = if truncated {
+            parse("et-al-use-first").unwrap_or(count)
+        } else {
+            count
+        };
+        if options.get("name-form") == Some("count") {
+            return (vec![Node::Text(shown.to_string())], shown);
+        }
+
+        let sort_order = options.get("name-as-sort-order");
+        let inverted = |index: usize| match sort_order {
+            Some("all") => true,
+            Some("first") => index == 0,
+            _ => false,
+        };
+        let delimiter = options.get("name-delimiter").unwrap_or(", ");
+        let and = match options.get("and") {
+            Some("text") => Some(self.term_or("and", "and")),
+            Some("symbol") => Some("&".to_string()),
+            _ => None,
+        };
+
+        let mut nodes = Vec::new();
+        for (index, name) in names.iter().take(shown).enumerate() {
+            let rendered = self.name(name, inverted(index), options, element);
+            if index > 0 {
+                let last = index == shown - 1 && !truncated;
+                let separator = match (&and, last) {
+                    (Some(and), true) => {
+                        let precedes = match options.get("delimiter-precedes-last") {
+                            Some("always") => true,
+                            Some("never") => false,
+                            Some("after-inverted-name") => inverted(index - 1),
+                            _ => shown > 2,
+                        };
+                        if precedes {
+                            format!("{delimiter}{and} ")
+                        } else {
+                            format!(" {and} ")
+                        }
+                    }
+                    _ => delimiter.to_string(),
+                };
+                append(&mut nodes, vec![Node::Text(separator)]);
+            }
+            append(&mut nodes, rendered);
+        }
+
+        if truncated {
+            let use_last = options.get("et-al-use-last") == Some("true");
+            if use_last && shown + 1 < count {
+                let last = &names[count - 1];
+                append(&mut nodes, vec![Node::Text(format!("{delimiter}… "))]);
+                append(
+                    &mut nodes,
+                    self.name(last, inverted(count - 1), options, element),
+                );
+            } else {
+                let term = et_al.map_or("et-al", |et_al| et_al.term.as_str());
+                let text = self.locale.term(term, TermForm::Long, false).unwrap_or("");
+                if !text.is_empty() {
+                    let precedes = match options.get("delimiter-precedes-et-al") {
+                        Some("always") => true,
+                        Some("never") => false,
+                        Some("after-inverted-name") => inverted(shown - 1),
+                        _ => shown > 1,
+                    };
+                    let separator = if precedes {
+                        delimiter.to_string()
+                    } else {
+                        " ".to_string()
+                    };
+                    let mut term_nodes = vec![Node::Text(text.to_string())];
+                    if let Some(et_al) = et_al {
+                        term_nodes = self.finish(term_nodes, &et_al.rendering);
+                    }
+                    append(&mut nodes, vec![Node::Text(separator)]);
+                    append(&mut nodes, term_nodes);
+                }
+            }
+        }
+
+        let mut outer = element.rendering.clone();
+        outer.text_case = None;
+        (self.finish(nodes, &outer), shown)
+    }
+
+    fn name(
+        &self,
+        name: &CslName,
+        inverted: bool,
+        options: &NameOptions,
+        element: &NameElement,
+    ) -> Vec<Node> {
+        let part = |text: String, rendering: Option<&Rendering>| match rendering {
+            Some(rendering) => self.finish(vec![Node::Text(text)], rendering),
+            None if text.is_empty() => Vec::new(),
+            None => vec![Node::Text(text)],
+        };
+        let clean = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let Some(family) = clean(&name.family) else {
+            let literal = clean(&name.literal).or_else(|| clean(&name.given));
+            return part(literal.unwrap_or_default(), element.family.as_ref());
+        };
+        let non_dropping = clean(&name.non_dropping_particle);
+        let dropping = clean(&name.dropping_particle);
+        let suffix = clean(&name.suffix);
+        let given = clean(&name.given).map(|given| match options.get("initialize-with") {
+            Some(with) if options.get("initialize") != Some("false") => {
+                initialize(&given, with, self.style.initialize_with_hyphen)
+            }
+            _ => given,
+        });
+
+        let join = |parts: &[Option<&str>]| {
+            parts
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut nodes = Vec::new();
+        if options.get("name-form") == Some("short") {
+            let family = join(&[non_dropping.as_deref(), Some(&family)]);
+            append(&mut nodes, part(family, element.family.as_ref()));
+        } else if inverted {
+            let separator = options.get("sort-separator").unwrap_or(", ");
+            let demote = self.style.demote_non_dropping_particle != "never";
+            let (family, given) = if demote {
+                (
+                    family.clone(),
+                    join(&[
+                        given.as_deref(),
+                        dropping.as_deref(),
+                        non_dropping.as_deref(),
+                    ]),
+                )
+            } else {
+                (
+                    join(&[non_dropping.as_deref(), Some(&family)]),
+                    join(&[given.as_deref(), dropping.as_deref()]),
+                )
+            };
+            append(&mut nodes, part(family, element.family.as_ref()));
+            if !given.is_empty() {
+                nodes.push(Node::Text(separator.to_string()));
+                append(&mut nodes, part(given, element.given.as_ref()));
+            }
+            if let Some(suffix) = suffix {
+                nodes.push(Node::Text(format!("{separator}{suffix}")));
+            }
+        } else {
+            let given = join(&[given.as_deref(), dropping.as_deref()]);
+            if !given.is_empty() {
+                append(&mut nodes, part(given, element.given.as_ref()));
+                nodes.push(Node::Text(" ".to_string()));
+            }
+            let family = join(&[non_dropping.as_deref(), Some(&family)]);
+            append(&mut nodes, part(family, element.family.as_ref()));
+            if let Some(suffix) = suffix {
+                nodes.push(Node::Text(format!(" {suffix}")));
+            }
+        }
+        let mut per_name = element.rendering.clone();
+        per_name.prefix = None;
+        per_name.suffix = None;
+        self.finish(nodes, &per_name)
+    }
+}
+
+/// Merge a style `<date-part>` over a localized one with the same name.
+fn override_part(localized: &DatePart, overrides: &[DatePart]) -> DatePart {
+    let mut part = localized.clone();
+    let Some(over) = overrides.iter().find(|over| over.name == localized.name) else {
+        return part;
+    };
+    if over.form.is_some() {
+        part.form = over.form.clone();
+    }
+    if over.range_delimiter.is_some() {
+        part.range_delimiter = over.range_delimiter.clone();
+    }
+    for attr in &over.attrs {
+        match attr.as_str() {
+            "text-case" => part.rendering.text_case = over.rendering.text_case,
+            "strip-periods" => part.rendering.strip_periods = over.rendering.strip_periods,
+            _ => part.rendering.formatting = over.rendering.formatting,
+        }
+    }
+    part
+}
+
+/// Reduce given names to initials, e.g. `Jean-Paul Sartre` with `. ` to `J.-P.`.
+fn initialize(given: &str, with: &str, hyphen: bool) -> String {
+    let mut out = String::new();
+    for word in given.split_whitespace() {
+        // Lowercase words such as particles stay as they are.
+        if word.chars().next().is_some_and(char::is_lowercase) {
+            out.push_str(word);
+            out.push(' ');
+            continue;
+        }
+        let pieces: Vec<String> = word
+            .split('-')
+            .filter_map(|piece| piece.chars().next())
+            .map(|initial| format!("{}{with}", initial.to_uppercase()))
+            .collect();
+        if hyphen {
+            let joined: Vec<&str> = pieces.iter().map(|piece| piece.trim_end()).collect();
+            out.push_str(&joined.join("-"));
+            if with.ends_with(' ') {
+                out.push(' ');
+            }
+        } else {
+            out.push_str(&pieces.concat());
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn roman(number: i64) -> Option<String> {
+    if !(1..4000).contains(&number) {
+        return None;
+    }
+    const NUMERALS: &[(i64, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut rest = number;
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while rest >= *value {
+            out.push_str(numeral);
+            rest -= value;
+        }
+    }
+    Some(out)
+}
+
+/// Lowercase text without leading punctuation, for sort keys.
+fn sort_text(text: &str) -> String {
+    text.trim_start_matches(|ch: char| !ch.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn apply_case(nodes: &mut [Node], case: TextCase) {
+    let mut first = true;
+    let mut keep = |markup| markup != Markup::NoCase;
+    map_text_where(nodes, &mut keep, &mut |text, nocase| {
+        let result = match case {
+            _ if nocase => text.to_string(),
+            TextCase::Lowercase => text.to_lowercase(),
+            TextCase::Uppercase => text.to_uppercase(),
+            TextCase::CapitalizeFirst | TextCase::Sentence if first => capitalize(text),
+            TextCase::CapitalizeFirst | TextCase::Sentence => text.to_string(),
+            TextCase::CapitalizeAll => text
+                .split(' ')
+                .map(capitalize)
+                .collect::<Vec<_>>()
+                .join(" "),
+            TextCase::Title => title_case(text),
+        };
+        if !text.trim().is_empty() {
+            first = false;
+        }
+        result
+    });
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// English title case: capitalize words except stop words, keeping mixed-case words.
+fn title_case(text: &str) -> String {
+    let words: Vec<&str> = text.split(' ').collect();
+    let last = words.len().saturating_sub(1);
+    let mut after_colon = true;
+    let mut out = Vec::with_capacity(words.len());
+    for (index, word) in words.iter().enumerate() {
+        let mixed = word.chars().skip(1).any(char::is_uppercase);
+        let lower = word.to_lowercase();
+        let stop = STOP_WORDS.contains(&lower.trim_matches(|ch: char| !ch.is_alphanumeric()));
+        let word = if mixed || (stop && !after_colon && index != last) {
+            word.to_string()
+        } else {
+            capitalize(word)
+        };
+        after_colon = word.ends_with(':') || (word.is_empty() && after_colon);
+        out.push(word);
+    }
+    out.join(" ")
+}
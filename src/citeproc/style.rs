@@ -0,0 +1,799 @@
+//! CSL 1.0.2 style parsing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use roxmltree::{Document, Node as XmlNode, ParsingOptions};
+use snafu::{OptionExt, ResultExt};
+
+use crate::citeproc::error::*;
+use crate::citeproc::locale::{Locale, TermForm};
+use crate::citeproc::output::Formatting;
+use crate::csl::PageRangeFormat;
+
+/// Whether a style produces in-text or note citations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StyleClass {
+    InText,
+    Note,
+}
+
+/// Simplified styles bundled with the crate, written for it rather than taken
+/// from the CSL styles repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BundledStyle {
+    /// APA 7th edition.
+    Apa,
+    /// MLA 9th edition.
+    Mla,
+    /// Chicago Manual of Style 17th edition (author-date).
+    ChicagoAuthorDate,
+    /// IEEE.
+    Ieee,
+    /// Vancouver (ICMJE).
+    Vancouver,
+}
+
+impl BundledStyle {
+    /// Return every bundled style.
+    pub fn all() -> &'static [BundledStyle] {
+        &[
+            BundledStyle::Apa,
+            BundledStyle::Mla,
+            BundledStyle::ChicagoAuthorDate,
+            BundledStyle::Ieee,
+            BundledStyle::Vancouver,
+        ]
+    }
+
+    /// Return the style id, e.g. `chicago-author-date`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BundledStyle::Apa => "apa",
+            BundledStyle::Mla => "mla",
+            BundledStyle::ChicagoAuthorDate => "chicago-author-date",
+            BundledStyle::Ieee => "ieee",
+            BundledStyle::Vancouver => "vancouver",
+        }
+    }
+
+    /// Return the CSL source of the style.
+    pub fn source(&self) -> &'static str {
+        match self {
+            BundledStyle::Apa => include_str!("../../styles/apa.csl"),
+            BundledStyle::Mla => include_str!("../../styles/mla.csl"),
+            BundledStyle::ChicagoAuthorDate => include_str!("../../styles/chicago-author-date.csl"),
+            BundledStyle::Ieee => include_str!("../../styles/ieee.csl"),
+            BundledStyle::Vancouver => include_str!("../../styles/vancouver.csl"),
+        }
+    }
+}
+
+impl std::str::FromStr for BundledStyle {
+    type Err = CiteprocError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .find(|style| style.as_str().eq_ignore_ascii_case(value.trim()))
+            .copied()
+            .context(InvalidStyleSnafu {
+                stage: "bundled-style",
+                message: format!("unknown bundled style `{value}`"),
+            })
+    }
+}
+
+/// A parsed CSL style.
+#[derive(Debug, Clone)]
+pub struct Style {
+    class: StyleClass,
+    title: Option<String>,
+    default_locale: Option<String>,
+    pub(crate) page_range_format: PageRangeFormat,
+    pub(crate) demote_non_dropping_particle: String,
+    pub(crate) initialize_with_hyphen: bool,
+    pub(crate) name_options: NameOptions,
+    pub(crate) macros: HashMap<String, Vec<Element>>,
+    pub(crate) locales: Vec<Locale>,
+    pub(crate) citation: Option<Section>,
+    pub(crate) bibliography: Option<Section>,
+}
+
+impl Style {
+    /// Parse a CSL style file.
+    pub fn parse(xml: &str) -> Result<Self, CiteprocError> {
+        let document = Document::parse_with_options(xml, parsing_options()).context(XmlSnafu {
+            stage: "parse-style",
+        })?;
+        let root = document.root_element();
+        if root.tag_name().name() != "style" {
+            return InvalidStyleSnafu {
+                stage: "parse-style",
+                message: format!("expected <style>, found <{}>", root.tag_name().name()),
+            }
+            .fail();
+        }
+
+        let title = children(root, "info")
+            .next()
+            .and_then(|info| children(info, "title").next())
+            .and_then(|title| title.text())
+            .map(str::to_string);
+        let page_range_format = match attr(root, "page-range-format") {
+            Some("expanded") => PageRangeFormat::Expanded,
+            Some("minimal") => PageRangeFormat::Minimal,
+            Some("minimal-two") => PageRangeFormat::MinimalTwo,
+            Some("chicago" | "chicago-15" | "chicago-16") => PageRangeFormat::Chicago,
+            _ => PageRangeFormat::AsIs,
+        };
+
+        let mut macros = HashMap::new();
+        for node in children(root, "macro") {
+            let name = attr(node, "name").context(InvalidStyleSnafu {
+                stage: "parse-macro",
+                message: "macro without a name".to_string(),
+            })?;
+            macros.insert(name.to_string(), parse_children(node)?);
+        }
+
+        let style = Self {
+            class: match attr(root, "class") {
+                Some("note") => StyleClass::Note,
+                _ => StyleClass::InText,
+            },
+            title,
+            default_locale: attr(root, "default-locale").map(str::to_string),
+            page_range_format,
+            demote_non_dropping_particle: attr(root, "demote-non-dropping-particle")
+                .unwrap_or("display-and-sort")
+                .to_string(),
+            initialize_with_hyphen: attr(root, "initialize-with-hyphen") != Some("false"),
+            name_options: NameOptions::from_node(root),
+            macros,
+            locales: children(root, "locale").map(Locale::from_node).collect(),
+            citation: children(root, "citation")
+                .next()
+                .map(Section::parse)
+                .transpose()?,
+            bibliography: children(root, "bibliography")
+                .next()
+                .map(Section::parse)
+                .transpose()?,
+        };
+        for name in style.called_macros() {
+            if !style.macros.contains_key(&name) {
+                return InvalidStyleSnafu {
+                    stage: "check-macros",
+                    message: format!("undefined macro `{name}`"),
+                }
+                .fail();
+            }
+        }
+        Ok(style)
+    }
+
+    /// Parse one of the bundled styles.
+    pub fn bundled(style: BundledStyle) -> Self {
+        Self::parse(style.source()).expect("bundled styles are valid")
+    }
+
+    /// Return the style class.
+    pub fn class(&self) -> StyleClass {
+        self.class
+    }
+
+    /// Return the style title from `<info>`.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Return the `default-locale` attribute.
+    pub fn default_locale(&self) -> Option<&str> {
+        self.default_locale.as_deref()
+    }
+
+    /// Return true when the style defines a bibliography.
+    pub fn has_bibliography(&self) -> bool {
+        self.bibliography.is_some()
+    }
+
+    /// Return the names of every macro called anywhere in the style.
+    fn called_macros(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let sections = self.citation.iter().chain(&self.bibliography);
+        let layouts = sections.flat_map(|section| {
+            let sort = section.sort.iter().filter_map(|key| match key {
+                SortKey::Macro { name, .. } => Some(name.clone()),
+                SortKey::Variable { .. } => None,
+            });
+            names.extend(sort);
+            section.layout.elements.iter()
+        });
+        let mut elements: Vec<&Element> = layouts.collect();
+        elements.extend(self.macros.values().flatten());
+        while let Some(element) = elements.pop() {
+            match element {
+                Element::Text(text) => {
+                    if let TextSource::Macro(name) = &text.source {
+                        names.push(name.clone());
+                    }
+                }
+                Element::Group(group) => elements.extend(&group.children),
+                Element::Choose(branches) => {
+                    elements.extend(branches.iter().flat_map(|branch| &branch.children))
+                }
+                Element::Names(names_element) => {
+                    elements.extend(names_element.substitute.iter().flatten())
+                }
+                _ => {}
+            }
+        }
+        names
+    }
+}
+
+/// A `<citation>` or `<bibliography>` section.
+#[derive(Debug, Clone)]
+pub(crate) struct Section {
+    pub layout: Layout,
+    pub sort: Vec<SortKey>,
+    pub name_options: NameOptions,
+}
+
+impl Section {
+    fn parse(node: XmlNode<'_, '_>) -> Result<Self, CiteprocError> {
+        let layout = children(node, "layout").next().context(InvalidStyleSnafu {
+            stage: "parse-section",
+            message: format!("<{}> without <layout>", node.tag_name().name()),
+        })?;
+        let sort = children(node, "sort")
+            .next()
+            .map(|sort| {
+                children(sort, "key")
+                    .filter_map(|key| {
+                        let ascending = attr(key, "sort") != Some("descending");
+                        if let Some(name) = attr(key, "macro") {
+                            Some(SortKey::Macro {
+                                name: name.to_string(),
+                                ascending,
+                            })
+                        } else {
+                            attr(key, "variable").map(|variable| SortKey::Variable {
+                                variable: variable.to_string(),
+                                ascending,
+                            })
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            layout: Layout {
+                elements: parse_children(layout)?,
+                rendering: Rendering::from_node(layout),
+                delimiter: attr(layout, "delimiter").map(str::to_string),
+            },
+            sort,
+            name_options: NameOptions::from_node(node),
+        })
+    }
+}
+
+/// A `<layout>` element.
+#[derive(Debug, Clone)]
+pub(crate) struct Layout {
+    pub elements: Vec<Element>,
+    pub rendering: Rendering,
+    pub delimiter: Option<String>,
+}
+
+/// A `<sort>` key.
+#[derive(Debug, Clone)]
+pub(crate) enum SortKey {
+    Variable { variable: String, ascending: bool },
+    Macro { name: String, ascending: bool },
+}
+
+/// Inheritable name attributes (`and`, `et-al-min`, `initialize-with`, ...).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct NameOptions {
+    attrs: BTreeMap<String, String>,
+}
+
+const NAME_ATTRIBUTES: &[&str] = &[
+    "and",
+    "delimiter-precedes-et-al",
+    "delimiter-precedes-last",
+    "et-al-min",
+    "et-al-use-first",
+    "et-al-subsequent-min",
+    "et-al-subsequent-use-first",
+    "et-al-use-last",
+    "initialize",
+    "initialize-with",
+    "name-as-sort-order",
+    "sort-separator",
+    "form",
+    "name-form",
+    "name-delimiter",
+    "names-delimiter",
+];
+
+impl NameOptions {
+    fn from_node(node: XmlNode<'_, '_>) -> Self {
+        let attrs = NAME_ATTRIBUTES
+            .iter()
+            .filter_map(|name| Some((name.to_string(), attr(node, name)?.to_string())))
+            .collect();
+        Self { attrs }
+    }
+
+    /// Read the attributes of a `<name>` element, where `delimiter` means the name delimiter.
+    fn from_name_node(node: XmlNode<'_, '_>) -> Self {
+        let mut options = Self::from_node(node);
+        if let Some(delimiter) = attr(node, "delimiter") {
+            options
+                .attrs
+                .insert("name-delimiter".to_string(), delimiter.to_string());
+        }
+        if let Some(form) = options.attrs.remove("form") {
+            options.attrs.insert("name-form".to_string(), form);
+        }
+        options
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(String::as_str)
+    }
+
+    /// Return these options with `over` taking precedence.
+    pub(crate) fn overlay(&self, over: &NameOptions) -> NameOptions {
+        let mut attrs = self.attrs.clone();
+        attrs.extend(over.attrs.clone());
+        NameOptions { attrs }
+    }
+}
+
+/// Text case transformations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TextCase {
+    Lowercase,
+    Uppercase,
+    CapitalizeFirst,
+    CapitalizeAll,
+    Sentence,
+    Title,
+}
+
+/// Affixes, formatting and other rendering attributes shared by elements.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Rendering {
+    pub formatting: Formatting,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    pub text_case: Option<TextCase>,
+    pub quotes: bool,
+    pub strip_periods: bool,
+    pub display: Option<String>,
+}
+
+impl Rendering {
+    pub(crate) fn from_node(node: XmlNode<'_, '_>) -> Self {
+        let formatting = Formatting {
+            italic: matches!(attr(node, "font-style"), Some("italic" | "oblique")),
+            bold: attr(node, "font-weight") == Some("bold"),
+            small_caps: attr(node, "font-variant") == Some("small-caps"),
+            underline: attr(node, "text-decoration") == Some("underline"),
+            superscript: attr(node, "vertical-align") == Some("sup"),
+            subscript: attr(node, "vertical-align") == Some("sub"),
+        };
+        let text_case = match attr(node, "text-case") {
+            Some("lowercase") => Some(TextCase::Lowercase),
+            Some("uppercase") => Some(TextCase::Uppercase),
+            Some("capitalize-first") => Some(TextCase::CapitalizeFirst),
+            Some("capitalize-all") => Some(TextCase::CapitalizeAll),
+            Some("sentence") => Some(TextCase::Sentence),
+            Some("title") => Some(TextCase::Title),
+            _ => None,
+        };
+        Self {
+            formatting,
+            prefix: attr(node, "prefix").map(str::to_string),
+            suffix: attr(node, "suffix").map(str::to_string),
+            text_case,
+            quotes: attr(node, "quotes") == Some("true"),
+            strip_periods: attr(node, "strip-periods") == Some("true"),
+            display: attr(node, "display").map(str::to_string),
+        }
+    }
+}
+
+/// A rendering element.
+#[derive(Debug, Clone)]
+pub(crate) enum Element {
+    Text(TextElement),
+    Number(NumberElement),
+    Label(LabelElement),
+    Date(DateElement),
+    Names(Box<NamesElement>),
+    Group(GroupElement),
+    Choose(Vec<Branch>),
+}
+
+/// What a `<text>` element renders.
+#[derive(Debug, Clone)]
+pub(crate) enum TextSource {
+    Variable {
+        name: String,
+        short: bool,
+    },
+    Macro(String),
+    Term {
+        name: String,
+        form: TermForm,
+        plural: bool,
+    },
+    Value(String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TextElement {
+    pub source: TextSource,
+    pub rendering: Rendering,
+}
+
+/// Number forms of `<number>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NumberForm {
+    Numeric,
+    Ordinal,
+    LongOrdinal,
+    Roman,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct NumberElement {
+    pub variable: String,
+    pub form: NumberForm,
+    pub rendering: Rendering,
+}
+
+/// Plural handling of `<label>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Plural {
+    Contextual,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LabelElement {
+    pub variable: Option<String>,
+    pub form: TermForm,
+    pub plural: Plural,
+    pub rendering: Rendering,
+}
+
+impl LabelElement {
+    fn parse(node: XmlNode<'_, '_>) -> Self {
+        Self {
+            variable: attr(node, "variable").map(str::to_string),
+            form: TermForm::parse(attr(node, "form")),
+            plural: match attr(node, "plural") {
+                Some("always") => Plural::Always,
+                Some("never") => Plural::Never,
+                _ => Plural::Contextual,
+            },
+            rendering: Rendering::from_node(node),
+        }
+    }
+}
+
+/// Which date parts to show (`date-parts` attribute).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum DatePartsFilter {
+    Year,
+    YearMonth,
+    YearMonthDay,
+}
+
+/// A `<date-part>` element.
+#[derive(Debug, Clone)]
+pub(crate) struct DatePart {
+    pub name: String,
+    pub form: Option<String>,
+    pub range_delimiter: Option<String>,
+    pub rendering: Rendering,
+    /// Formatting attributes set on the element, which override localized date parts.
+    pub attrs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DateElement {
+    pub variable: String,
+    /// `text` or `numeric` for localized dates.
+    pub form: Option<String>,
+    pub parts_filter: DatePartsFilter,
+    pub parts: Vec<DatePart>,
+    pub delimiter: Option<String>,
+    pub rendering: Rendering,
+}
+
+/// A `<names>` element.
+#[derive(Debug, Clone)]
+pub(crate) struct NamesElement {
+    pub variables: Vec<String>,
+    pub name: Option<NameElement>,
+    pub et_al: Option<EtAlElement>,
+    pub label: Option<LabelElement>,
+    pub label_before_name: bool,
+    pub substitute: Option<Vec<Element>>,
+    pub delimiter: Option<String>,
+    pub rendering: Rendering,
+}
+
+/// A `<name>` element.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NameElement {
+    pub options: NameOptions,
+    pub rendering: Rendering,
+    pub family: Option<Rendering>,
+    pub given: Option<Rendering>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EtAlElement {
+    pub term: String,
+    pub rendering: Rendering,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GroupElement {
+    pub children: Vec<Element>,
+    pub delimiter: Option<String>,
+    pub rendering: Rendering,
+}
+
+/// One condition test.
+#[derive(Debug, Clone)]
+pub(crate) enum Test {
+    Type(String),
+    Variable(String),
+    IsNumeric(String),
+    IsUncertainDate(String),
+    Position(String),
+    Locator(String),
+    Disambiguate(bool),
+}
+
+/// How the tests of a branch combine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Match {
+    All,
+    Any,
+    None,
+}
+
+/// An `<if>`, `<else-if>` or `<else>` branch; `<else>` has no tests.
+#[derive(Debug, Clone)]
+pub(crate) struct Branch {
+    pub tests: Vec<Test>,
+    pub match_mode: Match,
+    pub children: Vec<Element>,
+}
+
+fn parse_children(node: XmlNode<'_, '_>) -> Result<Vec<Element>, CiteprocError> {
+    let mut elements = Vec::new();
+    for child in node.children().filter(XmlNode::is_element) {
+        if let Some(element) = parse_element(child)? {
+            elements.push(element);
+        }
+    }
+    Ok(elements)
+}
+
+fn parse_element(node: XmlNode<'_, '_>) -> Result<Option<Element>, CiteprocError> {
+    let rendering = Rendering::from_node(node);
+    let element = match node.tag_name().name() {
+        "text" => {
+            let source = if let Some(name) = attr(node, "variable") {
+                TextSource::Variable {
+                    name: name.to_string(),
+                    short: attr(node, "form") == Some("short"),
+                }
+            } else if let Some(name) = attr(node, "macro") {
+                TextSource::Macro(name.to_string())
+            } else if let Some(name) = attr(node, "term") {
+                TextSource::Term {
+                    name: name.to_string(),
+                    form: TermForm::parse(attr(node, "form")),
+                    plural: attr(node, "plural") == Some("true"),
+                }
+            } else if let Some(value) = attr(node, "value") {
+                TextSource::Value(value.to_string())
+            } else {
+                return InvalidStyleSnafu {
+                    stage: "parse-text",
+                    message: "<text> needs variable, macro, term or value".to_string(),
+                }
+                .fail();
+            };
+            Element::Text(TextElement { source, rendering })
+        }
+        "number" => Element::Number(NumberElement {
+            variable: attr(node, "variable").unwrap_or_default().to_string(),
+            form: match attr(node, "form") {
+                Some("ordinal") => NumberForm::Ordinal,
+                Some("long-ordinal") => NumberForm::LongOrdinal,
+                Some("roman") => NumberForm::Roman,
+                _ => NumberForm::Numeric,
+            },
+            rendering,
+        }),
+        "label" => Element::Label(LabelElement::parse(node)),
+        "date" => Element::Date(DateElement {
+            variable: attr(node, "variable").unwrap_or_default().to_string(),
+            form: attr(node, "form").map(str::to_string),
+            parts_filter: match attr(node, "date-parts") {
+                Some("year") => DatePartsFilter::Year,
+                Some("year-month") => DatePartsFilter::YearMonth,
+                _ => DatePartsFilter::YearMonthDay,
+            },
+            parts: children(node, "date-part").map(parse_date_part).collect(),
+            delimiter: attr(node, "delimiter").map(str::to_string),
+            rendering,
+        }),
+        "names" => Element::Names(Box::new(parse_names(node, rendering)?)),
+        "group" => Element::Group(GroupElement {
+            children: parse_children(node)?,
+            delimiter: attr(node, "delimiter").map(str::to_string),
+            rendering,
+        }),
+        "choose" => {
+            let mut branches = Vec::new();
+            for branch in node.children().filter(XmlNode::is_element) {
+                branches.push(parse_branch(branch)?);
+            }
+            Element::Choose(branches)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(element))
+}
+
+fn parse_names(node: XmlNode<'_, '_>, rendering: Rendering) -> Result<NamesElement, CiteprocError> {
+    let mut names = NamesElement {
+        variables: attr(node, "variable")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        name: None,
+        et_al: None,
+        label: None,
+        label_before_name: false,
+        substitute: None,
+        delimiter: attr(node, "delimiter").map(str::to_string),
+        rendering,
+    };
+    for child in node.children().filter(XmlNode::is_element) {
+        match child.tag_name().name() {
+            "name" => {
+                let mut name = NameElement {
+                    options: NameOptions::from_name_node(child),
+                    rendering: Rendering::from_node(child),
+                    ..Default::default()
+                };
+                for part in children(child, "name-part") {
+                    match attr(part, "name") {
+                        Some("family") => name.family = Some(Rendering::from_node(part)),
+                        Some("given") => name.given = Some(Rendering::from_node(part)),
+                        _ => {}
+                    }
+                }
+                names.name = Some(name);
+            }
+            "et-al" => {
+                names.et_al = Some(EtAlElement {
+                    term: attr(child, "term").unwrap_or("et-al").to_string(),
+                    rendering: Rendering::from_node(child),
+                })
+            }
+            "label" => {
+                names.label_before_name = names.name.is_none();
+                names.label = Some(LabelElement::parse(child));
+            }
+            "substitute" => names.substitute = Some(parse_children(child)?),
+            _ => {}
+        }
+    }
+    Ok(names)
+}
+
+fn parse_branch(node: XmlNode<'_, '_>) -> Result<Branch, CiteprocError> {
+    let mut tests = Vec::new();
+    let split = |name: &str| -> Vec<String> {
+        attr(node, name)
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect()
+    };
+    tests.extend(split("type").into_iter().map(Test::Type));
+    tests.extend(split("variable").into_iter().map(Test::Variable));
+    tests.extend(split("is-numeric").into_iter().map(Test::IsNumeric));
+    tests.extend(
+        split("is-uncertain-date")
+            .into_iter()
+            .map(Test::IsUncertainDate),
+    );
+    tests.extend(split("position").into_iter().map(Test::Position));
+    tests.extend(split("locator").into_iter().map(Test::Locator));
+    if let Some(value) = attr(node, "disambiguate") {
+        tests.push(Test::Disambiguate(value == "true"));
+    }
+
+    let is_else = node.tag_name().name() == "else";
+    if !is_else && tests.is_empty() {
+        return InvalidStyleSnafu {
+            stage: "parse-choose",
+            message: format!("<{}> without a condition", node.tag_name().name()),
+        }
+        .fail();
+    }
+    Ok(Branch {
+        tests,
+        match_mode: match attr(node, "match") {
+            Some("any") => Match::Any,
+            Some("none") => Match::None,
+            _ => Match::All,
+        },
+        children: parse_children(node)?,
+    })
+}
+
+pub(crate) fn parse_date_part(node: XmlNode<'_, '_>) -> DatePart {
+    // Affixes of localized date parts cannot be overridden by the style.
+    const OVERRIDABLE: &[&str] = &[
+        "font-style",
+        "font-weight",
+        "font-variant",
+        "text-decoration",
+        "vertical-align",
+        "text-case",
+        "strip-periods",
+    ];
+    DatePart {
+        name: attr(node, "name").unwrap_or_default().to_string(),
+        form: attr(node, "form").map(str::to_string),
+        range_delimiter: attr(node, "range-delimiter").map(str::to_string),
+        rendering: Rendering::from_node(node),
+        attrs: OVERRIDABLE
+            .iter()
+            .filter(|name| attr(node, name).is_some())
+            .map(|name| name.to_string())
+            .collect(),
+    }
+}
+
+/// Return an attribute value.
+pub(crate) fn attr<'a>(node: XmlNode<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attribute(name)
+}
+
+/// Return child elements with a local name.
+pub(crate) fn children<'a, 'input>(
+    node: XmlNode<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = XmlNode<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+pub(crate) fn parsing_options<'input>() -> ParsingOptions<'input> {
+    ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    }
+}
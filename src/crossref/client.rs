@@ -0,0 +1,676 @@
+use crate::Doi;
+use crate::crossref::config::CrossrefConfig;
+use crate::crossref::entity::{
+    CrossrefFunderRecord, CrossrefJournal, CrossrefList, CrossrefMember, CrossrefPrefix,
+    CrossrefType,
+};
+use crate::crossref::error::*;
+use crate::crossref::query::{CrossrefWorkList, WorksQuery, WorksStream};
+use crate::crossref::unixsd::UnixsdRecord;
+use crate::crossref::work::{CrossrefResponse, CrossrefWork};
+use crate::csl::DoiMetadata;
+use crate::http::{RateLimiter, is_not_found, user_agent_headers};
+use crate::source::error::{CrossrefSnafu, NotFoundSnafu, SourceError};
+use crate::source::{MetadataSource, Record};
+use async_trait::async_trait;
+use reqwest::header::{HeaderName, HeaderValue};
+use serde::de::DeserializeOwned;
+use snafu::{OptionExt, ResultExt, ensure};
+
+/// Client for the Crossref REST API.
+pub struct CrossrefClient {
+    client: reqwest::Client,
+    base_url: String,
+    limiter: RateLimiter,
+}
+
+impl CrossrefClient {
+    const PLUS_TOKEN_HEADER: &'static str = "crossref-plus-api-token";
+    const UNIXSD: &'static str = "application/vnd.crossref.unixsd+xml";
+    const CSL_JSON: &'static str = "application/vnd.citationstyles.csl+json";
+
+    /// Build a Crossref client with configured defaults.
+    pub fn new(config: CrossrefConfig) -> std::result::Result<Self, CrossrefError> {
+        let base_url = config.base_url_value();
+        let client = reqwest::Client::builder()
+            .default_headers(Self::default_headers(&config))
+            .timeout(config.timeout)
+            .build()
+            .context(RequestSnafu {
+                stage: "build-client",
+            })?;
+        let limiter = RateLimiter::new(
+            Some(config.rate_limit_value()),
+            Some(config.concurrency_value()),
+        );
+
+        Ok(Self {
+            client,
+            base_url,
+            limiter,
+        })
+    }
+
+    /// Fetch the Crossref work record for a DOI.
+    pub async fn work(&self, doi: &Doi) -> std::result::Result<CrossrefWork, CrossrefError> {
+        self.work_response(doi)
+            .await
+            .map(|response| response.message)
+    }
+
+    /// Fetch the Crossref work record for a DOI together with its response envelope.
+    pub async fn work_response(
+        &self,
+        doi: &Doi,
+    ) -> std::result::Result<CrossrefResponse<CrossrefWork>, CrossrefError> {
+        let path = format!("works/{}", doi.as_str());
+        self.get_message(&path, &[], "work").await
+    }
+
+    /// Fetch the CSL-JSON transform of a work.
+    pub async fn csl(&self, doi: &Doi) -> std::result::Result<DoiMetadata, CrossrefError> {
+        let path = format!("works/{}/transform/{}", doi.as_str(), Self::CSL_JSON);
+        self.get_json(&path, &[]).await
+    }
+
+    /// Fetch the UNIXSD XML record for a DOI.
+    pub async fn work_unixsd(&self, doi: &Doi) -> std::result::Result<UnixsdRecord, CrossrefError> {
+        let path = format!("works/{}/transform/{}", doi.as_str(), Self::UNIXSD);
+        let xml = self.get_text(&path).await?;
+        UnixsdRecord::parse(&xml)
+    }
+
+    /// Fetch a single page of `/works` search results.
+    pub async fn works(
+        &self,
+        query: &WorksQuery,
+    ) -> std::result::Result<CrossrefWorkList, CrossrefError> {
+        self.works_page("works", query, None).await
+    }
+
+    /// Stream every work matching the query, following `cursor` deep paging.
+    pub fn works_stream(&self, query: WorksQuery) -> WorksStream<'_> {
+        WorksStream::new(self, "works".to_string(), query)
+    }
+
+    /// Fetch a journal by ISSN.
+    pub async fn journal(&self, issn: &str) -> std::result::Result<CrossrefJournal, CrossrefError> {
+        let path = format!("journals/{}", issn.trim());
+        self.message(&path, "journal").await
+    }
+
+    /// Stream the works published in a journal.
+    pub fn journal_works(&self, issn: &str, query: WorksQuery) -> WorksStream<'_> {
+        WorksStream::new(self, format!("journals/{}/works", issn.trim()), query)
+    }
+
+    /// Fetch a member by its Crossref member id.
+    pub async fn member(&self, id: u64) -> std::result::Result<CrossrefMember, CrossrefError> {
+        let path = format!("members/{}", id);
+        self.message(&path, "member").await
+    }
+
+    /// Fetch the owner of a DOI prefix (e.g. `10.5555`).
+    pub async fn prefix(&self, prefix: &str) -> std::result::Result<CrossrefPrefix, CrossrefError> {
+        let path = format!("prefixes/{}", prefix.trim());
+        self.message(&path, "prefix").await
+    }
+
+    /// Fetch the owner of the prefix of a DOI.
+    pub async fn prefix_of(&self, doi: &Doi) -> std::result::Result<CrossrefPrefix, CrossrefError> {
+        let prefix = doi.prefix().context(MissingPrefixSnafu {
+            stage: "doi-prefix",
+            doi: doi.as_str().to_string(),
+        })?;
+        self.prefix(prefix).await
+    }
+
+    /// Fetch a funder by Funder Registry id or DOI.
+    pub async fn funder(
+        &self,
+        id: &str,
+    ) -> std::result::Result<CrossrefFunderRecord, CrossrefError> {
+        let path = format!("funders/{}", Self::funder_id(id));
+        self.message(&path, "funder").await
+    }
+
+    /// Stream the works funded by a funder.
+    pub fn funder_works(&self, id: &str, query: WorksQuery) -> WorksStream<'_> {
+        WorksStream::new(
+            self,
+            format!("funders/{}/works", Self::funder_id(id)),
+            query,
+        )
+    }
+
+    /// Fetch every work type known to Crossref.
+    pub async fn types(&self) -> std::result::Result<Vec<CrossrefType>, CrossrefError> {
+        self.message::<CrossrefList<CrossrefType>>("types", "type-list")
+            .await
+            .map(|list| list.items)
+    }
+
+    /// Fetch one page of works from a works endpoint.
+    pub(crate) async fn works_page(
+        &self,
+        path: &str,
+        query: &WorksQuery,
+        cursor: Option<&str>,
+    ) -> std::result::Result<CrossrefWorkList, CrossrefError> {
+        self.get_message(path, &query.to_query_pairs(cursor), "work-list")
+            .await
+            .map(|response| response.message)
+    }
+
+    /// Fetch a path without query parameters and return the message payload.
+    async fn message<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        message_type: &str,
+    ) -> std::result::Result<T, CrossrefError> {
+        self.get_message(path, &[], message_type)
+            .await
+            .map(|response| response.message)
+    }
+
+    /// Fetch a path and check the envelope's message type.
+    pub(crate) async fn get_message<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+        message_type: &str,
+    ) -> std::result::Result<CrossrefResponse<T>, CrossrefError> {
+        let response: CrossrefResponse<T> = self.get_json(path, query).await?;
+        ensure!(
+            response.message_type == message_type,
+            MessageTypeSnafu {
+                stage: "check-message-type",
+                message_type: response.message_type.clone(),
+            }
+        );
+        Ok(response)
+    }
+
+    /// Fetch a path relative to the base URL and parse the JSON body.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+    ) -> std::result::Result<T, CrossrefError> {
+        let text = self.get_body(path, query).await?;
+
+        let mut deserializer = serde_json::Deserializer::from_str(&text);
+
+        serde_path_to_error::deserialize::<_, T>(&mut deserializer).context(SerializePathSnafu {
+            stage: "parse-json",
+        })
+    }
+
+    /// Fetch a path relative to the base URL and return the raw body.
+    async fn get_text(&self, path: &str) -> std::result::Result<String, CrossrefError> {
+        self.get_body(path, &[]).await
+    }
+
+    /// Send a rate-limited GET request and read the body.
+    async fn get_body(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+    ) -> std::result::Result<String, CrossrefError> {
+        let url = format!("{}/{}", self.base_url, path);
+        let _permit = self.limiter.acquire().await;
+
+        let response = self
+            .client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .context(RequestSnafu {
+                stage: "send-request",
+            })?
+            .error_for_status()
+            .context(RequestSnafu {
+                stage: "http-status",
+            })?;
+
+        response.text().await.context(RequestSnafu {
+            stage: "response-body",
+        })
+    }
+
+    /// Strip DOI and URL forms from a funder id (`10.13039/100000001` gives `100000001`).
+    fn funder_id(id: &str) -> &str {
+        let id = id.trim();
+        id.rsplit_once("10.13039/").map_or(id, |(_, suffix)| suffix)
+    }
+
+    /// Build default headers for the Crossref client.
+    fn default_headers(config: &CrossrefConfig) -> reqwest::header::HeaderMap {
+        let mut headers = user_agent_headers(config.user_agent_value(), config.mailto_value());
+        if let Some(token) = config.plus_token_value()
+            && let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token))
+        {
+            headers.insert(HeaderName::from_static(Self::PLUS_TOKEN_HEADER), value);
+        }
+        headers
+    }
+}
+
+#[async_trait]
+impl MetadataSource for CrossrefClient {
+    fn name(&self) -> &str {
+        "crossref"
+    }
+
+    async fn metadata(&self, doi: &Doi) -> std::result::Result<Record, SourceError> {
+        match self.csl(doi).await {
+            Ok(metadata) => Ok(Record::new(metadata, self.name())),
+            Err(CrossrefError::Request { source, .. }) if is_not_found(&source) => NotFoundSnafu {
+                source_name: self.name(),
+                doi: doi.as_str(),
+            }
+            .fail(),
+            Err(error) => Err(error).context(CrossrefSnafu { stage: "metadata" }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crossref::query::WorksFilter;
+    use futures::StreamExt;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const WORK_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/crossref/work.json"
+    ));
+
+    /// Build a client pointed at the mock server.
+    fn client(server: &MockServer, config: CrossrefConfig) -> CrossrefClient {
+        CrossrefClient::new(CrossrefConfig {
+            base_url: server.uri(),
+            ..config
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    /// Parses a full work record and its envelope.
+    async fn crossref_work_parses_fixture() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/works/10.5555/12345678"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(WORK_FIXTURE))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5555/12345678").unwrap();
+        let response = client(&server, CrossrefConfig::default())
+            .work_response(&doi)
+            .await
+            .unwrap();
+        assert_eq!(response.status, "ok");
+        assert_eq!(response.message_type, "work");
+        assert_eq!(response.message_version, "1.0.0");
+
+        let work = response.message;
+        assert_eq!(work.doi, "10.5555/12345678");
+        assert_eq!(
+            work.primary_title(),
+            Some("Toward a Unified Theory of High-Energy Metaphysics: Silly String Theory")
+        );
+        assert_eq!(
+            work.author[0].orcid.as_deref(),
+            Some("http://orcid.org/0000-0002-1825-0097")
+        );
+        assert_eq!(work.funder[0].award, vec!["12345".to_string()]);
+        assert_eq!(work.license[0].content_version.as_deref(), Some("vor"));
+        assert_eq!(work.link.len(), 1);
+        assert_eq!(
+            work.clinical_trial_number[0].registry.as_deref(),
+            Some("10.18810/isrctn")
+        );
+        assert_eq!(work.update_to[0].update_type, "correction");
+        assert_eq!(work.relation["is-preprint-of"][0].id_type, "doi");
+        assert_eq!(work.assertion[0].name, "received");
+        assert_eq!(work.reference[0].key, "ref1");
+        assert_eq!(
+            work.issued.as_ref().and_then(|date| date.year()),
+            Some(2008)
+        );
+    }
+
+    #[tokio::test]
+    /// Serves the CSL-JSON transform as a metadata source and maps 404 to not found.
+    async fn crossref_metadata_source() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/works/10.5555/12345678/transform/application/vnd.citationstyles.csl+json",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"DOI": "10.5555/12345678", "type": "article-journal", "title": "Toward a Unified Theory"}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let source: Box<dyn MetadataSource> = Box::new(client(&server, CrossrefConfig::default()));
+        let doi = Doi::parse("10.5555/12345678").unwrap();
+        let record = source.metadata(&doi).await.unwrap();
+        assert_eq!(record.source, "crossref");
+        assert_eq!(
+            record.metadata.title.as_deref(),
+            Some("Toward a Unified Theory")
+        );
+
+        let missing = Doi::parse("10.5555/missing").unwrap();
+        assert!(source.metadata(&missing).await.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    /// Fetches and parses the UNIXSD XML transform.
+    async fn crossref_work_unixsd_parses_fixture() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/crossref/unixsd.xml"
+        ));
+        Mock::given(method("GET"))
+            .and(path(
+                "/works/10.5555/12345678/transform/application/vnd.crossref.unixsd+xml",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_string(fixture))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5555/12345678").unwrap();
+        let record = client(&server, CrossrefConfig::default())
+            .work_unixsd(&doi)
+            .await
+            .unwrap();
+        assert_eq!(record.doi, "10.5555/12345678");
+        assert_eq!(record.funding.len(), 1);
+    }
+
+    #[tokio::test]
+    /// Sends the polite User-Agent and the Plus API token.
+    async fn crossref_work_sends_identification_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/works/10.5555/12345678"))
+            .and(header("user-agent", "my-app mailto:me@example.com"))
+            .and(header("crossref-plus-api-token", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(WORK_FIXTURE))
+            .mount(&server)
+            .await;
+
+        let config = CrossrefConfig {
+            user_agent: Some("my-app".to_string()),
+            ..Default::default()
+        }
+        .polite("me@example.com")
+        .plus("secret");
+        let doi = Doi::parse("10.5555/12345678").unwrap();
+        assert!(client(&server, config).work(&doi).await.is_ok());
+    }
+
+    #[tokio::test]
+    /// Surfaces HTTP errors for unknown DOIs.
+    async fn crossref_work_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Resource not found."))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5555/missing").unwrap();
+        let error = client(&server, CrossrefConfig::default())
+            .work(&doi)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CrossrefError::Request {
+                stage: "http-status",
+                ..
+            }
+        ));
+    }
+
+    /// Build a work-list page body with the given DOIs.
+    fn work_list(dois: &[&str], total: u64, next_cursor: &str) -> String {
+        let items: Vec<serde_json::Value> = dois
+            .iter()
+            .map(|doi| serde_json::json!({"DOI": doi, "type": "journal-article"}))
+            .collect();
+        serde_json::json!({
+            "status": "ok",
+            "message-type": "work-list",
+            "message-version": "1.0.0",
+            "message": {
+                "facets": {"type-name": {"value-count": 1, "values": {"Journal Article": total}}},
+                "next-cursor": next_cursor,
+                "total-results": total,
+                "items": items,
+                "items-per-page": 2
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    /// Walks every page with cursor deep paging and exposes total-results.
+    async fn crossref_works_stream_follows_cursor() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/works"))
+            .and(query_param("cursor", "*"))
+            .and(query_param("filter", "prefix:10.5555"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(work_list(
+                &["10.5555/1", "10.5555/2"],
+                3,
+                "page-2",
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/works"))
+            .and(query_param("cursor", "page-2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(work_list(
+                &["10.5555/3"],
+                3,
+                "page-3",
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, CrossrefConfig::default());
+        let query = WorksQuery::new()
+            .filter(WorksFilter::Prefix("10.5555".to_string()))
+            .rows(2);
+        let mut stream = client.works_stream(query);
+        assert_eq!(stream.total_results(), None);
+
+        let mut dois = Vec::new();
+        while let Some(work) = stream.next().await {
+            dois.push(work.unwrap().doi);
+        }
+        assert_eq!(dois, ["10.5555/1", "10.5555/2", "10.5555/3"]);
+        assert_eq!(stream.total_results(), Some(3));
+    }
+
+    #[tokio::test]
+    /// Returns facets from a single search page.
+    async fn crossref_works_page_facets() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/works"))
+            .and(query_param("facet", "type-name:*"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(work_list(
+                &["10.5555/1"],
+                1,
+                "x",
+            )))
+            .mount(&server)
+            .await;
+
+        let query = WorksQuery::new().facet("type-name", None).rows(0);
+        let page = client(&server, CrossrefConfig::default())
+            .works(&query)
+            .await
+            .unwrap();
+        assert_eq!(page.facets["type-name"].values["Journal Article"], 1);
+    }
+
+    /// Mount a fixture file at a path.
+    async fn mount_fixture(server: &MockServer, at: &str, fixture: &str) {
+        Mock::given(method("GET"))
+            .and(path(at))
+            .respond_with(ResponseTemplate::new(200).set_body_string(fixture))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    /// Parses journal coverage, counts and breakdowns.
+    async fn crossref_journal_parses_fixture() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/crossref/journal.json"
+        ));
+        mount_fixture(&server, "/journals/0264-3561", fixture).await;
+
+        let journal = client(&server, CrossrefConfig::default())
+            .journal("0264-3561")
+            .await
+            .unwrap();
+        assert_eq!(journal.title, "Journal of Psychoceramics");
+        assert_eq!(journal.counts.total_dois, 10);
+        assert_eq!(journal.breakdowns.dois_by_issued_year[0], (2008, 6));
+        assert_eq!(journal.coverage["abstracts-current"], 0.5);
+        assert!(journal.flags["deposits-orcids-current"]);
+        assert_eq!(journal.subjects[0].asjc, Some(3200));
+    }
+
+    #[tokio::test]
+    /// Parses member prefixes and per-type counts.
+    async fn crossref_member_parses_fixture() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/crossref/member.json"
+        ));
+        mount_fixture(&server, "/members/7822", fixture).await;
+
+        let member = client(&server, CrossrefConfig::default())
+            .member(7822)
+            .await
+            .unwrap();
+        assert_eq!(member.primary_name, "Crossref");
+        assert_eq!(member.prefixes, vec!["10.5555".to_string()]);
+        assert_eq!(member.prefix[0].public_references, Some(true));
+        assert_eq!(member.counts_type["all"]["journal-article"], 8);
+    }
+
+    #[tokio::test]
+    /// Resolves the prefix owner of a DOI.
+    async fn crossref_prefix_of_doi() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/crossref/prefix.json"
+        ));
+        mount_fixture(&server, "/prefixes/10.5555", fixture).await;
+
+        let doi = Doi::parse("10.5555/12345678").unwrap();
+        let prefix = client(&server, CrossrefConfig::default())
+            .prefix_of(&doi)
+            .await
+            .unwrap();
+        assert_eq!(prefix.name, "Society of Psychoceramics");
+        assert_eq!(prefix.member_id(), Some(7822));
+    }
+
+    #[tokio::test]
+    /// Accepts funder DOIs and parses the funder hierarchy.
+    async fn crossref_funder_parses_fixture() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/crossref/funder.json"
+        ));
+        mount_fixture(&server, "/funders/100000001", fixture).await;
+
+        let funder = client(&server, CrossrefConfig::default())
+            .funder("https://doi.org/10.13039/100000001")
+            .await
+            .unwrap();
+        assert_eq!(funder.name, "National Science Foundation");
+        assert_eq!(funder.alt_names, vec!["NSF".to_string()]);
+        assert_eq!(funder.work_count, Some(420));
+        assert_eq!(funder.descendants.len(), 2);
+    }
+
+    #[tokio::test]
+    /// Streams the works of a funder.
+    async fn crossref_funder_works_stream() {
+        let server = MockServer::start().await;
+        mount_fixture(
+            &server,
+            "/funders/100000001/works",
+            &work_list(&["10.5555/1"], 1, "next"),
+        )
+        .await;
+
+        let client = client(&server, CrossrefConfig::default());
+        let works: Vec<_> = client
+            .funder_works("10.13039/100000001", WorksQuery::new())
+            .collect()
+            .await;
+        assert_eq!(works.len(), 1);
+    }
+
+    #[tokio::test]
+    /// Lists the work types.
+    async fn crossref_types_parses_fixture() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/crossref/types.json"
+        ));
+        mount_fixture(&server, "/types", fixture).await;
+
+        let types = client(&server, CrossrefConfig::default())
+            .types()
+            .await
+            .unwrap();
+        assert_eq!(types.len(), 3);
+        assert_eq!(types[0].id, "book-section");
+    }
+
+    #[test]
+    /// Picks pool defaults from the presence of a contact email.
+    fn crossref_config_pool_defaults() {
+        let config = CrossrefConfig::default();
+        assert_eq!(
+            (config.rate_limit_value(), config.concurrency_value()),
+            (5, 1)
+        );
+        let config = config.polite("me@example.com");
+        assert_eq!(
+            (config.rate_limit_value(), config.concurrency_value()),
+            (10, 3)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+/// Configuration for the Crossref REST API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossrefConfig {
+    /// Base URL for the Crossref REST API (default: https://api.crossref.org).
+    pub base_url: String,
+    /// HTTP client timeout applied to each request.
+    pub timeout: Duration,
+    /// Contact email used for the polite pool in the User-Agent header.
+    pub mailto: Option<String>,
+    /// Application identifier used in the User-Agent header.
+    pub user_agent: Option<String>,
+    /// Metadata Plus API token sent as `Crossref-Plus-API-Token`.
+    pub plus_token: Option<String>,
+    /// Maximum requests per second; `None` picks a default from the pool.
+    pub rate_limit_per_sec: Option<u32>,
+    /// Maximum concurrent requests; `None` picks a default from the pool.
+    pub concurrency: Option<usize>,
+}
+
+impl Default for CrossrefConfig {
+    /// Return default Crossref client configuration values.
+    fn default() -> Self {
+        Self {
+            base_url: "https://api.crossref.org".to_string(),
+            timeout: Duration::from_secs(30),
+            mailto: None,
+            user_agent: None,
+            plus_token: None,
+            rate_limit_per_sec: None,
+            concurrency: None,
+        }
+    }
+}
+
+impl CrossrefConfig {
+    /// Return trimmed base URL without trailing slash.
+    pub fn base_url_value(&self) -> String {
+        self.base_url.trim_end_matches('/').to_string()
+    }
+
+    /// Return trimmed mailto when configured.
+    pub fn mailto_value(&self) -> Option<&str> {
+        self.mailto
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Return trimmed user-agent when configured.
+    pub fn user_agent_value(&self) -> Option<&str> {
+        self.user_agent
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Return trimmed Plus API token when configured.
+    pub fn plus_token_value(&self) -> Option<&str> {
+        self.plus_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Return the request rate, defaulting to 10/s in the polite pool and 5/s otherwise.
+    pub fn rate_limit_value(&self) -> u32 {
+        self.rate_limit_per_sec
+            .unwrap_or(if self.mailto_value().is_some() { 10 } else { 5 })
+    }
+
+    /// Return the concurrency, defaulting to 3 in the polite pool and 1 otherwise.
+    pub fn concurrency_value(&self) -> usize {
+        self.concurrency
+            .unwrap_or(if self.mailto_value().is_some() { 3 } else { 1 })
+    }
+
+    /// Configure polite access with a contact email.
+    pub fn polite(mut self, email: &str) -> Self {
+        let trimmed = email.trim();
+        if !trimmed.is_empty() {
+            self.mailto = Some(trimmed.to_string());
+        }
+        self
+    }
+
+    /// Configure a Metadata Plus API token.
+    pub fn plus(mut self, token: &str) -> Self {
+        let trimmed = token.trim();
+        if !trimmed.is_empty() {
+            self.plus_token = Some(trimmed.to_string());
+        }
+        self
+    }
+}
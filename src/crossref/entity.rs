@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::crossref::work::CrossrefTypedValue;
+
+/// DOI counts for a journal or member.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefCounts {
+    /// DOIs published in the current and previous two years.
+    #[serde(default)]
+    pub current_dois: u64,
+    /// DOIs published before the current window.
+    #[serde(default)]
+    pub backfile_dois: u64,
+    /// All registered DOIs.
+    #[serde(default)]
+    pub total_dois: u64,
+}
+
+/// Breakdowns of registered DOIs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefBreakdowns {
+    /// `[year, count]` pairs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dois_by_issued_year: Vec<(i32, u64)>,
+}
+
+/// A subject classification of a journal.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrossrefSubject {
+    /// All Science Journal Classification code.
+    #[serde(rename = "ASJC", skip_serializing_if = "Option::is_none")]
+    pub asjc: Option<u32>,
+    pub name: String,
+}
+
+/// A journal from `/journals/{issn}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefJournal {
+    /// journal title
+    pub title: String,
+    /// journal publisher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// journal ISSNs
+    #[serde(rename = "ISSN", default, skip_serializing_if = "Vec::is_empty")]
+    pub issn: Vec<String>,
+    /// journal typed ISSNs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issn_type: Vec<CrossrefTypedValue>,
+    /// journal subjects
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subjects: Vec<CrossrefSubject>,
+    /// journal DOI counts
+    #[serde(default)]
+    pub counts: CrossrefCounts,
+    /// journal DOI breakdowns
+    #[serde(default)]
+    pub breakdowns: CrossrefBreakdowns,
+    /// journal metadata coverage ratios (e.g. `abstracts-current`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub coverage: BTreeMap<String, f64>,
+    /// journal coverage per period (`all`, `current`, `backfile`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub coverage_type: BTreeMap<String, Value>,
+    /// journal deposit flags (e.g. `deposits-orcids-current`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub flags: BTreeMap<String, bool>,
+    /// journal last status check (epoch milliseconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status_check_time: Option<i64>,
+    /// Any key not modeled above, kept verbatim for lossless round-trips.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+/// A prefix owned by a member, as listed in the member record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefMemberPrefix {
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_references: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_visibility: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backfile_dois: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_dois: Option<u64>,
+}
+
+/// A Crossref member from `/members/{id}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefMember {
+    /// member id
+    pub id: u64,
+    /// member primary name
+    pub primary_name: String,
+    /// member alternative names
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+    /// member location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// member prefixes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<String>,
+    /// member prefixes with details
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefix: Vec<CrossrefMemberPrefix>,
+    /// member DOI counts
+    #[serde(default)]
+    pub counts: CrossrefCounts,
+    /// member DOI counts per period and work type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counts_type: BTreeMap<String, BTreeMap<String, u64>>,
+    /// member DOI breakdowns
+    #[serde(default)]
+    pub breakdowns: CrossrefBreakdowns,
+    /// member metadata coverage ratios
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub coverage: BTreeMap<String, f64>,
+    /// member coverage per period and work type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub coverage_type: BTreeMap<String, Value>,
+    /// member deposit flags
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub flags: BTreeMap<String, bool>,
+    /// member last status check (epoch milliseconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status_check_time: Option<i64>,
+    /// Any key not modeled above, kept verbatim for lossless round-trips.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+/// A DOI prefix from `/prefixes/{prefix}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefPrefix {
+    /// member URI, e.g. `http://id.crossref.org/member/7822`
+    pub member: String,
+    /// prefix owner name
+    pub name: String,
+    /// prefix URI, e.g. `http://id.crossref.org/prefix/10.5555`
+    pub prefix: String,
+}
+
+impl CrossrefPrefix {
+    /// Return the numeric member id from the member URI.
+    pub fn member_id(&self) -> Option<u64> {
+        self.member.rsplit('/').next()?.parse().ok()
+    }
+}
+
+/// A funder from `/funders/{id}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefFunderRecord {
+    /// funder id in the Funder Registry
+    pub id: String,
+    /// funder name
+    pub name: String,
+    /// funder DOI URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// funder country
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// funder alternative names
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alt_names: Vec<String>,
+    /// funder ids this funder replaces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaces: Vec<String>,
+    /// funder ids replacing this funder
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaced_by: Vec<String>,
+    /// funder descendant ids
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub descendants: Vec<String>,
+    /// funder hierarchy (nested ids)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hierarchy: Option<Value>,
+    /// funder names for the ids in the hierarchy
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hierarchy_names: BTreeMap<String, Option<String>>,
+    /// works funded directly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub work_count: Option<u64>,
+    /// works funded by this funder and its descendants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descendant_work_count: Option<u64>,
+    /// Any key not modeled above, kept verbatim for lossless round-trips.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+/// A Crossref work type from `/types`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefType {
+    /// type id, e.g. `journal-article`
+    pub id: String,
+    /// type label, e.g. `Journal Article`
+    pub label: String,
+}
+
+/// A list of items with its total, as returned by the list endpoints.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefList<T> {
+    #[serde(default)]
+    pub total_results: u64,
+    #[serde(default)]
+    pub items: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items_per_page: Option<u64>,
+}
@@ -0,0 +1,39 @@
+use snafu::Snafu;
+
+/// Errors returned by the Crossref REST API client.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum CrossrefError {
+    #[snafu(display("HTTP request failed at {stage}: {source}"))]
+    Request {
+        stage: &'static str,
+        source: reqwest::Error,
+    },
+
+    #[snafu(display("Failed to serialize at {stage}: {source}"))]
+    SerializePath {
+        source: serde_path_to_error::Error<serde_json::Error>,
+        stage: &'static str,
+    },
+
+    #[snafu(display("Unexpected Crossref message type at {stage}: {message_type}"))]
+    MessageType {
+        stage: &'static str,
+        message_type: String,
+    },
+
+    #[snafu(display("DOI has no valid prefix at {stage}: {doi}"))]
+    MissingPrefix { stage: &'static str, doi: String },
+
+    #[snafu(display("Failed to parse XML at {stage}: {source}"))]
+    Xml {
+        stage: &'static str,
+        source: roxmltree::Error,
+    },
+
+    #[snafu(display("Unexpected XML record at {stage}: {message}"))]
+    XmlRecord {
+        stage: &'static str,
+        message: &'static str,
+    },
+}
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod config;
+pub mod entity;
+pub mod error;
+pub mod query;
+pub mod unixsd;
+pub mod work;
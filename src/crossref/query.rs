@@ -0,0 +1,430 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use chrono::NaiveDate;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::crossref::client::CrossrefClient;
+use crate::crossref::error::CrossrefError;
+use crate::crossref::work::CrossrefWork;
+
+/// A filter supported by the Crossref `/works` endpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorksFilter {
+    /// Published on or after the date.
+    FromPubDate(NaiveDate),
+    /// Published on or before the date.
+    UntilPubDate(NaiveDate),
+    /// Crossref work type, e.g. `journal-article`.
+    Type(String),
+    /// Whether the work has an abstract.
+    HasAbstract(bool),
+    /// Whether any contributor has an ORCID.
+    HasOrcid(bool),
+    /// Whether the work has funder information.
+    HasFunder(bool),
+    /// Whether the work has references.
+    HasReferences(bool),
+    /// Whether the work has full-text links.
+    HasFullText(bool),
+    /// DOI prefix, e.g. `10.5555`.
+    Prefix(String),
+    /// Crossref member id.
+    Member(String),
+    /// Funder Registry id or DOI.
+    Funder(String),
+    /// Container ISSN.
+    Issn(String),
+    /// Any other filter, given as name and value.
+    Custom(String, String),
+}
+
+impl WorksFilter {
+    /// Return the filter as a `name:value` pair.
+    fn to_pair(&self) -> (String, String) {
+        let (name, value) = match self {
+            WorksFilter::FromPubDate(date) => ("from-pub-date", date.to_string()),
+            WorksFilter::UntilPubDate(date) => ("until-pub-date", date.to_string()),
+            WorksFilter::Type(value) => ("type", value.clone()),
+            WorksFilter::HasAbstract(flag) => ("has-abstract", flag.to_string()),
+            WorksFilter::HasOrcid(flag) => ("has-orcid", flag.to_string()),
+            WorksFilter::HasFunder(flag) => ("has-funder", flag.to_string()),
+            WorksFilter::HasReferences(flag) => ("has-references", flag.to_string()),
+            WorksFilter::HasFullText(flag) => ("has-full-text", flag.to_string()),
+            WorksFilter::Prefix(value) => ("prefix", value.clone()),
+            WorksFilter::Member(value) => ("member", value.clone()),
+            WorksFilter::Funder(value) => ("funder", value.clone()),
+            WorksFilter::Issn(value) => ("issn", value.clone()),
+            WorksFilter::Custom(name, value) => return (name.clone(), value.clone()),
+        };
+        (name.to_string(), value)
+    }
+}
+
+/// Sort keys supported by the Crossref `/works` endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorksSort {
+    Score,
+    Relevance,
+    Updated,
+    Deposited,
+    Indexed,
+    Published,
+    PublishedPrint,
+    PublishedOnline,
+    Issued,
+    IsReferencedByCount,
+    ReferencesCount,
+    Created,
+}
+
+impl WorksSort {
+    /// Return the API name of the sort key.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorksSort::Score => "score",
+            WorksSort::Relevance => "relevance",
+            WorksSort::Updated => "updated",
+            WorksSort::Deposited => "deposited",
+            WorksSort::Indexed => "indexed",
+            WorksSort::Published => "published",
+            WorksSort::PublishedPrint => "published-print",
+            WorksSort::PublishedOnline => "published-online",
+            WorksSort::Issued => "issued",
+            WorksSort::IsReferencedByCount => "is-referenced-by-count",
+            WorksSort::ReferencesCount => "references-count",
+            WorksSort::Created => "created",
+        }
+    }
+}
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    /// Return the API name of the sort order.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+/// Builder for Crossref `/works` search parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorksQuery {
+    query: Option<String>,
+    field_queries: Vec<(String, String)>,
+    filters: Vec<WorksFilter>,
+    select: Vec<String>,
+    sort: Option<WorksSort>,
+    order: Option<SortOrder>,
+    rows: Option<u32>,
+    sample: Option<u32>,
+    facets: Vec<(String, Option<u32>)>,
+}
+
+impl WorksQuery {
+    /// Create an empty query matching every work.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Free-text query over all fields.
+    pub fn query(mut self, text: &str) -> Self {
+        self.query = Some(text.to_string());
+        self
+    }
+
+    /// Query titles, authors, ISSNs and years (`query.bibliographic`).
+    pub fn bibliographic(self, text: &str) -> Self {
+        self.field_query("bibliographic", text)
+    }
+
+    /// Query contributor names (`query.author`).
+    pub fn author(self, text: &str) -> Self {
+        self.field_query("author", text)
+    }
+
+    /// Query container titles (`query.container-title`).
+    pub fn container_title(self, text: &str) -> Self {
+        self.field_query("container-title", text)
+    }
+
+    /// Add any `query.<field>` parameter.
+    pub fn field_query(mut self, field: &str, text: &str) -> Self {
+        self.field_queries
+            .push((format!("query.{}", field), text.to_string()));
+        self
+    }
+
+    /// Add a filter; filters are combined with AND.
+    pub fn filter(mut self, filter: WorksFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Limit returned fields (`select`).
+    pub fn select(mut self, field: &str) -> Self {
+        self.select.push(field.to_string());
+        self
+    }
+
+    /// Sort results.
+    pub fn sort(mut self, sort: WorksSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    /// Set the sort direction.
+    pub fn order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Set results per page (Crossref allows up to 1000).
+    pub fn rows(mut self, rows: u32) -> Self {
+        self.rows = Some(rows);
+        self
+    }
+
+    /// Return a random sample of works instead of a result list.
+    pub fn sample(mut self, size: u32) -> Self {
+        self.sample = Some(size);
+        self
+    }
+
+    /// Request a facet, optionally limiting the number of values (`*` for all).
+    pub fn facet(mut self, name: &str, limit: Option<u32>) -> Self {
+        self.facets.push((name.to_string(), limit));
+        self
+    }
+
+    /// Return true when the query asks for a random sample.
+    pub fn is_sample(&self) -> bool {
+        self.sample.is_some()
+    }
+
+    /// Build the query-string pairs, adding the deep-paging cursor when given.
+    pub fn to_query_pairs(&self, cursor: Option<&str>) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        if let Some(query) = &self.query {
+            pairs.push(("query".to_string(), query.clone()));
+        }
+        pairs.extend(self.field_queries.iter().cloned());
+        if !self.filters.is_empty() {
+            let filter = self
+                .filters
+                .iter()
+                .map(|filter| {
+                    let (name, value) = filter.to_pair();
+                    format!("{}:{}", name, value)
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            pairs.push(("filter".to_string(), filter));
+        }
+        if !self.select.is_empty() {
+            pairs.push(("select".to_string(), self.select.join(",")));
+        }
+        if let Some(sort) = self.sort {
+            pairs.push(("sort".to_string(), sort.as_str().to_string()));
+        }
+        if let Some(order) = self.order {
+            pairs.push(("order".to_string(), order.as_str().to_string()));
+        }
+        if let Some(rows) = self.rows {
+            pairs.push(("rows".to_string(), rows.to_string()));
+        }
+        if let Some(sample) = self.sample {
+            pairs.push(("sample".to_string(), sample.to_string()));
+        }
+        if !self.facets.is_empty() {
+            let facet = self
+                .facets
+                .iter()
+                .map(|(name, limit)| match limit {
+                    Some(limit) => format!("{}:{}", name, limit),
+                    None => format!("{}:*", name),
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            pairs.push(("facet".to_string(), facet));
+        }
+        if let Some(cursor) = cursor {
+            pairs.push(("cursor".to_string(), cursor.to_string()));
+        }
+        pairs
+    }
+}
+
+/// One page of `/works` results (`message-type: work-list`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefWorkList {
+    /// Total number of matching works.
+    #[serde(default)]
+    pub total_results: u64,
+    /// Works on this page.
+    #[serde(default)]
+    pub items: Vec<CrossrefWork>,
+    /// Page size used by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items_per_page: Option<u64>,
+    /// Cursor for the next page when deep paging.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Requested facets keyed by facet name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub facets: BTreeMap<String, CrossrefFacet>,
+    /// Echo of the query parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<Value>,
+}
+
+/// Counts for one facet.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefFacet {
+    /// Number of distinct values.
+    #[serde(default)]
+    pub value_count: u64,
+    /// Works per facet value.
+    #[serde(default)]
+    pub values: BTreeMap<String, u64>,
+}
+
+/// Paging state of a [`WorksStream`].
+struct PageState {
+    cursor: Option<String>,
+    buffer: VecDeque<CrossrefWork>,
+    fetched: u64,
+    finished: bool,
+}
+
+/// An async stream of works walking Crossref results with `cursor` deep paging.
+pub struct WorksStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<CrossrefWork, CrossrefError>> + Send + 'a>>,
+    total: Arc<OnceLock<u64>>,
+}
+
+impl<'a> WorksStream<'a> {
+    /// Build a stream over a works endpoint (e.g. `works`, `journals/{issn}/works`).
+    pub(crate) fn new(client: &'a CrossrefClient, path: String, query: WorksQuery) -> Self {
+        let total = Arc::new(OnceLock::new());
+        let shared_total = Arc::clone(&total);
+        let sample = query.is_sample();
+        let state = PageState {
+            cursor: (!sample).then(|| "*".to_string()),
+            buffer: VecDeque::new(),
+            fetched: 0,
+            finished: false,
+        };
+
+        let inner = futures::stream::try_unfold(state, move |mut state| {
+            let path = path.clone();
+            let query = query.clone();
+            let total = Arc::clone(&shared_total);
+            async move {
+                loop {
+                    if let Some(work) = state.buffer.pop_front() {
+                        return Ok(Some((work, state)));
+                    }
+                    if state.finished {
+                        return Ok(None);
+                    }
+
+                    let page = client
+                        .works_page(&path, &query, state.cursor.as_deref())
+                        .await?;
+                    let _ = total.set(page.total_results);
+                    state.fetched += page.items.len() as u64;
+                    state.finished = sample
+                        || page.items.is_empty()
+                        || page.next_cursor.is_none()
+                        || state.fetched >= page.total_results;
+                    state.cursor = page.next_cursor;
+                    state.buffer.extend(page.items);
+                }
+            }
+        });
+
+        Self {
+            inner: Box::pin(inner),
+            total,
+        }
+    }
+
+    /// Return `total-results` once the first page has been fetched.
+    pub fn total_results(&self) -> Option<u64> {
+        self.total.get().copied()
+    }
+}
+
+impl Stream for WorksStream<'_> {
+    type Item = Result<CrossrefWork, CrossrefError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Serializes every builder option into Crossref query parameters.
+    fn works_query_pairs() {
+        let query = WorksQuery::new()
+            .bibliographic("psychoceramics carberry")
+            .author("Carberry")
+            .container_title("Journal of Psychoceramics")
+            .filter(WorksFilter::FromPubDate(
+                NaiveDate::from_ymd_opt(2008, 1, 1).unwrap(),
+            ))
+            .filter(WorksFilter::Type("journal-article".to_string()))
+            .filter(WorksFilter::HasAbstract(true))
+            .filter(WorksFilter::Prefix("10.5555".to_string()))
+            .select("DOI")
+            .select("title")
+            .sort(WorksSort::Published)
+            .order(SortOrder::Desc)
+            .rows(100)
+            .facet("type-name", None)
+            .facet("publisher-name", Some(10));
+
+        let pairs = query.to_query_pairs(Some("*"));
+        let get = |name: &str| {
+            pairs
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("query.bibliographic"), Some("psychoceramics carberry"));
+        assert_eq!(get("query.author"), Some("Carberry"));
+        assert_eq!(
+            get("query.container-title"),
+            Some("Journal of Psychoceramics")
+        );
+        assert_eq!(
+            get("filter"),
+            Some("from-pub-date:2008-01-01,type:journal-article,has-abstract:true,prefix:10.5555")
+        );
+        assert_eq!(get("select"), Some("DOI,title"));
+        assert_eq!(get("sort"), Some("published"));
+        assert_eq!(get("order"), Some("desc"));
+        assert_eq!(get("rows"), Some("100"));
+        assert_eq!(get("facet"), Some("type-name:*,publisher-name:10"));
+        assert_eq!(get("cursor"), Some("*"));
+    }
+}
@@ -0,0 +1,1099 @@
+//! Parsing of Crossref UNIXSD and UNIXREF XML records.
+//!
+//! Elements are matched by local name, so the schema versions and namespace
+//! prefixes (`jats:`, `fr:`, `ai:`) used by different deposits all parse.
+
+use std::collections::BTreeMap;
+
+use roxmltree::{Document, Node, ParsingOptions};
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+
+use crate::crossref::error::*;
+use crate::crossref::work::CrossrefTypedValue;
+use crate::csl::{
+    Affiliation, CslDate, CslItemType, CslName, CslValue, DateParts, DoiMetadata, Reference,
+};
+
+/// The kind of registered work, from the XML element carrying its DOI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnixsdKind {
+    JournalArticle,
+    JournalIssue,
+    Journal,
+    Book,
+    BookChapter,
+    ConferencePaper,
+    Proceedings,
+    Dataset,
+    Database,
+    Dissertation,
+    Report,
+    PostedContent,
+    Component,
+    #[default]
+    Other,
+}
+
+/// A contributor with its Crossref role.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdContributor {
+    /// `author`, `editor`, `chair`, `translator`, ...
+    pub role: String,
+    /// `first` or `additional`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub surname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// organization name for `<organization>` contributors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affiliations: Vec<String>,
+}
+
+impl UnixsdContributor {
+    /// Convert to a CSL name, keeping organizations as literal names.
+    pub fn to_csl_name(&self) -> CslName {
+        let mut name = match (&self.organization, &self.surname) {
+            (Some(organization), _) => CslName::literal(organization),
+            (None, Some(surname)) => CslName::new(surname, self.given.as_deref().unwrap_or("")),
+            (None, None) => CslName::literal(self.given.as_deref().unwrap_or("")),
+        };
+        name.suffix = self.suffix.clone();
+        name.orcid = self.orcid.clone();
+        name.sequence = self.sequence.clone();
+        name.affiliation = self
+            .affiliations
+            .iter()
+            .map(|affiliation| Affiliation {
+                name: affiliation.clone(),
+                ..Default::default()
+            })
+            .collect();
+        name
+    }
+}
+
+/// A `publication_date`, `posted_date` or similar date element.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdDate {
+    /// `print` or `online` when given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    pub year: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<u8>,
+}
+
+impl UnixsdDate {
+    /// Convert to a CSL date.
+    pub fn to_csl_date(&self) -> CslDate {
+        CslDate::new(self.date_parts())
+    }
+
+    fn date_parts(&self) -> DateParts {
+        match (self.month, self.day) {
+            (Some(month), Some(day)) => DateParts::ymd(self.year, month, day),
+            (Some(month), None) => DateParts::year_month(self.year, month),
+            _ => DateParts::year_only(self.year),
+        }
+    }
+}
+
+/// A funder from the FundRef program.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdFunding {
+    pub name: String,
+    /// Funder Registry DOI, e.g. `https://doi.org/10.13039/100000001`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funder_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub awards: Vec<String>,
+}
+
+/// A Crossmark update notice pointing at the updated DOI.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdUpdate {
+    /// `correction`, `retraction`, ...
+    pub update_type: String,
+    pub doi: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+/// A Crossmark custom metadata assertion.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdAssertion {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub value: String,
+}
+
+/// Crossmark metadata of a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdCrossmark {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// DOI of the publisher's Crossmark policy page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub updates: Vec<UnixsdUpdate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<UnixsdAssertion>,
+}
+
+/// A component (figure, table, supplement) registered under a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdComponent {
+    pub doi: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// A work parsed from a Crossref UNIXSD or UNIXREF record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct UnixsdRecord {
+    /// record doi
+    pub doi: String,
+    /// record kind
+    pub kind: UnixsdKind,
+    /// record `doi type` from UNIXSD (e.g. `journal_article`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi_type: Option<String>,
+    /// record owner prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// record deposit timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// UNIXSD `crm-item` values keyed by name (e.g. `publisher-name`, `member-id`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub crm_items: BTreeMap<String, String>,
+    /// record titles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub titles: Vec<String>,
+    /// record subtitles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtitles: Vec<String>,
+    /// record titles in the original language
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub original_titles: Vec<String>,
+    /// record contributors in document order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<UnixsdContributor>,
+    /// record container title (journal, book, proceedings, database)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_title: Option<String>,
+    /// record abbreviated container title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_abbrev: Option<String>,
+    /// record ISSNs with media types
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issn: Vec<CrossrefTypedValue>,
+    /// record ISBNs with media types
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub isbn: Vec<CrossrefTypedValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_page: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_page: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_number: Option<String>,
+    /// record publication dates (print, online)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dates: Vec<UnixsdDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher_place: Option<String>,
+    /// record language code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// record abstracts as JATS markup, without the enclosing `abstract` element
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub abstracts: Vec<String>,
+    /// record landing page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// record institution (dissertations, reports)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub institution: Option<String>,
+    /// record degree (dissertations)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub degree: Option<String>,
+    /// record conference name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_place: Option<String>,
+    /// record posted content type (e.g. `preprint`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub posted_type: Option<String>,
+    /// record license URLs from the access indicators program
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<String>,
+    /// record funders from the FundRef program
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub funding: Vec<UnixsdFunding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crossmark: Option<UnixsdCrossmark>,
+    /// record components
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<UnixsdComponent>,
+    /// record citation list
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Reference>,
+}
+
+impl UnixsdRecord {
+    /// Parse the first record of a UNIXSD or UNIXREF document.
+    pub fn parse(xml: &str) -> std::result::Result<Self, CrossrefError> {
+        Self::parse_all(xml)?
+            .into_iter()
+            .next()
+            .context(XmlRecordSnafu {
+                stage: "find-record",
+                message: "no doi_record element",
+            })
+    }
+
+    /// Parse every record of a UNIXSD or UNIXREF document.
+    pub fn parse_all(xml: &str) -> std::result::Result<Vec<Self>, CrossrefError> {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document =
+            Document::parse_with_options(xml, options).context(XmlSnafu { stage: "parse-xml" })?;
+        document
+            .descendants()
+            .filter(|node| node.has_tag_name_local("doi_record"))
+            .map(parse_record)
+            .collect()
+    }
+
+    /// Return the main title.
+    pub fn primary_title(&self) -> Option<&str> {
+        self.titles.first().map(String::as_str)
+    }
+
+    /// Return the earliest publication date, print or online.
+    pub fn issued(&self) -> Option<&UnixsdDate> {
+        self.dates.iter().min_by_key(|date| date.date_parts())
+    }
+
+    /// Return the contributors with a role.
+    pub fn contributors_with_role<'a>(
+        &'a self,
+        role: &'a str,
+    ) -> impl Iterator<Item = &'a UnixsdContributor> + 'a {
+        self.contributors
+            .iter()
+            .filter(move |contributor| contributor.role == role)
+    }
+}
+
+impl From<UnixsdRecord> for DoiMetadata {
+    /// Map a UNIXSD record onto CSL-JSON variables.
+    fn from(record: UnixsdRecord) -> Self {
+        let names = |role: &str| -> Vec<CslName> {
+            record
+                .contributors_with_role(role)
+                .map(UnixsdContributor::to_csl_name)
+                .collect()
+        };
+        let media_date = |media: &str| {
+            record
+                .dates
+                .iter()
+                .find(|date| date.media_type.as_deref() == Some(media))
+                .map(UnixsdDate::to_csl_date)
+        };
+        let page = match (&record.first_page, &record.last_page) {
+            (Some(first), Some(last)) => Some(format!("{}-{}", first, last)),
+            (Some(first), None) => Some(first.clone()),
+            _ => None,
+        };
+        let item_type = match record.kind {
+            UnixsdKind::JournalArticle => CslItemType::ArticleJournal,
+            UnixsdKind::Journal | UnixsdKind::JournalIssue => CslItemType::Periodical,
+            UnixsdKind::Book => CslItemType::Book,
+            UnixsdKind::BookChapter => CslItemType::Chapter,
+            UnixsdKind::ConferencePaper => CslItemType::PaperConference,
+            UnixsdKind::Proceedings => CslItemType::Book,
+            UnixsdKind::Dataset | UnixsdKind::Database => CslItemType::Dataset,
+            UnixsdKind::Dissertation => CslItemType::Thesis,
+            UnixsdKind::Report => CslItemType::Report,
+            UnixsdKind::PostedContent => CslItemType::Article,
+            UnixsdKind::Component | UnixsdKind::Other => CslItemType::Document,
+        };
+        let title = record
+            .primary_title()
+            .map(|title| match record.subtitles.first() {
+                Some(subtitle) => format!("{}: {}", title, subtitle),
+                None => title.to_string(),
+            });
+        let abstract_text = (!record.abstracts.is_empty()).then(|| record.abstracts.join("\n\n"));
+
+        Self {
+            id: Some(record.doi.clone()),
+            item_type,
+            doi: Some(record.doi.clone()),
+            url: record.resource.clone(),
+            title,
+            original_title: record.original_titles.first().cloned(),
+            author: names("author"),
+            editor: names("editor"),
+            translator: names("translator"),
+            chair: names("chair"),
+            container_title: record.container_title.clone(),
+            container_title_short: record.container_abbrev.clone(),
+            journal_abbreviation: record.container_abbrev.clone(),
+            issn: record.issn.iter().map(|issn| issn.value.clone()).collect(),
+            isbn: record.isbn.iter().map(|isbn| isbn.value.clone()).collect(),
+            volume: record.volume.as_deref().map(CslValue::from),
+            issue: record.issue.as_deref().map(CslValue::from),
+            page: page.as_deref().map(CslValue::from),
+            page_first: record.first_page.as_deref().map(CslValue::from),
+            number: record.item_number.as_deref().map(CslValue::from),
+            issued: record.issued().map(UnixsdDate::to_csl_date),
+            published_print: media_date("print"),
+            published_online: media_date("online"),
+            publisher: record
+                .publisher
+                .clone()
+                .or_else(|| record.institution.clone()),
+            publisher_place: record.publisher_place.clone(),
+            language: record.language.clone(),
+            abstract_text,
+            event_title: record.event_name.clone(),
+            event_place: record.event_place.clone(),
+            genre: record.degree.clone().or_else(|| record.posted_type.clone()),
+            license: record.licenses.first().cloned(),
+            source: Some("Crossref".to_string()),
+            reference_count: Some(record.citations.len()),
+            reference: record.citations,
+            ..Default::default()
+        }
+    }
+}
+
+/// Local-name matching helpers over roxmltree nodes.
+trait NodeExt<'a, 'input: 'a> {
+    fn has_tag_name_local(self, name: &str) -> bool;
+    fn child(self, name: &str) -> Option<Node<'a, 'input>>;
+    fn children_named(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>>;
+    fn child_text(self, name: &str) -> Option<String>;
+    fn path_text(self, path: &[&str]) -> Option<String>;
+    fn flat_text(self) -> String;
+}
+
+impl<'a, 'input: 'a> NodeExt<'a, 'input> for Node<'a, 'input> {
+    /// Return true for an element with the local name.
+    fn has_tag_name_local(self, name: &str) -> bool {
+        self.is_element() && self.tag_name().name() == name
+    }
+
+    /// Return the first child element with the local name.
+    fn child(self, name: &str) -> Option<Node<'a, 'input>> {
+        self.children().find(|node| node.has_tag_name_local(name))
+    }
+
+    /// Return every child element with the local name.
+    fn children_named(self, name: &'static str) -> impl Iterator<Item = Node<'a, 'input>> {
+        self.children()
+            .filter(move |node| node.has_tag_name_local(name))
+    }
+
+    /// Return the trimmed text of the first child element with the local name.
+    fn child_text(self, name: &str) -> Option<String> {
+        self.child(name)
+            .map(|node| node.flat_text())
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Return the trimmed text at a path of child element names.
+    fn path_text(self, path: &[&str]) -> Option<String> {
+        let (last, parents) = path.split_last()?;
+        let mut node = self;
+        for name in parents {
+            node = node.child(name)?;
+        }
+        node.child_text(last)
+    }
+
+    /// Return all descendant text with whitespace collapsed.
+    fn flat_text(self) -> String {
+        let text: String = self
+            .descendants()
+            .filter(|node| node.is_text())
+            .filter_map(|node| node.text())
+            .collect();
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Parse one `doi_record` element.
+fn parse_record(doi_record: Node) -> std::result::Result<UnixsdRecord, CrossrefError> {
+    let mut record = UnixsdRecord {
+        owner: doi_record.attribute("owner").map(str::to_string),
+        timestamp: doi_record.attribute("timestamp").map(str::to_string),
+        ..Default::default()
+    };
+
+    // UNIXSD wraps the record in a <query> carrying the DOI type and crm-items.
+    if let Some(query) = doi_record
+        .ancestors()
+        .find(|node| node.has_tag_name_local("query"))
+    {
+        if let Some(doi) = query.child("doi") {
+            record.doi = doi.flat_text();
+            record.doi_type = doi.attribute("type").map(str::to_string);
+        }
+        for item in query.children_named("crm-item") {
+            if let Some(name) = item.attribute("name") {
+                record.crm_items.insert(name.to_string(), item.flat_text());
+            }
+        }
+    }
+
+    let work = find_work(doi_record, &record.doi).context(XmlRecordSnafu {
+        stage: "find-work",
+        message: "no element with doi_data",
+    })?;
+    if record.doi.is_empty() {
+        record.doi = work.path_text(&["doi_data", "doi"]).unwrap_or_default();
+    }
+    record.kind = work_kind(work);
+
+    parse_work(work, &mut record);
+    parse_container(work, &mut record);
+    if record.publisher.is_none() {
+        record.publisher = record.crm_items.get("publisher-name").cloned();
+    }
+    Ok(record)
+}
+
+/// Find the element whose `doi_data` carries the record DOI.
+fn find_work<'a, 'input>(doi_record: Node<'a, 'input>, doi: &str) -> Option<Node<'a, 'input>> {
+    let candidates: Vec<Node> = doi_record
+        .descendants()
+        .filter(|node| node.has_tag_name_local("doi_data"))
+        .filter_map(|doi_data| doi_data.parent_element())
+        .filter(|node| !node.has_tag_name_local("component"))
+        .collect();
+    candidates
+        .iter()
+        .find(|node| {
+            node.path_text(&["doi_data", "doi"])
+                .is_some_and(|value| value.eq_ignore_ascii_case(doi))
+        })
+        .or_else(|| candidates.last())
+        .copied()
+}
+
+/// Classify the work element.
+fn work_kind(work: Node) -> UnixsdKind {
+    let parent = work.parent_element().map(|node| node.tag_name().name());
+    match work.tag_name().name() {
+        "journal_article" => UnixsdKind::JournalArticle,
+        "journal_issue" => UnixsdKind::JournalIssue,
+        "journal_metadata" => UnixsdKind::Journal,
+        "book_metadata" | "book_series_metadata" | "book_set_metadata" => UnixsdKind::Book,
+        "content_item" if parent == Some("book") => UnixsdKind::BookChapter,
+        "content_item" if parent == Some("report-paper") => UnixsdKind::Report,
+        "conference_paper" => UnixsdKind::ConferencePaper,
+        "proceedings_metadata" | "proceedings_series_metadata" => UnixsdKind::Proceedings,
+        "dataset" => UnixsdKind::Dataset,
+        "database_metadata" => UnixsdKind::Database,
+        "dissertation" => UnixsdKind::Dissertation,
+        "report-paper_metadata" | "report-paper_series_metadata" => UnixsdKind::Report,
+        "posted_content" => UnixsdKind::PostedContent,
+        "sa_component" | "component" => UnixsdKind::Component,
+        _ => UnixsdKind::Other,
+    }
+}
+
+/// Read the fields carried by the work element itself.
+fn parse_work(work: Node, record: &mut UnixsdRecord) {
+    if let Some(titles) = work.child("titles") {
+        record.titles = titles
+            .children_named("title")
+            .map(|node| node.flat_text())
+            .collect();
+        record.subtitles = titles
+            .children_named("subtitle")
+            .map(|node| node.flat_text())
+            .collect();
+        record.original_titles = titles
+            .children_named("original_language_title")
+            .map(|node| node.flat_text())
+            .collect();
+    }
+    // Dissertations list their author directly under the work element.
+    record.contributors = parse_contributors(work.child("contributors").unwrap_or(work));
+    record.dates = ["publication_date", "posted_date", "approval_date"]
+        .into_iter()
+        .flat_map(|name| {
+            work.children()
+                .filter(move |node| node.has_tag_name_local(name))
+        })
+        .chain(
+            work.child("database_date")
+                .into_iter()
+                .flat_map(|node| node.children_named("publication_date")),
+        )
+        .filter_map(parse_date)
+        .collect();
+    record.first_page = work.path_text(&["pages", "first_page"]);
+    record.last_page = work.path_text(&["pages", "last_page"]);
+    record.item_number = work.path_text(&["publisher_item", "item_number"]);
+    record.resource = work.path_text(&["doi_data", "resource"]);
+    record.language = work.attribute("language").map(str::to_string);
+    record.abstracts = work
+        .children_named("abstract")
+        .map(abstract_markup)
+        .filter(|markup| !markup.is_empty())
+        .collect();
+    record.isbn = typed_values(work, "isbn");
+    record.volume = work.child_text("volume");
+    record.institution = work.path_text(&["institution", "institution_name"]);
+    record.degree = work.child_text("degree");
+    record.posted_type = work.attribute("type").map(str::to_string);
+    if let Some(publisher) = work.child("publisher") {
+        record.publisher = publisher.child_text("publisher_name");
+        record.publisher_place = publisher.child_text("publisher_place");
+    }
+
+    let crossmark = work.child("crossmark");
+    let programs: Vec<Node> = work
+        .children_named("program")
+        .chain(
+            crossmark
+                .and_then(|node| node.child("custom_metadata"))
+                .into_iter()
+                .flat_map(|node| node.children_named("program")),
+        )
+        .collect();
+    for program in &programs {
+        match program.attribute("name") {
+            Some("fundref") => record.funding.extend(parse_funding(*program)),
+            Some("AccessIndicators") => record.licenses.extend(
+                program
+                    .children_named("license_ref")
+                    .map(|node| node.flat_text()),
+            ),
+            _ => {}
+        }
+    }
+    record.crossmark = crossmark.map(parse_crossmark);
+    record.components = work
+        .child("component_list")
+        .into_iter()
+        .flat_map(|list| list.children_named("component"))
+        .map(parse_component)
+        .collect();
+    record.citations = work
+        .child("citation_list")
+        .into_iter()
+        .flat_map(|list| list.children_named("citation"))
+        .map(parse_citation)
+        .collect();
+}
+
+/// Fill container fields from the sibling and ancestor metadata elements.
+fn parse_container(work: Node, record: &mut UnixsdRecord) {
+    let Some(parent) = work.parent_element() else {
+        return;
+    };
+    if let Some(journal) = parent.child("journal_metadata") {
+        record.container_title = journal.child_text("full_title");
+        record.container_abbrev = journal.child_text("abbrev_title");
+        record.issn = typed_values(journal, "issn");
+        record.language = record
+            .language
+            .take()
+            .or_else(|| journal.attribute("language").map(str::to_string));
+    }
+    if let Some(issue) = parent.child("journal_issue") {
+        record.volume = issue.path_text(&["journal_volume", "volume"]);
+        record.issue = issue.child_text("issue");
+        if record.dates.is_empty() {
+            record.dates = issue
+                .children_named("publication_date")
+                .filter_map(parse_date)
+                .collect();
+        }
+    }
+    let container = ["book_metadata", "book_series_metadata", "book_set_metadata"]
+        .into_iter()
+        .chain(["proceedings_metadata", "database_metadata"])
+        .chain(["report-paper_metadata", "report-paper_series_metadata"])
+        .find_map(|name| parent.child(name))
+        .filter(|container| *container != work);
+    if let Some(container) = container {
+        record.container_title = container
+            .path_text(&["titles", "title"])
+            .or_else(|| container.child_text("proceedings_title"));
+        record.isbn.extend(typed_values(container, "isbn"));
+        record.volume = record
+            .volume
+            .take()
+            .or_else(|| container.child_text("volume"));
+        if let Some(publisher) = container.child("publisher") {
+            record.publisher = record
+                .publisher
+                .take()
+                .or_else(|| publisher.child_text("publisher_name"));
+            record.publisher_place = record
+                .publisher_place
+                .take()
+                .or_else(|| publisher.child_text("publisher_place"));
+        }
+        record.institution = record
+            .institution
+            .take()
+            .or_else(|| container.path_text(&["institution", "institution_name"]));
+        if record.dates.is_empty() {
+            record.dates = container
+                .children_named("publication_date")
+                .chain(
+                    container
+                        .child("database_date")
+                        .into_iter()
+                        .flat_map(|node| node.children_named("publication_date")),
+                )
+                .filter_map(parse_date)
+                .collect();
+        }
+        if record.contributors.is_empty() && record.kind == UnixsdKind::Dataset {
+            record.contributors = container
+                .child("contributors")
+                .map(parse_contributors)
+                .unwrap_or_default();
+        }
+    }
+    if let Some(event) = parent.child("event_metadata") {
+        record.event_name = event.child_text("conference_name");
+        record.event_place = event.child_text("conference_location");
+    }
+}
+
+/// Parse `person_name` and `organization` contributors.
+fn parse_contributors(contributors: Node) -> Vec<UnixsdContributor> {
+    contributors
+        .children()
+        .filter(|node| {
+            node.has_tag_name_local("person_name") || node.has_tag_name_local("organization")
+        })
+        .map(|node| {
+            let mut contributor = UnixsdContributor {
+                role: node
+                    .attribute("contributor_role")
+                    .unwrap_or("author")
+                    .to_string(),
+                sequence: node.attribute("sequence").map(str::to_string),
+                ..Default::default()
+            };
+            if node.has_tag_name_local("organization") {
+                contributor.organization = Some(node.flat_text());
+                return contributor;
+            }
+            contributor.given = node.child_text("given_name");
+            contributor.surname = node.child_text("surname");
+            contributor.suffix = node.child_text("suffix");
+            contributor.orcid = node.child_text("ORCID");
+            contributor.affiliations = node
+                .children_named("affiliation")
+                .map(|node| node.flat_text())
+                .chain(
+                    node.child("affiliations")
+                        .into_iter()
+                        .flat_map(|node| node.children_named("institution"))
+                        .filter_map(|node| node.child_text("institution_name")),
+                )
+                .collect();
+            contributor
+        })
+        .collect()
+}
+
+/// Parse a date element with `year`, `month` and `day` children.
+fn parse_date(node: Node) -> Option<UnixsdDate> {
+    Some(UnixsdDate {
+        media_type: node.attribute("media_type").map(str::to_string),
+        year: node.child_text("year")?.parse().ok()?,
+        month: node
+            .child_text("month")
+            .and_then(|month| month.parse().ok()),
+        day: node.child_text("day").and_then(|day| day.parse().ok()),
+    })
+}
+
+/// Collect `issn`/`isbn` children with their media types.
+fn typed_values(node: Node, name: &'static str) -> Vec<CrossrefTypedValue> {
+    node.children_named(name)
+        .map(|value| CrossrefTypedValue {
+            value_type: value.attribute("media_type").unwrap_or("print").to_string(),
+            value: value.flat_text(),
+        })
+        .collect()
+}
+
+/// Return the source markup inside a JATS abstract, as the REST API serves it.
+///
+/// Conversion to text is left to [`JatsAbstract`](crate::JatsAbstract).
+fn abstract_markup(node: Node) -> String {
+    match (node.first_child(), node.last_child()) {
+        (Some(first), Some(last)) => node.document().input_text()
+            [first.range().start..last.range().end]
+            .trim()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Parse a FundRef program into funders.
+fn parse_funding(program: Node) -> Vec<UnixsdFunding> {
+    program
+        .children_named("assertion")
+        .filter(|node| node.attribute("name") == Some("fundgroup"))
+        .filter_map(|group| {
+            let funder = group
+                .children_named("assertion")
+                .find(|node| node.attribute("name") == Some("funder_name"))?;
+            let name = funder
+                .children()
+                .filter(|node| node.is_text())
+                .filter_map(|node| node.text())
+                .collect::<String>()
+                .trim()
+                .to_string();
+            let funder_id = funder
+                .descendants()
+                .find(|node| node.attribute("name") == Some("funder_identifier"))
+                .map(|node| node.flat_text());
+            let awards = group
+                .children_named("assertion")
+                .filter(|node| node.attribute("name") == Some("award_number"))
+                .map(|node| node.flat_text())
+                .collect();
+            Some(UnixsdFunding {
+                name,
+                funder_id,
+                awards,
+            })
+        })
+        .collect()
+}
+
+/// Parse Crossmark metadata.
+fn parse_crossmark(crossmark: Node) -> UnixsdCrossmark {
+    UnixsdCrossmark {
+        version: crossmark.child_text("crossmark_version"),
+        policy: crossmark.child_text("crossmark_policy"),
+        domains: crossmark
+            .child("crossmark_domains")
+            .into_iter()
+            .flat_map(|node| node.children_named("crossmark_domain"))
+            .filter_map(|node| node.child_text("domain"))
+            .collect(),
+        updates: crossmark
+            .child("updates")
+            .into_iter()
+            .flat_map(|node| node.children_named("update"))
+            .map(|node| UnixsdUpdate {
+                update_type: node.attribute("type").unwrap_or_default().to_string(),
+                doi: node.flat_text(),
+                date: node.attribute("date").map(str::to_string),
+            })
+            .collect(),
+        assertions: crossmark
+            .child("custom_metadata")
+            .into_iter()
+            .flat_map(|node| node.children_named("assertion"))
+            .map(|node| UnixsdAssertion {
+                name: node.attribute("name").unwrap_or_default().to_string(),
+                label: node.attribute("label").map(str::to_string),
+                value: node.flat_text(),
+            })
+            .collect(),
+    }
+}
+
+/// Parse a `component`.
+fn parse_component(component: Node) -> UnixsdComponent {
+    UnixsdComponent {
+        doi: component
+            .path_text(&["doi_data", "doi"])
+            .unwrap_or_default(),
+        title: component.path_text(&["titles", "title"]),
+        description: component.child_text("description"),
+        mime_type: component
+            .child("format")
+            .and_then(|node| node.attribute("mime_type"))
+            .map(str::to_string),
+    }
+}
+
+/// Parse a `citation` into a reference.
+fn parse_citation(citation: Node) -> Reference {
+    Reference {
+        key: citation.attribute("key").unwrap_or_default().to_string(),
+        doi: citation.child_text("doi"),
+        doi_asserted_by: citation
+            .child("doi")
+            .and_then(|node| node.attribute("provider"))
+            .map(str::to_string),
+        year: citation.child_text("cYear").map(CslValue::from),
+        author: citation.child_text("author"),
+        volume: citation.child_text("volume").map(CslValue::from),
+        issue: citation.child_text("issue").map(CslValue::from),
+        first_page: citation.child_text("first_page").map(CslValue::from),
+        journal_title: citation.child_text("journal_title"),
+        article_title: citation.child_text("article_title"),
+        unstructured: citation.child_text("unstructured_citation"),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JatsAbstract, OutputFormat};
+
+    const UNIXSD_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/crossref/unixsd.xml"
+    ));
+
+    const UNIXREF_CHAPTER: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/crossref/unixref-chapter.xml"
+    ));
+
+    /// Wrap a `<crossref>` body in a UNIXREF document.
+    fn unixref(body: &str) -> String {
+        format!(
+            "<doi_records><doi_record owner=\"10.5555\"><crossref>{}</crossref></doi_record></doi_records>",
+            body
+        )
+    }
+
+    #[test]
+    /// Parses a UNIXSD journal article with every supported section.
+    fn unixsd_journal_article() {
+        let record = UnixsdRecord::parse(UNIXSD_FIXTURE).unwrap();
+        assert_eq!(record.doi, "10.5555/12345678");
+        assert_eq!(record.kind, UnixsdKind::JournalArticle);
+        assert_eq!(record.doi_type.as_deref(), Some("journal_article"));
+        assert_eq!(record.crm_items["member-id"], "7822");
+        assert_eq!(record.subtitles, vec!["Silly String Theory".to_string()]);
+        assert_eq!(record.container_abbrev.as_deref(), Some("J. Psychoceram."));
+        assert_eq!(record.issn[1].value_type, "electronic");
+        assert_eq!(record.volume.as_deref(), Some("5"));
+        assert_eq!(record.issue.as_deref(), Some("11"));
+        assert_eq!(record.language.as_deref(), Some("en"));
+        assert_eq!(record.item_number.as_deref(), Some("e123"));
+
+        let author = &record.contributors[0];
+        assert_eq!(author.affiliations, vec!["Brown University".to_string()]);
+        assert_eq!(
+            author.orcid.as_deref(),
+            Some("https://orcid.org/0000-0002-1825-0097")
+        );
+        assert_eq!(record.contributors[1].role, "editor");
+        assert_eq!(
+            record.contributors[2].organization.as_deref(),
+            Some("Psychoceramics Working Group")
+        );
+
+        assert_eq!(record.abstracts.len(), 1);
+        assert!(record.abstracts[0].starts_with("<jats:title>Abstract</jats:title>"));
+        assert!(record.abstracts[0].ends_with(
+            "<jats:p>Several narratives concerning <jats:italic>the fatal flaw</jats:italic> exist.</jats:p>"
+        ));
+
+        assert_eq!(record.funding[0].name, "National Science Foundation");
+        assert_eq!(record.funding[0].awards.len(), 2);
+        assert_eq!(
+            record.licenses,
+            vec!["https://creativecommons.org/licenses/by/4.0/".to_string()]
+        );
+        let crossmark = record.crossmark.as_ref().unwrap();
+        assert_eq!(crossmark.updates[0].update_type, "correction");
+        assert_eq!(crossmark.assertions[0].value, "2008-06-01");
+        assert_eq!(record.components[0].mime_type.as_deref(), Some("image/png"));
+        assert_eq!(
+            record.citations[0].doi_asserted_by.as_deref(),
+            Some("crossref")
+        );
+        assert!(record.citations[1].unstructured.is_some());
+        assert_eq!(record.issued().unwrap().year, 2008);
+    }
+
+    #[test]
+    /// Converts a journal article into CSL-JSON metadata.
+    fn unixsd_to_metadata() {
+        let metadata = DoiMetadata::from(UnixsdRecord::parse(UNIXSD_FIXTURE).unwrap());
+        assert_eq!(metadata.item_type, CslItemType::ArticleJournal);
+        assert_eq!(
+            metadata.title.as_deref(),
+            Some("Toward a Unified Theory of High-Energy Metaphysics: Silly String Theory")
+        );
+        assert_eq!(metadata.author.len(), 2);
+        assert_eq!(metadata.editor[0].suffix.as_deref(), Some("Jr."));
+        assert_eq!(
+            metadata.page.as_ref().and_then(CslValue::as_str),
+            Some("1-3")
+        );
+        assert_eq!(
+            metadata.publisher.as_deref(),
+            Some("Society of Psychoceramics")
+        );
+        assert_eq!(metadata.issn, vec!["0264-3561", "0264-3562"]);
+        assert_eq!(metadata.issued.as_ref().and_then(CslDate::year), Some(2008));
+        assert!(metadata.published_online.is_some());
+        assert_eq!(metadata.reference_count, Some(2));
+        assert_eq!(metadata.title_short, None);
+
+        let abstract_text = metadata.abstract_text.as_deref().unwrap();
+        assert!(abstract_text.contains("<jats:italic>the fatal flaw</jats:italic>"));
+        assert_eq!(
+            JatsAbstract::parse(abstract_text).render(OutputFormat::Markdown),
+            "The characteristic theme of the works of Stone is the bridge between culture \
+             and society.\n\nSeveral narratives concerning *the fatal flaw* exist."
+        );
+    }
+
+    #[test]
+    /// Parses a UNIXREF book chapter with its book metadata.
+    fn unixref_book_chapter() {
+        let record = UnixsdRecord::parse(UNIXREF_CHAPTER).unwrap();
+        assert_eq!(record.doi, "10.5555/handbook.ch3");
+        assert_eq!(record.kind, UnixsdKind::BookChapter);
+        assert_eq!(record.owner.as_deref(), Some("10.5555"));
+        assert_eq!(
+            record.container_title.as_deref(),
+            Some("Handbook of Psychoceramics")
+        );
+        assert_eq!(record.isbn.len(), 2);
+        assert_eq!(record.volume.as_deref(), Some("2"));
+        assert_eq!(record.publisher_place.as_deref(), Some("Lynnfield"));
+        assert_eq!(record.issued().unwrap().year, 2019);
+
+        let metadata = DoiMetadata::from(record);
+        assert_eq!(metadata.item_type, CslItemType::Chapter);
+        assert_eq!(metadata.publisher.as_deref(), Some("Crossref Press"));
+        assert_eq!(metadata.author[0].family.as_deref(), Some("van Beethoven"));
+    }
+
+    #[test]
+    /// Classifies conference papers, datasets, dissertations, reports and posted content.
+    fn unixref_other_kinds() {
+        let conference = UnixsdRecord::parse(&unixref(
+            "<conference><event_metadata><conference_name>Urn Symposium</conference_name>\
+             <conference_location>Providence</conference_location></event_metadata>\
+             <proceedings_metadata><proceedings_title>Proc. Urns</proceedings_title>\
+             <publisher><publisher_name>ACM</publisher_name></publisher>\
+             <publication_date media_type=\"print\"><year>2011</year></publication_date>\
+             <publication_date media_type=\"online\"><year>2010</year><month>11</month></publication_date>\
+             </proceedings_metadata><conference_paper><titles><title>Pots</title></titles>\
+             <doi_data><doi>10.5555/conf.1</doi></doi_data></conference_paper></conference>",
+        ))
+        .unwrap();
+        assert_eq!(conference.kind, UnixsdKind::ConferencePaper);
+        assert_eq!(conference.container_title.as_deref(), Some("Proc. Urns"));
+        assert_eq!(conference.event_name.as_deref(), Some("Urn Symposium"));
+        assert_eq!(conference.publisher.as_deref(), Some("ACM"));
+        assert_eq!(conference.issued().unwrap().month, Some(11));
+
+        let dataset = UnixsdRecord::parse(&unixref(
+            "<database><database_metadata><titles><title>Urn Database</title></titles>\
+             </database_metadata><dataset dataset_type=\"record\"><titles><title>Urn 7</title></titles>\
+             <database_date><publication_date><year>2012</year></publication_date></database_date>\
+             <doi_data><doi>10.5555/db.7</doi></doi_data></dataset></database>",
+        ))
+        .unwrap();
+        assert_eq!(dataset.kind, UnixsdKind::Dataset);
+        assert_eq!(dataset.container_title.as_deref(), Some("Urn Database"));
+        assert_eq!(dataset.issued().unwrap().year, 2012);
+
+        let thesis = UnixsdRecord::parse(&unixref(
+            "<dissertation><person_name contributor_role=\"author\"><surname>Roe</surname></person_name>\
+             <titles><title>On Urns</title></titles><approval_date><year>2015</year></approval_date>\
+             <institution><institution_name>Brown University</institution_name></institution>\
+             <degree>PhD</degree><doi_data><doi>10.5555/thesis</doi></doi_data></dissertation>",
+        ))
+        .unwrap();
+        assert_eq!(thesis.kind, UnixsdKind::Dissertation);
+        assert_eq!(thesis.degree.as_deref(), Some("PhD"));
+        assert_eq!(thesis.contributors[0].surname.as_deref(), Some("Roe"));
+        let metadata = DoiMetadata::from(thesis);
+        assert_eq!(metadata.item_type, CslItemType::Thesis);
+        assert_eq!(metadata.publisher.as_deref(), Some("Brown University"));
+
+        let report = UnixsdRecord::parse(&unixref(
+            "<report-paper><report-paper_metadata><titles><title>Urn Report</title></titles>\
+             <institution><institution_name>NIST</institution_name></institution>\
+             <doi_data><doi>10.5555/report</doi></doi_data></report-paper_metadata></report-paper>",
+        ))
+        .unwrap();
+        assert_eq!(report.kind, UnixsdKind::Report);
+        assert_eq!(report.institution.as_deref(), Some("NIST"));
+
+        let preprint = UnixsdRecord::parse(&unixref(
+            "<posted_content type=\"preprint\"><titles><title>Urns Revisited</title></titles>\
+             <posted_date><year>2021</year><month>3</month></posted_date>\
+             <doi_data><doi>10.5555/preprint</doi></doi_data></posted_content>",
+        ))
+        .unwrap();
+        assert_eq!(preprint.kind, UnixsdKind::PostedContent);
+        assert_eq!(preprint.posted_type.as_deref(), Some("preprint"));
+        assert_eq!(preprint.issued().unwrap().month, Some(3));
+        assert_eq!(DoiMetadata::from(preprint).item_type, CslItemType::Article);
+    }
+
+    #[test]
+    /// Rejects malformed XML and documents without records.
+    fn unixsd_parse_errors() {
+        assert!(matches!(
+            UnixsdRecord::parse("<doi_records>"),
+            Err(CrossrefError::Xml { .. })
+        ));
+        assert!(matches!(
+            UnixsdRecord::parse("<doi_records/>"),
+            Err(CrossrefError::XmlRecord { .. })
+        ));
+    }
+}
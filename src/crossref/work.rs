@@ -0,0 +1,443 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::csl::{Affiliation, ContentDomain, CslDate, Reference};
+
+/// The envelope wrapping every Crossref REST API response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefResponse<T> {
+    /// Response status, `ok` on success.
+    pub status: String,
+    /// Type of the message payload (e.g. `work`, `work-list`).
+    pub message_type: String,
+    /// Version of the message schema.
+    pub message_version: String,
+    /// The response payload.
+    pub message: T,
+}
+
+/// A Crossref work record from `/works/{doi}`.
+///
+/// Every field is optional on the wire so that `select`-ed search results
+/// still parse.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefWork {
+    /// work doi
+    #[serde(rename = "DOI", default)]
+    pub doi: String,
+    /// work resolver url
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// work Crossref type (e.g. `journal-article`)
+    #[serde(rename = "type", default)]
+    pub work_type: String,
+    /// work title
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub title: Vec<String>,
+    /// work subtitle
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtitle: Vec<String>,
+    /// work short title
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub short_title: Vec<String>,
+    /// work original title
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub original_title: Vec<String>,
+    /// work container title
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub container_title: Vec<String>,
+    /// work short container title
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub short_container_title: Vec<String>,
+    /// work group title (posted content)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_title: Option<String>,
+    /// work publisher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// work publisher location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher_location: Option<String>,
+    /// work issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    /// work volume
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    /// work page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    /// work article number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub article_number: Option<String>,
+    /// work edition number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edition_number: Option<String>,
+    /// work abstract (JATS XML)
+    #[serde(rename = "abstract", skip_serializing_if = "Option::is_none")]
+    pub abstract_text: Option<String>,
+    /// work language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// work authors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub author: Vec<CrossrefContributor>,
+    /// work editors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub editor: Vec<CrossrefContributor>,
+    /// work translators
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub translator: Vec<CrossrefContributor>,
+    /// work chairs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chair: Vec<CrossrefContributor>,
+
+    /// work issued date (earliest publication date)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued: Option<CslDate>,
+    /// work published date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<CslDate>,
+    /// work print publication date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_print: Option<CslDate>,
+    /// work online publication date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_online: Option<CslDate>,
+    /// work posted date (posted content)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub posted: Option<CslDate>,
+    /// work accepted date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted: Option<CslDate>,
+    /// work approved date (dissertations, reports)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved: Option<CslDate>,
+    /// work registration date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<CslDate>,
+    /// work last deposit date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposited: Option<CslDate>,
+    /// work last index date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexed: Option<CslDate>,
+
+    /// work funders
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub funder: Vec<CrossrefFunder>,
+    /// work licenses
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub license: Vec<CrossrefLicense>,
+    /// work full-text links
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub link: Vec<CrossrefLink>,
+    /// work clinical trial numbers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clinical_trial_number: Vec<CrossrefClinicalTrial>,
+    /// work updates (corrections, retractions) this work applies to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub update_to: Vec<CrossrefUpdate>,
+    /// work relations keyed by relation type (e.g. `is-preprint-of`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub relation: BTreeMap<String, Vec<CrossrefRelation>>,
+    /// work crossmark assertions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion: Vec<CrossrefAssertion>,
+    /// work references
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reference: Vec<Reference>,
+
+    /// work deposited reference count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_count: Option<u64>,
+    /// work deposited reference count (alias kept by Crossref)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub references_count: Option<u64>,
+    /// work citation count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_referenced_by_count: Option<u64>,
+
+    /// work subjects
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subject: Vec<String>,
+    /// work ISSNs
+    #[serde(rename = "ISSN", default, skip_serializing_if = "Vec::is_empty")]
+    pub issn: Vec<String>,
+    /// work typed ISSNs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issn_type: Vec<CrossrefTypedValue>,
+    /// work ISBNs
+    #[serde(rename = "ISBN", default, skip_serializing_if = "Vec::is_empty")]
+    pub isbn: Vec<String>,
+    /// work typed ISBNs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub isbn_type: Vec<CrossrefTypedValue>,
+    /// work alternative identifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternative_id: Vec<String>,
+    /// work archives
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub archive: Vec<String>,
+    /// work institutions (dissertations, reports)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub institution: Vec<CrossrefInstitution>,
+    /// work conference event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<CrossrefEvent>,
+    /// work journal issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_issue: Option<CrossrefJournalIssue>,
+    /// work resource links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<CrossrefResource>,
+    /// work content domain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_domain: Option<ContentDomain>,
+    /// work crossmark update policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<String>,
+
+    /// work DOI prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// work Crossref member id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    /// work metadata source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// work relevance score (search results)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+
+    /// Any key not modeled above, kept verbatim for lossless round-trips.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+/// A contributor (author, editor, ...) on a Crossref work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefContributor {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    /// Organization name for institutional contributors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(rename = "ORCID", skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticated_orcid: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affiliation: Vec<Affiliation>,
+}
+
+/// A funder acknowledged on a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefFunder {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Funder Registry DOI.
+    #[serde(rename = "DOI", skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi_asserted_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub award: Vec<String>,
+}
+
+/// A license applying to a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefLicense {
+    #[serde(rename = "URL")]
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<CslDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_in_days: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_version: Option<String>,
+}
+
+/// A full-text link for a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefLink {
+    #[serde(rename = "URL")]
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intended_application: Option<String>,
+}
+
+/// A clinical trial registration linked to a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefClinicalTrial {
+    pub clinical_trial_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub trial_type: Option<String>,
+}
+
+/// An update (correction, retraction, ...) applied to another work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefUpdate {
+    #[serde(rename = "DOI")]
+    pub doi: String,
+    #[serde(rename = "type")]
+    pub update_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<CslDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_id: Option<i64>,
+}
+
+/// A relation to another object.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefRelation {
+    pub id_type: String,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asserted_by: Option<String>,
+}
+
+/// A crossmark assertion.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefAssertion {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<CrossrefAssertionGroup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<CrossrefAssertionExplanation>,
+}
+
+/// Grouping of a crossmark assertion.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefAssertionGroup {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Link explaining a crossmark assertion.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefAssertionExplanation {
+    #[serde(rename = "URL")]
+    pub url: String,
+}
+
+/// An identifier with its type, such as `{"type": "print", "value": "0264-3561"}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefTypedValue {
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub value: String,
+}
+
+/// An institution attached to a dissertation or report.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefInstitution {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub place: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub department: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acronym: Vec<String>,
+}
+
+/// The conference event of a proceedings paper.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefEvent {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acronym: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sponsor: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<CslDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<CslDate>,
+}
+
+/// The journal issue a work appeared in.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefJournalIssue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_print: Option<CslDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_online: Option<CslDate>,
+}
+
+/// Resource links of a work.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefResource {
+    pub primary: CrossrefResourceLink,
+}
+
+/// A single resource link.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CrossrefResourceLink {
+    #[serde(rename = "URL")]
+    pub url: String,
+}
+
+impl CrossrefWork {
+    /// Return the first title when present.
+    pub fn primary_title(&self) -> Option<&str> {
+        self.title.first().map(String::as_str)
+    }
+
+    /// Return the first container title when present.
+    pub fn primary_container_title(&self) -> Option<&str> {
+        self.container_title.first().map(String::as_str)
+    }
+}
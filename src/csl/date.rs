@@ -0,0 +1,646 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+};
+use serde_json::Value;
+
+/// How much of a calendar date is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DatePrecision {
+    Year,
+    Month,
+    Day,
+}
+
+/// Seasons as encoded by CSL (`season` field or months 13–16 / 21–24).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// Map a CSL season number (1–4, 13–16 or 21–24) to a season.
+    pub fn from_number(value: i64) -> Option<Self> {
+        match value {
+            1 | 13 | 21 => Some(Self::Spring),
+            2 | 14 | 22 => Some(Self::Summer),
+            3 | 15 | 23 => Some(Self::Autumn),
+            4 | 16 | 24 => Some(Self::Winter),
+            _ => None,
+        }
+    }
+
+    /// Parse a season from its number or English name.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(number) = value.parse::<i64>() {
+            return Self::from_number(number);
+        }
+        match value.to_ascii_lowercase().as_str() {
+            "spring" => Some(Self::Spring),
+            "summer" => Some(Self::Summer),
+            "autumn" | "fall" => Some(Self::Autumn),
+            "winter" => Some(Self::Winter),
+            _ => None,
+        }
+    }
+}
+
+/// A calendar date converted to `chrono` with the precision it was given in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PreciseDate {
+    /// The date, with missing month/day filled in as 1.
+    pub date: NaiveDate,
+    /// Which components were actually present.
+    pub precision: DatePrecision,
+}
+
+/// One CSL `date-parts` entry: year, optional month, optional day.
+///
+/// A day can only be present together with a month, so every value
+/// serializes back to exactly the parts it was built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DateParts {
+    year: i32,
+    month: Option<u8>,
+    day: Option<u8>,
+}
+
+impl DateParts {
+    /// Build a year-only date.
+    pub fn year_only(year: i32) -> Self {
+        Self {
+            year,
+            month: None,
+            day: None,
+        }
+    }
+
+    /// Build a year and month date. Months 13–16 and 21–24 encode seasons.
+    pub fn year_month(year: i32, month: u8) -> Self {
+        Self {
+            year,
+            month: Some(month),
+            day: None,
+        }
+    }
+
+    /// Build a full year, month and day date.
+    pub fn ymd(year: i32, month: u8, day: u8) -> Self {
+        Self {
+            year,
+            month: Some(month),
+            day: Some(day),
+        }
+    }
+
+    /// Return the year (negative for BCE).
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Return the month when it is a calendar month (1–12).
+    pub fn month(&self) -> Option<u8> {
+        self.month.filter(|month| (1..=12).contains(month))
+    }
+
+    /// Return the day when present.
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    /// Return the season encoded in the month position, if any.
+    pub fn season(&self) -> Option<Season> {
+        self.month
+            .filter(|month| *month > 12)
+            .and_then(|month| Season::from_number(i64::from(month)))
+    }
+
+    /// Return the precision of the date.
+    pub fn precision(&self) -> DatePrecision {
+        match (self.month(), self.day) {
+            (Some(_), Some(_)) => DatePrecision::Day,
+            (Some(_), None) => DatePrecision::Month,
+            _ => DatePrecision::Year,
+        }
+    }
+
+    /// Convert to a `chrono` date, filling missing components with 1.
+    pub fn to_naive_date(&self) -> Option<PreciseDate> {
+        let precision = self.precision();
+        let month = self.month().unwrap_or(1);
+        let day = match precision {
+            DatePrecision::Day => self.day.unwrap_or(1),
+            _ => 1,
+        };
+        let date = NaiveDate::from_ymd_opt(self.year, u32::from(month), u32::from(day))?;
+        Some(PreciseDate { date, precision })
+    }
+
+    /// Parse an ISO-like date string such as `2019`, `2019-12` or `2019-12-01`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        // Drop any time component of an ISO timestamp.
+        let input = input.split_once('T').map_or(input, |(date, _)| date);
+        let (negative, body) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+        let mut parts = body.split(['-', '/', '.']).filter(|part| !part.is_empty());
+
+        let year: i32 = parts.next()?.trim().parse().ok()?;
+        let year = if negative { -year } else { year };
+        let month = match parts.next() {
+            Some(month) => Some(month.trim().parse::<u8>().ok()?),
+            None => None,
+        };
+        let day = match parts.next() {
+            Some(day) => Some(day.trim().parse::<u8>().ok()?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(match (month, day) {
+            (Some(month), Some(day)) => Self::ymd(year, month, day),
+            (Some(month), None) => Self::year_month(year, month),
+            _ => Self::year_only(year),
+        })
+    }
+
+    /// Build from loosely typed parts, rejecting a day without a month.
+    fn from_parts(year: i64, month: Option<i64>, day: Option<i64>) -> Result<Self, String> {
+        let year = i32::try_from(year).map_err(|_| format!("year out of range: {year}"))?;
+        let month = month
+            .map(|month| u8::try_from(month).map_err(|_| format!("month out of range: {month}")))
+            .transpose()?;
+        let day = day
+            .map(|day| u8::try_from(day).map_err(|_| format!("day out of range: {day}")))
+            .transpose()?;
+
+        match (month, day) {
+            (None, Some(_)) => Err("day given without a month".to_string()),
+            (month, day) => Ok(Self { year, month, day }),
+        }
+    }
+}
+
+impl From<NaiveDate> for DateParts {
+    fn from(date: NaiveDate) -> Self {
+        // Month and day from chrono always fit in u8.
+        Self::ymd(date.year(), date.month() as u8, date.day() as u8)
+    }
+}
+
+impl fmt::Display for DateParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.year < 0 {
+            write!(f, "-{:04}", -self.year)?;
+        } else {
+            write!(f, "{:04}", self.year)?;
+        }
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for DateParts {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = 1 + usize::from(self.month.is_some()) + usize::from(self.day.is_some());
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        seq.serialize_element(&self.year)?;
+        if let Some(month) = self.month {
+            seq.serialize_element(&month)?;
+        }
+        if let Some(day) = self.day {
+            seq.serialize_element(&day)?;
+        }
+        seq.end()
+    }
+}
+
+/// A single date component as sent on the wire: a number, a numeric string or null.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawPart {
+    Number(i64),
+    Text(String),
+}
+
+impl RawPart {
+    /// Return the numeric value, treating blank strings as missing.
+    fn value<E: de::Error>(self) -> Result<Option<i64>, E> {
+        match self {
+            RawPart::Number(value) => Ok(Some(value)),
+            RawPart::Text(text) if text.trim().is_empty() => Ok(None),
+            RawPart::Text(text) => text
+                .trim()
+                .parse()
+                .map(Some)
+                .map_err(|_| E::custom(format!("invalid date part: {text:?}"))),
+        }
+    }
+}
+
+/// Read up to three raw parts; `Ok(None)` means the year itself is null.
+fn visit_parts<'de, A>(mut seq: A) -> Result<Option<DateParts>, A::Error>
+where
+    A: SeqAccess<'de>,
+{
+    let mut values = Vec::with_capacity(3);
+    while let Some(part) = seq.next_element::<Option<RawPart>>()? {
+        values.push(match part {
+            Some(part) => part.value()?,
+            None => None,
+        });
+    }
+    if values.len() > 3 {
+        return Err(de::Error::invalid_length(
+            values.len(),
+            &"1 to 3 date parts",
+        ));
+    }
+
+    let mut values = values.into_iter();
+    let Some(year) = values.next().flatten() else {
+        return Ok(None);
+    };
+    let month = values.next().flatten();
+    let day = values.next().flatten();
+    DateParts::from_parts(year, month, day)
+        .map(Some)
+        .map_err(de::Error::custom)
+}
+
+impl<'de> Deserialize<'de> for DateParts {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DatePartsVisitor;
+
+        impl<'de> Visitor<'de> for DatePartsVisitor {
+            type Value = DateParts;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "an array of 1, 2 or 3 numbers: [year, month?, day?]")
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                visit_parts(seq)?.ok_or_else(|| de::Error::custom("missing year in date parts"))
+            }
+        }
+
+        deserializer.deserialize_seq(DatePartsVisitor)
+    }
+}
+
+/// A CSL date variable.
+///
+/// Holds structured `date-parts` (one entry, or two for a range) together
+/// with the optional `season`, `circa`, `literal` and `raw` forms, plus the
+/// `date-time`/`timestamp` extensions sent by Crossref.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CslDate {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_date_parts",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub date_parts: Vec<DateParts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circa: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+}
+
+/// Deserialize `date-parts`, dropping entries whose year is null (`[[null]]`).
+fn deserialize_date_parts<'de, D>(deserializer: D) -> Result<Vec<DateParts>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct MaybeParts(Option<DateParts>);
+
+    impl<'de> Deserialize<'de> for MaybeParts {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct MaybePartsVisitor;
+
+            impl<'de> Visitor<'de> for MaybePartsVisitor {
+                type Value = MaybeParts;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "an array of date parts")
+                }
+
+                fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    visit_parts(seq).map(MaybeParts)
+                }
+            }
+
+            deserializer.deserialize_seq(MaybePartsVisitor)
+        }
+    }
+
+    let parts = Option::<Vec<MaybeParts>>::deserialize(deserializer)?.unwrap_or_default();
+    if parts.len() > 2 {
+        return Err(de::Error::invalid_length(
+            parts.len(),
+            &"1 or 2 date-parts entries",
+        ));
+    }
+    Ok(parts.into_iter().filter_map(|parts| parts.0).collect())
+}
+
+impl CslDate {
+    /// Build a single date.
+    pub fn new(parts: DateParts) -> Self {
+        Self {
+            date_parts: vec![parts],
+            ..Default::default()
+        }
+    }
+
+    /// Build a date range.
+    pub fn range(start: DateParts, end: DateParts) -> Self {
+        Self {
+            date_parts: vec![start, end],
+            ..Default::default()
+        }
+    }
+
+    /// Build a date that only carries a literal string (e.g. "n.d.").
+    pub fn literal(value: &str) -> Self {
+        Self {
+            literal: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Build a date from a raw string such as `2019-12-01` or `2019/2020`.
+    ///
+    /// The raw string is kept, and parsed into `date-parts` when it is a
+    /// recognised ISO-like date or range.
+    pub fn from_raw(value: &str) -> Self {
+        Self {
+            date_parts: parse_raw(value).unwrap_or_default(),
+            raw: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Return the start date, falling back to the parsed `raw` form.
+    pub fn start(&self) -> Option<DateParts> {
+        match self.date_parts.first() {
+            Some(parts) => Some(*parts),
+            None => self
+                .raw
+                .as_deref()
+                .and_then(parse_raw)
+                .and_then(|parts| parts.first().copied()),
+        }
+    }
+
+    /// Return the end date of a range.
+    pub fn end(&self) -> Option<DateParts> {
+        match self.date_parts.get(1) {
+            Some(parts) => Some(*parts),
+            None if self.date_parts.is_empty() => self
+                .raw
+                .as_deref()
+                .and_then(parse_raw)
+                .and_then(|parts| parts.get(1).copied()),
+            None => None,
+        }
+    }
+
+    /// Return true when the date is a range.
+    pub fn is_range(&self) -> bool {
+        self.end().is_some()
+    }
+
+    /// Return the start year when known.
+    pub fn year(&self) -> Option<i32> {
+        self.start()
+            .map(|parts| parts.year())
+            .or_else(|| self.date_time.map(|date_time| date_time.year()))
+    }
+
+    /// Return the season from the `season` field or the month position.
+    pub fn season(&self) -> Option<Season> {
+        let explicit = match &self.season {
+            Some(Value::Number(number)) => number.as_i64().and_then(Season::from_number),
+            Some(Value::String(text)) => Season::parse(text),
+            _ => None,
+        };
+        explicit.or_else(|| self.start().and_then(|parts| parts.season()))
+    }
+
+    /// Return true when the date is marked as approximate.
+    pub fn is_circa(&self) -> bool {
+        match &self.circa {
+            Some(Value::Bool(value)) => *value,
+            Some(Value::Number(number)) => number.as_i64() != Some(0),
+            Some(Value::String(text)) => {
+                !text.is_empty() && text != "0" && !text.eq_ignore_ascii_case("false")
+            }
+            _ => false,
+        }
+    }
+
+    /// Convert the start date to `chrono` with its precision.
+    pub fn to_naive_date(&self) -> Option<PreciseDate> {
+        self.start().and_then(|parts| parts.to_naive_date())
+    }
+
+    /// Compare two dates chronologically.
+    ///
+    /// Less precise dates sort before more precise dates in the same period
+    /// (`2019` < `2019-03` < `2019-03-01`), ranges sort after a single date
+    /// with the same start, and dates without structured parts sort last.
+    pub fn cmp_by_date(&self, other: &Self) -> Ordering {
+        match (self.start(), other.start()) {
+            (Some(left), Some(right)) => {
+                left.cmp(&right).then_with(|| self.end().cmp(&other.end()))
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.literal.cmp(&other.literal),
+        }
+    }
+}
+
+impl From<DateParts> for CslDate {
+    fn from(parts: DateParts) -> Self {
+        Self::new(parts)
+    }
+}
+
+/// Parse a raw date or `start/end` range into date parts.
+fn parse_raw(value: &str) -> Option<Vec<DateParts>> {
+    let value = value.trim();
+    if let Some(parts) = DateParts::parse(value) {
+        return Some(vec![parts]);
+    }
+    let (start, end) = value.split_once('/')?;
+    Some(vec![DateParts::parse(start)?, DateParts::parse(end)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Parses date parts with one, two or three numbers.
+    fn date_parts_deserialize_lengths() {
+        let date = serde_json::from_str::<DateParts>("[2019, 12, 1]").unwrap();
+        assert_eq!(date, DateParts::ymd(2019, 12, 1));
+        let date = serde_json::from_str::<DateParts>("[2019, 12]").unwrap();
+        assert_eq!(date, DateParts::year_month(2019, 12));
+        let date = serde_json::from_str::<DateParts>("[2019]").unwrap();
+        assert_eq!(date, DateParts::year_only(2019));
+    }
+
+    #[test]
+    /// Accepts Crossref-style string numbers.
+    fn date_parts_deserialize_strings() {
+        let date = serde_json::from_str::<DateParts>(r#"["2019", "12"]"#).unwrap();
+        assert_eq!(date, DateParts::year_month(2019, 12));
+    }
+
+    #[test]
+    /// Rejects a day that has no month.
+    fn date_parts_reject_day_without_month() {
+        assert!(serde_json::from_str::<DateParts>("[2019, null, 1]").is_err());
+    }
+
+    #[test]
+    /// Serializes exactly the parts that are present.
+    fn date_parts_serialize() {
+        let date = DateParts::ymd(2019, 12, 1);
+        assert_eq!(serde_json::to_string(&date).unwrap(), "[2019,12,1]");
+        let date = DateParts::year_month(2019, 12);
+        assert_eq!(serde_json::to_string(&date).unwrap(), "[2019,12]");
+        let date = DateParts::year_only(2019);
+        assert_eq!(serde_json::to_string(&date).unwrap(), "[2019]");
+    }
+
+    #[test]
+    /// Parses and re-serializes a date range losslessly.
+    fn csl_date_range_round_trip() {
+        let input = r#"{"date-parts":[[2019,12],[2020,1,15]]}"#;
+        let date: CslDate = serde_json::from_str(input).unwrap();
+        assert!(date.is_range());
+        assert_eq!(date.end(), Some(DateParts::ymd(2020, 1, 15)));
+        assert_eq!(serde_json::to_string(&date).unwrap(), input);
+    }
+
+    #[test]
+    /// Keeps season, circa, literal and raw forms.
+    fn csl_date_other_forms() {
+        let date: CslDate =
+            serde_json::from_str(r#"{"date-parts":[[1999]],"season":"3","circa":true}"#).unwrap();
+        assert_eq!(date.season(), Some(Season::Autumn));
+        assert!(date.is_circa());
+
+        let date: CslDate = serde_json::from_str(r#"{"date-parts":[[1999, 21]]}"#).unwrap();
+        assert_eq!(date.season(), Some(Season::Spring));
+        assert_eq!(date.start().unwrap().precision(), DatePrecision::Year);
+
+        let date: CslDate = serde_json::from_str(r#"{"literal":"n.d."}"#).unwrap();
+        assert_eq!(date.literal.as_deref(), Some("n.d."));
+        assert!(date.start().is_none());
+
+        let date: CslDate = serde_json::from_str(r#"{"raw":"2004-05/2004-07"}"#).unwrap();
+        assert_eq!(date.start(), Some(DateParts::year_month(2004, 5)));
+        assert_eq!(date.end(), Some(DateParts::year_month(2004, 7)));
+        assert_eq!(
+            serde_json::to_string(&date).unwrap(),
+            r#"{"raw":"2004-05/2004-07"}"#
+        );
+    }
+
+    #[test]
+    /// Treats Crossref's `[[null]]` as an unknown date.
+    fn csl_date_null_parts() {
+        let date: CslDate = serde_json::from_str(r#"{"date-parts":[[null]]}"#).unwrap();
+        assert!(date.date_parts.is_empty());
+        assert!(date.to_naive_date().is_none());
+    }
+
+    #[test]
+    /// Converts to chrono with precision.
+    fn csl_date_to_naive_date() {
+        let date = CslDate::new(DateParts::year_month(2019, 12));
+        let precise = date.to_naive_date().unwrap();
+        assert_eq!(precise.date, NaiveDate::from_ymd_opt(2019, 12, 1).unwrap());
+        assert_eq!(precise.precision, DatePrecision::Month);
+    }
+
+    #[test]
+    /// Sorts dates of mixed precision chronologically.
+    fn csl_date_sorting() {
+        let mut dates = [
+            CslDate::new(DateParts::ymd(2019, 3, 1)),
+            CslDate::literal("forthcoming"),
+            CslDate::new(DateParts::year_only(2019)),
+            CslDate::range(DateParts::year_only(2019), DateParts::year_only(2020)),
+            CslDate::new(DateParts::year_month(2019, 3)),
+            CslDate::new(DateParts::year_only(-50)),
+        ];
+        dates.sort_by(CslDate::cmp_by_date);
+        let keys: Vec<String> = dates
+            .iter()
+            .map(|date| match date.start() {
+                Some(parts) => parts.to_string(),
+                None => date.literal.clone().unwrap_or_default(),
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "-0050",
+                "2019",
+                "2019",
+                "2019-03",
+                "2019-03-01",
+                "forthcoming"
+            ]
+        );
+        assert!(dates[2].is_range());
+    }
+}
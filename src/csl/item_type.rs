@@ -0,0 +1,146 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Generates the CSL item type enum with its wire names.
+macro_rules! csl_item_types {
+    ($($variant:ident => $name:literal),+ $(,)?) => {
+        /// CSL 1.0.2 item types.
+        ///
+        /// Unknown values are preserved in [`CslItemType::Other`] so records
+        /// from non-conforming sources survive a round-trip.
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+        pub enum CslItemType {
+            $($variant,)+
+            /// Generic document, used when nothing more specific applies.
+            #[default]
+            Document,
+            /// A type not defined by CSL 1.0.2.
+            Other(String),
+        }
+
+        impl CslItemType {
+            /// Return the CSL wire name of the item type.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $name,)+
+                    Self::Document => "document",
+                    Self::Other(value) => value.as_str(),
+                }
+            }
+
+            /// Return every item type defined by CSL 1.0.2.
+            pub fn all() -> &'static [CslItemType] {
+                &[$(Self::$variant,)+ Self::Document]
+            }
+        }
+
+        impl FromStr for CslItemType {
+            type Err = std::convert::Infallible;
+
+            /// Parse a CSL wire name, keeping unknown names as `Other`.
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Ok(match value {
+                    $($name => Self::$variant,)+
+                    "document" => Self::Document,
+                    other => Self::Other(other.to_string()),
+                })
+            }
+        }
+    };
+}
+
+csl_item_types! {
+    Article => "article",
+    ArticleJournal => "article-journal",
+    ArticleMagazine => "article-magazine",
+    ArticleNewspaper => "article-newspaper",
+    Bill => "bill",
+    Book => "book",
+    Broadcast => "broadcast",
+    Chapter => "chapter",
+    Classic => "classic",
+    Collection => "collection",
+    Dataset => "dataset",
+    Entry => "entry",
+    EntryDictionary => "entry-dictionary",
+    EntryEncyclopedia => "entry-encyclopedia",
+    Event => "event",
+    Figure => "figure",
+    Graphic => "graphic",
+    Hearing => "hearing",
+    Interview => "interview",
+    LegalCase => "legal_case",
+    Legislation => "legislation",
+    Manuscript => "manuscript",
+    Map => "map",
+    MotionPicture => "motion_picture",
+    MusicalScore => "musical_score",
+    Pamphlet => "pamphlet",
+    PaperConference => "paper-conference",
+    Patent => "patent",
+    Performance => "performance",
+    Periodical => "periodical",
+    PersonalCommunication => "personal_communication",
+    Post => "post",
+    PostWeblog => "post-weblog",
+    Regulation => "regulation",
+    Report => "report",
+    Review => "review",
+    ReviewBook => "review-book",
+    Software => "software",
+    Song => "song",
+    Speech => "speech",
+    Standard => "standard",
+    Thesis => "thesis",
+    Treaty => "treaty",
+    Webpage => "webpage",
+}
+
+impl fmt::Display for CslItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for CslItemType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CslItemType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Ok(value.parse().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Round-trips every known item type through its wire name.
+    fn item_type_round_trip_known() {
+        for item_type in CslItemType::all() {
+            let parsed: CslItemType = item_type.as_str().parse().unwrap();
+            assert_eq!(&parsed, item_type);
+        }
+    }
+
+    #[test]
+    /// Keeps unknown item types verbatim.
+    fn item_type_preserves_unknown() {
+        let parsed: CslItemType = serde_json::from_str("\"journal-issue\"").unwrap();
+        assert_eq!(parsed, CslItemType::Other("journal-issue".to_string()));
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "\"journal-issue\"");
+    }
+}
@@ -1,56 +1,404 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
-use crate::csl::value::{ClsDatePart, CslValue};
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+use crate::csl::date::CslDate;
+use crate::csl::item_type::CslItemType;
+use crate::csl::name::CslName;
+use crate::csl::page::PageRange;
+use crate::csl::value::{CslValue, string_or_first, string_or_seq};
+
+/// Title variables that Crossref sends as lists of strings.
+const TITLE_LISTS: &[&str] = &[
+    "collection-title",
+    "container-title",
+    "container-title-short",
+    "original-title",
+    "title",
+    "title-short",
+];
+
+/// Identifier variables that some sources send as a single string.
+const STRING_LISTS: &[&str] = &["ISBN", "ISSN"];
+
+/// A CSL-JSON 1.0.2 item, including the Crossref extensions sent by doi.org.
+///
+/// Title variables sent as lists are read as their first entry; the list
+/// itself is kept aside and written back in place of the string as long as
+/// the string still matches its first entry. `ISBN` and `ISSN` sent as a
+/// single string are kept the same way.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self", rename_all = "kebab-case")]
 pub struct DoiMetadata {
     /// The DOI identifier.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     /// doi item type
     #[serde(rename = "type")]
-    pub item_type: String,
+    pub item_type: CslItemType,
+    /// doi item citation key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_key: Option<String>,
     /// doi item categories
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub categories: Vec<String>,
-    /// doi item publisher
-    pub publisher: String,
-    /// doi item issued date
-    pub issued: Issued,
-    /// doi item doi
-    #[serde(rename = "DOI")]
-    pub doi: String,
-    /// doi item title
-    pub title: String,
     /// doi item language
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub lang: Option<String>,
-    /// doi item abstract
-    #[serde(rename = "abstract")]
-    pub abstract_text: String,
-    /// doi item journal
-    #[serde(rename = "URL")]
-    pub url: String,
+    pub language: Option<String>,
+    /// doi item journal abbreviation
+    #[serde(
+        rename = "journalAbbreviation",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub journal_abbreviation: Option<String>,
+    /// doi item short title
+    #[serde(rename = "shortTitle", skip_serializing_if = "Option::is_none")]
+    pub short_title: Option<String>,
+
+    // Name variables.
     /// doi item author
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub author: Vec<Author>,
-    /// doi item issn
+    pub author: Vec<CslName>,
+    /// doi item chair
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chair: Vec<CslName>,
+    /// doi item collection editor
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collection_editor: Vec<CslName>,
+    /// doi item compiler
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compiler: Vec<CslName>,
+    /// doi item composer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub composer: Vec<CslName>,
+    /// doi item container author
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub container_author: Vec<CslName>,
+    /// doi item contributor
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributor: Vec<CslName>,
+    /// doi item curator
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub curator: Vec<CslName>,
+    /// doi item director
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub director: Vec<CslName>,
+    /// doi item editor
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub editor: Vec<CslName>,
+    /// doi item editorial director
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub editorial_director: Vec<CslName>,
+    /// doi item executive producer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executive_producer: Vec<CslName>,
+    /// doi item guest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guest: Vec<CslName>,
+    /// doi item host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host: Vec<CslName>,
+    /// doi item interviewer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interviewer: Vec<CslName>,
+    /// doi item illustrator
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub illustrator: Vec<CslName>,
+    /// doi item narrator
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub narrator: Vec<CslName>,
+    /// doi item organizer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub organizer: Vec<CslName>,
+    /// doi item original author
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub original_author: Vec<CslName>,
+    /// doi item performer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub performer: Vec<CslName>,
+    /// doi item producer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub producer: Vec<CslName>,
+    /// doi item recipient
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipient: Vec<CslName>,
+    /// doi item reviewed author
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviewed_author: Vec<CslName>,
+    /// doi item script writer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub script_writer: Vec<CslName>,
+    /// doi item series creator
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub series_creator: Vec<CslName>,
+    /// doi item translator
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub translator: Vec<CslName>,
+
+    // Date variables.
+    /// doi item accessed date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessed: Option<CslDate>,
+    /// doi item available date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_date: Option<CslDate>,
+    /// doi item event date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_date: Option<CslDate>,
+    /// doi item issued date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued: Option<CslDate>,
+    /// doi item original date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_date: Option<CslDate>,
+    /// doi item submitted date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitted: Option<CslDate>,
+    /// doi item print publication date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_print: Option<CslDate>,
+    /// doi item online publication date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_online: Option<CslDate>,
+
+    // Standard variables.
+    /// doi item abstract
+    #[serde(rename = "abstract", skip_serializing_if = "Option::is_none")]
+    pub abstract_text: Option<String>,
+    /// doi item annotation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annote: Option<String>,
+    /// doi item archive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+    /// doi item archive collection
+    #[serde(rename = "archive_collection", skip_serializing_if = "Option::is_none")]
+    pub archive_collection: Option<String>,
+    /// doi item archive location
+    #[serde(rename = "archive_location", skip_serializing_if = "Option::is_none")]
+    pub archive_location: Option<String>,
+    /// doi item archive place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_place: Option<String>,
+    /// doi item authority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authority: Option<String>,
+    /// doi item call number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_number: Option<String>,
+    /// doi item citation label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_label: Option<String>,
+    /// doi item collection title
+    #[serde(
+        default,
+        deserialize_with = "string_or_first",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub collection_title: Option<String>,
+    /// doi item container title
+    #[serde(
+        default,
+        deserialize_with = "string_or_first",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub container_title: Option<String>,
+    /// doi item short container title
+    #[serde(
+        default,
+        deserialize_with = "string_or_first",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub container_title_short: Option<String>,
+    /// doi item dimensions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<String>,
+    /// doi item division
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub division: Option<String>,
+    /// doi item doi
+    #[serde(rename = "DOI", skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    /// doi item event (deprecated alias of `event-title`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    /// doi item event title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_title: Option<String>,
+    /// doi item event place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_place: Option<String>,
+    /// doi item genre
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    /// doi item isbn
+    #[serde(
+        rename = "ISBN",
+        default,
+        deserialize_with = "string_or_seq",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub isbn: Vec<String>,
+    /// doi item issn
+    #[serde(
+        rename = "ISSN",
+        default,
+        deserialize_with = "string_or_seq",
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub issn: Vec<String>,
+    /// doi item jurisdiction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+    /// doi item keyword
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+    /// doi item language tag as sent by some registration agencies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// doi item license
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// doi item medium
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub medium: Option<String>,
+    /// doi item note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// doi item original publisher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_publisher: Option<String>,
+    /// doi item original publisher place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_publisher_place: Option<String>,
+    /// doi item original title
+    #[serde(
+        default,
+        deserialize_with = "string_or_first",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub original_title: Option<String>,
+    /// doi item page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<CslValue>,
+    /// doi item first page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_first: Option<CslValue>,
+    /// doi item part title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_title: Option<String>,
+    /// doi item PubMed Central id
+    #[serde(rename = "PMCID", skip_serializing_if = "Option::is_none")]
+    pub pmcid: Option<String>,
+    /// doi item PubMed id
+    #[serde(rename = "PMID", skip_serializing_if = "Option::is_none")]
+    pub pmid: Option<String>,
+    /// doi item publisher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// doi item publisher place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher_place: Option<String>,
+    /// doi item references (free text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub references: Option<String>,
+    /// doi item reviewed genre
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_genre: Option<String>,
+    /// doi item reviewed title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_title: Option<String>,
+    /// doi item scale
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<String>,
+    /// doi metadata source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// doi item status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// doi item title
+    #[serde(
+        default,
+        deserialize_with = "string_or_first",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title: Option<String>,
+    /// doi item short title
+    #[serde(
+        default,
+        deserialize_with = "string_or_first",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title_short: Option<String>,
+    /// doi item url
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// doi item volume title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_title: Option<String>,
+    /// doi item year suffix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year_suffix: Option<String>,
+
+    // Number variables.
+    /// doi item chapter number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter_number: Option<CslValue>,
+    /// doi item citation number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_number: Option<CslValue>,
+    /// doi item collection number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_number: Option<CslValue>,
+    /// doi item edition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edition: Option<CslValue>,
+    /// doi item first reference note number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_reference_note_number: Option<CslValue>,
+    /// doi item issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<CslValue>,
+    /// doi item locator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locator: Option<CslValue>,
+    /// doi item number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<CslValue>,
+    /// doi item number of pages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_pages: Option<CslValue>,
+    /// doi item number of volumes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_volumes: Option<CslValue>,
+    /// doi item part number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_number: Option<CslValue>,
+    /// doi item printing number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub printing_number: Option<CslValue>,
+    /// doi item section
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<CslValue>,
+    /// doi item supplement number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supplement_number: Option<CslValue>,
+    /// doi item version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<CslValue>,
     /// doi item volume
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volume: Option<CslValue>,
+
+    // Crossref extensions sent through doi.org content negotiation.
     /// doi item reference
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub reference: Vec<Reference>,
-    /// doi item issue
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub issue: Option<CslValue>,
-    /// doi metadata source
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub source: Option<String>,
     /// doi item reference count
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference_count: Option<usize>,
@@ -60,62 +408,274 @@ pub struct DoiMetadata {
     /// doi item content domain
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content_domain: Option<ContentDomain>,
+
+    /// Free-form `custom` object defined by CSL 1.0.2.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, Value>,
+    /// Any key not modeled above, kept verbatim for lossless round-trips.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+    /// Title lists and single-string `ISBN`/`ISSN` values as they were read.
+    #[serde(skip)]
+    pub originals: ListOriginals,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct Issued {
-    pub date_parts: ClsDatePart,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub date_time: Option<DateTime<Utc>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub timestamp: Option<i64>,
+/// The original form of list variables read into a [`DoiMetadata`].
+///
+/// Its contents are private; the field only exists so that serialization can
+/// write the lists back, and struct literals fill it with `..Default::default()`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListOriginals(BTreeMap<&'static str, Value>);
+
+impl<'de> Deserialize<'de> for DoiMetadata {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Map::<String, Value>::deserialize(deserializer)?;
+        let originals = TITLE_LISTS
+            .iter()
+            .filter_map(|key| match raw.get(*key) {
+                Some(list @ Value::Array(_)) => Some((*key, list.clone())),
+                _ => None,
+            })
+            .chain(STRING_LISTS.iter().filter_map(|key| match raw.get(*key) {
+                Some(single @ Value::String(_)) => Some((*key, single.clone())),
+                _ => None,
+            }))
+            .collect();
+        let mut metadata =
+            DoiMetadata::deserialize(Value::Object(raw)).map_err(D::Error::custom)?;
+        metadata.originals = ListOriginals(originals);
+        Ok(metadata)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct Author {
-    pub given: String,
-    pub family: String,
-    #[serde(rename = "ORCID", skip_serializing_if = "Option::is_none")]
-    pub orcid: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sequence: Option<String>,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub affiliation: Vec<String>,
+impl Serialize for DoiMetadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut item = Cow::Borrowed(self);
+        let mut originals = Vec::new();
+        for (key, original) in &self.originals.0 {
+            let item = item.to_mut();
+            // Write the original only while the field still matches it.
+            let matches = if TITLE_LISTS.contains(key) {
+                let field = item.title_field(key);
+                let first = original
+                    .as_array()
+                    .map(|list| list.first().and_then(Value::as_str));
+                let matches = first.is_some_and(|first| first == field.as_deref());
+                if matches {
+                    *field = None;
+                }
+                matches
+            } else {
+                let field = if *key == "ISBN" {
+                    &mut item.isbn
+                } else {
+                    &mut item.issn
+                };
+                let matches = original.as_str().is_some_and(|single| *field == [single]);
+                if matches {
+                    field.clear();
+                }
+                matches
+            };
+            if matches {
+                originals.push((*key, original));
+            }
+        }
+        if originals.is_empty() {
+            return DoiMetadata::serialize(&item, serializer);
+        }
+        let mut map = match DoiMetadata::serialize(&item, serde_json::value::Serializer) {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => unreachable!("DoiMetadata serializes to an object"),
+            Err(error) => return Err(S::Error::custom(error)),
+        };
+        for (key, original) in originals {
+            map.insert(key.to_string(), original.clone());
+        }
+        map.serialize(serializer)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct JournalIssue {
-    pub issue: String,
-    pub published_print: PublishedPrint,
+impl DoiMetadata {
+    /// Return the field of a title variable listed in [`TITLE_LISTS`].
+    fn title_field(&mut self, key: &str) -> &mut Option<String> {
+        match key {
+            "collection-title" => &mut self.collection_title,
+            "container-title" => &mut self.container_title,
+            "container-title-short" => &mut self.container_title_short,
+            "original-title" => &mut self.original_title,
+            "title-short" => &mut self.title_short,
+            _ => &mut self.title,
+        }
+    }
+
+    /// Return the parsed `page` variable, falling back to `page-first`.
+    pub fn page_range(&self) -> Option<PageRange> {
+        self.page
+            .as_ref()
+            .or(self.page_first.as_ref())
+            .and_then(CslValue::page_range)
+    }
+
+    /// Return the first page from `page-first` or the start of `page`.
+    pub fn first_page(&self) -> Option<String> {
+        match &self.page_first {
+            Some(page_first) => Some(page_first.to_string()),
+            None => self
+                .page_range()
+                .and_then(|range| range.first().map(str::to_string)),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
-pub struct PublishedPrint {
-    pub date_parts: ClsDatePart,
+pub struct JournalIssue {
+    pub issue: String,
+    pub published_print: CslDate,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Reference {
     pub key: String,
     #[serde(rename = "DOI", skip_serializing_if = "Option::is_none")]
     pub doi: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi_asserted_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub year: Option<CslValue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volume: Option<CslValue>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<CslValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_page: Option<CslValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub journal_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub article_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unstructured: Option<String>,
+    /// Any key not modeled above, kept verbatim for lossless round-trips.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ContentDomain {
     pub domain: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crossmark_restriction: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CROSSREF_CSL: &str = r#"{
+        "indexed": {"date-parts": [[2024, 1, 5]], "date-time": "2024-01-05T10:00:00Z", "timestamp": 1704448800000},
+        "reference-count": 1,
+        "publisher": "Test Publisher",
+        "issue": "3",
+        "content-domain": {"domain": [], "crossmark-restriction": false},
+        "DOI": "10.5555/12345678",
+        "type": "article-journal",
+        "page": "100-110",
+        "source": "Crossref",
+        "is-referenced-by-count": 7,
+        "title": "Toward a Unified Theory of High-Energy Metaphysics",
+        "author": [{"given": "Josiah", "family": "Carberry", "sequence": "first", "affiliation": []}],
+        "editor": [{"given": "Jane", "family": "Doe"}],
+        "container-title": "Journal of Psychoceramics",
+        "original-title": [],
+        "language": "en",
+        "published-print": {"date-parts": [[2008, 8, 13]]},
+        "issued": {"date-parts": [[2008, 8, 13]]},
+        "volume": 5,
+        "ISSN": ["0264-3561"],
+        "ISBN": "978-3-16-148410-0",
+        "URL": "https://doi.org/10.5555/12345678",
+        "reference": [{"key": "ref1", "unstructured": "A reference", "doi-asserted-by": "crossref", "first-page": "12"}],
+        "note": "A note",
+        "custom": {"shelf": "B2"},
+        "subject": ["General Medicine"]
+    }"#;
+
+    #[test]
+    /// Parses a Crossref-flavored CSL item with standard and extension keys.
+    fn metadata_parse_crossref_csl() {
+        let metadata: DoiMetadata = serde_json::from_str(CROSSREF_CSL).unwrap();
+        assert_eq!(metadata.item_type, CslItemType::ArticleJournal);
+        assert_eq!(metadata.doi.as_deref(), Some("10.5555/12345678"));
+        assert_eq!(
+            metadata.container_title.as_deref(),
+            Some("Journal of Psychoceramics")
+        );
+        assert_eq!(metadata.editor.len(), 1);
+        assert!(metadata.published_print.is_some());
+        assert_eq!(metadata.issn, vec!["0264-3561".to_string()]);
+        assert_eq!(metadata.isbn, vec!["978-3-16-148410-0".to_string()]);
+        assert_eq!(metadata.custom.get("shelf"), Some(&Value::from("B2")));
+        assert!(metadata.extra.contains_key("indexed"));
+        assert!(metadata.extra.contains_key("subject"));
+        assert_eq!(metadata.first_page().as_deref(), Some("100"));
+        assert_eq!(
+            metadata.page_range().and_then(|range| range.page_count()),
+            Some(11)
+        );
+    }
+
+    #[test]
+    /// Keeps unknown keys when serializing a parsed item back to JSON.
+    fn metadata_round_trip_preserves_unknown_keys() {
+        let metadata: DoiMetadata = serde_json::from_str(CROSSREF_CSL).unwrap();
+        let value = serde_json::to_value(&metadata).unwrap();
+        let original: Value = serde_json::from_str(CROSSREF_CSL).unwrap();
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    /// Writes title lists back whole, unless the title was changed.
+    fn metadata_round_trip_preserves_title_lists() {
+        let input = serde_json::json!({
+            "type": "article-journal",
+            "title": ["A", "B"],
+            "original-title": ["O1", "O2"],
+            "container-title": [],
+            "title-short": "Short"
+        });
+        let mut metadata: DoiMetadata = serde_json::from_value(input.clone()).unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("A"));
+        assert_eq!(metadata.original_title.as_deref(), Some("O1"));
+        assert_eq!(metadata.container_title, None);
+        assert!(metadata.extra.is_empty());
+        assert_eq!(serde_json::to_value(&metadata).unwrap(), input);
+
+        metadata.title = Some("Changed".to_string());
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(value["title"], "Changed");
+        assert_eq!(value["original-title"], serde_json::json!(["O1", "O2"]));
+    }
+
+    #[test]
+    /// Accepts a minimal item with only a type.
+    fn metadata_parse_minimal() {
+        let metadata: DoiMetadata = serde_json::from_str(r#"{"type": "dataset"}"#).unwrap();
+        assert_eq!(metadata.item_type, CslItemType::Dataset);
+        assert!(metadata.title.is_none());
+        assert_eq!(
+            serde_json::to_string(&metadata).unwrap(),
+            r#"{"type":"dataset"}"#
+        );
+    }
 }
@@ -1,5 +1,13 @@
+mod date;
+mod item_type;
 mod metadata;
+mod name;
+mod page;
 mod value;
 
+pub use date::*;
+pub use item_type::*;
 pub use metadata::*;
+pub use name::*;
+pub use page::*;
 pub use value::*;
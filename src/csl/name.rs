@@ -0,0 +1,597 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// Suffixes recognised by the name parser.
+const NAME_SUFFIXES: &[&str] = &[
+    "jr", "jr.", "sr", "sr.", "ii", "iii", "iv", "esq", "esq.", "phd", "ph.d.",
+];
+
+/// How non-dropping particles ("van" in "van Gogh") are treated.
+///
+/// Mirrors the CSL `demote-non-dropping-particle` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DemoteParticle {
+    /// Keep the particle with the family name for display and sorting.
+    Never,
+    /// Keep the particle for display, but sort by the bare family name.
+    SortOnly,
+    /// Move the particle after the given name for display and sorting.
+    #[default]
+    DisplayAndSort,
+}
+
+/// An affiliation, sent by Crossref as `{"name": ...}` and by others as a string.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Affiliation {
+    pub name: String,
+    /// Other keys of the affiliation object, such as `id` or `place`.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl<'de> Deserialize<'de> for Affiliation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Object {
+            #[serde(default)]
+            name: String,
+            #[serde(flatten)]
+            extra: BTreeMap<String, Value>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Name(String),
+            Object(Object),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Name(name) => Affiliation {
+                name,
+                extra: BTreeMap::new(),
+            },
+            Raw::Object(object) => Affiliation {
+                name: object.name,
+                extra: object.extra,
+            },
+        })
+    }
+}
+
+/// A CSL name variable entry (author, editor, ...).
+///
+/// Personal names use `family`/`given` with optional particles and suffix;
+/// institutional names use `literal`. Crossref sends organizations as
+/// `{"name": ...}`, which is read into `literal`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self", rename_all = "kebab-case")]
+pub struct CslName {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dropping_particle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub non_dropping_particle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comma_suffix: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub static_ordering: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_names: Option<Value>,
+    #[serde(rename = "ORCID", skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+    #[serde(
+        rename = "authenticated-orcid",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub authenticated_orcid: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affiliation: Vec<Affiliation>,
+    /// Keys without a field above, plus an empty `affiliation` list when one
+    /// was sent, so Crossref names serialize back unchanged.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl<'de> Deserialize<'de> for CslName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut raw = Map::<String, Value>::deserialize(deserializer)?;
+        let personal = ["family", "given", "literal"]
+            .iter()
+            .any(|key| raw.contains_key(*key));
+        if !personal && raw.get("name").is_some_and(Value::is_string) {
+            let name = raw.remove("name").unwrap_or_default();
+            raw.insert("literal".to_string(), name);
+        }
+        let empty_affiliation = raw
+            .get("affiliation")
+            .and_then(Value::as_array)
+            .is_some_and(Vec::is_empty);
+        let mut name = CslName::deserialize(Value::Object(raw)).map_err(D::Error::custom)?;
+        if empty_affiliation {
+            name.extra
+                .insert("affiliation".to_string(), Value::Array(Vec::new()));
+        }
+        Ok(name)
+    }
+}
+
+impl Serialize for CslName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut name = Cow::Borrowed(self);
+        // The empty-list marker must not repeat a non-empty `affiliation`.
+        if !self.affiliation.is_empty() && self.extra.contains_key("affiliation") {
+            name.to_mut().extra.remove("affiliation");
+        }
+        CslName::serialize(&name, serializer)
+    }
+}
+
+impl CslName {
+    /// Build a personal name from family and given parts.
+    pub fn new(family: &str, given: &str) -> Self {
+        Self {
+            family: non_empty(family),
+            given: non_empty(given),
+            ..Default::default()
+        }
+    }
+
+    /// Build an institutional name that must not be split.
+    pub fn literal(name: &str) -> Self {
+        Self {
+            literal: non_empty(name),
+            ..Default::default()
+        }
+    }
+
+    /// Parse a free-text personal name into CSL parts.
+    ///
+    /// Accepts display order ("Ludwig van Beethoven Jr.") and sort order
+    /// ("Beethoven, L. van", "van Beethoven, Ludwig, Jr.", BibTeX-style
+    /// "Beethoven, Jr., Ludwig"). Lowercase words before the family name
+    /// become the non-dropping particle; lowercase words trailing the given
+    /// name in sort order become the dropping particle.
+    pub fn parse(input: &str) -> Self {
+        let input = collapse_whitespace(input);
+        if input.is_empty() {
+            return Self::default();
+        }
+
+        let parts: Vec<&str> = input.split(',').map(str::trim).collect();
+        match parts.as_slice() {
+            [display] => Self::parse_display_order(display),
+            [display, suffix] if is_suffix(suffix) => {
+                let mut name = Self::parse_display_order(display);
+                name.suffix = non_empty(suffix);
+                name.comma_suffix = Some(Value::Bool(true));
+                name
+            }
+            [family, given] => Self::parse_sort_order(family, given, ""),
+            [family, suffix, given] if is_suffix(suffix) => {
+                Self::parse_sort_order(family, given, suffix)
+            }
+            [family, given, suffix, ..] => Self::parse_sort_order(family, given, suffix),
+            [] => Self::default(),
+        }
+    }
+
+    /// Parse "Given particle Family Suffix".
+    fn parse_display_order(input: &str) -> Self {
+        let mut words: Vec<&str> = input.split(' ').filter(|word| !word.is_empty()).collect();
+
+        let mut suffix = Vec::new();
+        while words.len() > 1 && words.last().is_some_and(|word| is_suffix(word)) {
+            suffix.insert(0, words.pop().unwrap_or_default());
+        }
+
+        let Some(family) = words.pop() else {
+            return Self::default();
+        };
+
+        let mut particle = Vec::new();
+        while words.last().is_some_and(|word| is_particle(word)) {
+            particle.insert(0, words.pop().unwrap_or_default());
+        }
+
+        Self {
+            family: non_empty(family),
+            given: non_empty(&words.join(" ")),
+            non_dropping_particle: non_empty(&particle.join(" ")),
+            suffix: non_empty(&suffix.join(" ")),
+            ..Default::default()
+        }
+    }
+
+    /// Parse "particle Family" + "Given particle" + optional suffix.
+    fn parse_sort_order(family: &str, given: &str, suffix: &str) -> Self {
+        let (non_dropping, family) = split_leading_particles(family);
+        let (given, dropping) = split_trailing_particles(given);
+        Self {
+            family: non_empty(&family),
+            given: non_empty(&given),
+            dropping_particle: non_empty(&dropping),
+            non_dropping_particle: non_empty(&non_dropping),
+            suffix: non_empty(suffix),
+            ..Default::default()
+        }
+    }
+
+    /// Return a copy with particles split out of `given`/`family`.
+    ///
+    /// Follows CSL `parse-names`: nothing is changed when it is set to false,
+    /// or for literal names.
+    pub fn parse_particles(&self) -> Self {
+        let mut name = self.clone();
+        if self.literal.is_some() || is_false(self.parse_names.as_ref()) {
+            return name;
+        }
+
+        if name.non_dropping_particle.is_none()
+            && let Some(family) = &self.family
+        {
+            let (particle, family) = split_leading_particles(family);
+            if !family.is_empty() {
+                name.non_dropping_particle = non_empty(&particle);
+                name.family = non_empty(&family);
+            }
+        }
+        if name.dropping_particle.is_none()
+            && let Some(given) = &self.given
+        {
+            let (given, particle) = split_trailing_particles(given);
+            if !given.is_empty() {
+                name.dropping_particle = non_empty(&particle);
+                name.given = non_empty(&given);
+            }
+        }
+        name
+    }
+
+    /// Return true for institutional names.
+    pub fn is_literal(&self) -> bool {
+        self.literal.is_some()
+    }
+
+    /// Return initials for the given name, appending `initialize_with`
+    /// after each initial ("Jean-Paul" with "." gives "J.-P.").
+    pub fn initials(&self, initialize_with: &str) -> Option<String> {
+        let given = self.given.as_deref()?;
+        let mut words = Vec::new();
+        for word in given.split([' ', '.']).filter(|word| !word.is_empty()) {
+            let hyphenated: Vec<String> = word
+                .split('-')
+                .filter_map(|part| part.chars().next())
+                .map(|initial| format!("{}{}", initial.to_uppercase(), initialize_with))
+                .collect();
+            words.push(hyphenated.join("-"));
+        }
+        let initials = words.concat();
+        let initials = initials.trim_end();
+        (!initials.is_empty()).then(|| initials.to_string())
+    }
+
+    /// Return the family name with its non-dropping particle, if any.
+    pub fn family_with_particle(&self) -> Option<String> {
+        let family = self.family.as_deref()?;
+        Some(join_non_empty(&[
+            self.non_dropping_particle.as_deref().unwrap_or_default(),
+            family,
+        ]))
+    }
+
+    /// Return the name in display order ("Ludwig van Beethoven Jr.").
+    pub fn display_name(&self) -> String {
+        if let Some(literal) = &self.literal {
+            return literal.clone();
+        }
+        let name = join_non_empty(&[
+            self.given.as_deref().unwrap_or_default(),
+            self.dropping_particle.as_deref().unwrap_or_default(),
+            self.non_dropping_particle.as_deref().unwrap_or_default(),
+            self.family.as_deref().unwrap_or_default(),
+        ]);
+        match self.suffix.as_deref() {
+            Some(suffix) if self.has_comma_suffix() => format!("{name}, {suffix}"),
+            Some(suffix) => join_non_empty(&[&name, suffix]),
+            None => name,
+        }
+    }
+
+    /// Return the name in sort order ("Beethoven, Ludwig van, Jr.").
+    pub fn inverted_name(&self, demote: DemoteParticle) -> String {
+        if let Some(literal) = &self.literal {
+            return literal.clone();
+        }
+        let non_dropping = self.non_dropping_particle.as_deref().unwrap_or_default();
+        let family = self.family.as_deref().unwrap_or_default();
+        let (family, trailing) = match demote {
+            DemoteParticle::DisplayAndSort => (family.to_string(), non_dropping),
+            _ => (join_non_empty(&[non_dropping, family]), ""),
+        };
+        let given = join_non_empty(&[
+            self.given.as_deref().unwrap_or_default(),
+            self.dropping_particle.as_deref().unwrap_or_default(),
+            trailing,
+        ]);
+        let mut parts = vec![family];
+        parts.extend(non_empty(&given));
+        parts.extend(self.suffix.clone());
+        parts.retain(|part| !part.is_empty());
+        parts.join(", ")
+    }
+
+    /// Return a case-insensitive key for sorting names.
+    ///
+    /// Components are ordered family, particles, given, suffix, following the
+    /// CSL name-sorting rules for the chosen particle demotion.
+    pub fn sort_key(&self, demote: DemoteParticle) -> String {
+        if let Some(literal) = &self.literal {
+            return literal.to_lowercase();
+        }
+        let non_dropping = self.non_dropping_particle.as_deref().unwrap_or_default();
+        let family = self.family.as_deref().unwrap_or_default();
+        let key = match demote {
+            DemoteParticle::Never => join_non_empty(&[
+                non_dropping,
+                family,
+                self.dropping_particle.as_deref().unwrap_or_default(),
+                self.given.as_deref().unwrap_or_default(),
+                self.suffix.as_deref().unwrap_or_default(),
+            ]),
+            DemoteParticle::SortOnly | DemoteParticle::DisplayAndSort => join_non_empty(&[
+                family,
+                self.dropping_particle.as_deref().unwrap_or_default(),
+                non_dropping,
+                self.given.as_deref().unwrap_or_default(),
+                self.suffix.as_deref().unwrap_or_default(),
+            ]),
+        };
+        key.to_lowercase()
+    }
+
+    /// Return true when the suffix is separated by a comma.
+    fn has_comma_suffix(&self) -> bool {
+        matches!(self.comma_suffix.as_ref(), Some(value) if !is_false(Some(value)))
+    }
+}
+
+impl fmt::Display for CslName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.display_name())
+    }
+}
+
+/// Return true when a CSL flag value is explicitly false.
+fn is_false(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(flag)) => !flag,
+        Some(Value::Number(number)) => number.as_i64() == Some(0),
+        Some(Value::String(text)) => text.is_empty() || text == "0" || text == "false",
+        _ => false,
+    }
+}
+
+/// Return true for name suffixes such as "Jr." or "III".
+fn is_suffix(word: &str) -> bool {
+    NAME_SUFFIXES.contains(&word.trim().to_lowercase().as_str())
+}
+
+/// Return true for lowercase name particles such as "van", "de" or "d'".
+fn is_particle(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_lowercase)
+}
+
+/// Split "van der Berg" into ("van der", "Berg").
+fn split_leading_particles(family: &str) -> (String, String) {
+    let words: Vec<&str> = family.split_whitespace().collect();
+    let count = words.iter().take_while(|word| is_particle(word)).count();
+    if count == words.len() {
+        return (String::new(), words.join(" "));
+    }
+    (words[..count].join(" "), words[count..].join(" "))
+}
+
+/// Split "Ludwig van" into ("Ludwig", "van").
+fn split_trailing_particles(given: &str) -> (String, String) {
+    let words: Vec<&str> = given.split_whitespace().collect();
+    let count = words
+        .iter()
+        .rev()
+        .take_while(|word| is_particle(word))
+        .count();
+    if count == words.len() {
+        return (words.join(" "), String::new());
+    }
+    let split = words.len() - count;
+    (words[..split].join(" "), words[split..].join(" "))
+}
+
+/// Join non-empty strings with a single space.
+fn join_non_empty(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Collapse runs of whitespace into single spaces.
+fn collapse_whitespace(input: &str) -> String {
+    input.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Return an owned string when the trimmed input is non-empty.
+fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Keeps unknown keys and reads Crossref's `name` as a literal name.
+    fn name_round_trip_keeps_unknown_keys() {
+        let name: CslName = serde_json::from_value(serde_json::json!({
+            "name": "CERN Collaboration",
+            "sequence": "additional",
+            "affiliation": [],
+            "role": "lead"
+        }))
+        .unwrap();
+        assert_eq!(name.literal.as_deref(), Some("CERN Collaboration"));
+        assert_eq!(name.extra.get("role"), Some(&Value::from("lead")));
+        assert_eq!(
+            serde_json::to_value(&name).unwrap(),
+            serde_json::json!({
+                "literal": "CERN Collaboration",
+                "sequence": "additional",
+                "affiliation": [],
+                "role": "lead"
+            })
+        );
+
+        let person: CslName = serde_json::from_value(serde_json::json!({
+            "family": "Doe",
+            "name": "Jane Doe"
+        }))
+        .unwrap();
+        assert_eq!(person.literal, None);
+        assert_eq!(person.extra.get("name"), Some(&Value::from("Jane Doe")));
+    }
+
+    #[test]
+    /// Parses display order with particle and suffix.
+    fn name_parse_display_order() {
+        let name = CslName::parse("Ludwig van Beethoven Jr.");
+        assert_eq!(name.given.as_deref(), Some("Ludwig"));
+        assert_eq!(name.non_dropping_particle.as_deref(), Some("van"));
+        assert_eq!(name.family.as_deref(), Some("Beethoven"));
+        assert_eq!(name.suffix.as_deref(), Some("Jr."));
+    }
+
+    #[test]
+    /// Parses sort order with a trailing dropping particle.
+    fn name_parse_sort_order() {
+        let name = CslName::parse("Beethoven, L. van");
+        assert_eq!(name.family.as_deref(), Some("Beethoven"));
+        assert_eq!(name.given.as_deref(), Some("L."));
+        assert_eq!(name.dropping_particle.as_deref(), Some("van"));
+    }
+
+    #[test]
+    /// Parses sort order with suffix in either position.
+    fn name_parse_sort_order_suffix() {
+        let name = CslName::parse("de la Fontaine, Jean, Jr.");
+        assert_eq!(name.non_dropping_particle.as_deref(), Some("de la"));
+        assert_eq!(name.family.as_deref(), Some("Fontaine"));
+        assert_eq!(name.given.as_deref(), Some("Jean"));
+        assert_eq!(name.suffix.as_deref(), Some("Jr."));
+
+        let name = CslName::parse("King, Jr., Martin Luther");
+        assert_eq!(name.family.as_deref(), Some("King"));
+        assert_eq!(name.given.as_deref(), Some("Martin Luther"));
+        assert_eq!(name.suffix.as_deref(), Some("Jr."));
+    }
+
+    #[test]
+    /// Keeps a single word as the family name.
+    fn name_parse_single_word() {
+        let name = CslName::parse("Plato");
+        assert_eq!(name.family.as_deref(), Some("Plato"));
+        assert!(name.given.is_none());
+    }
+
+    #[test]
+    /// Reads literal names and Crossref affiliation objects.
+    fn name_deserialize_literal_and_affiliation() {
+        let name: CslName = serde_json::from_str(
+            r#"{"literal": "WHO Consortium", "affiliation": [{"name": "WHO"}, "UN"]}"#,
+        )
+        .unwrap();
+        assert!(name.is_literal());
+        assert_eq!(name.display_name(), "WHO Consortium");
+        assert_eq!(name.affiliation[0].name, "WHO");
+        assert_eq!(name.affiliation[1].name, "UN");
+    }
+
+    #[test]
+    /// Splits particles out of given/family unless parse-names is false.
+    fn name_parse_particles() {
+        let name: CslName =
+            serde_json::from_str(r#"{"family": "van Gogh", "given": "Vincent"}"#).unwrap();
+        let parsed = name.parse_particles();
+        assert_eq!(parsed.non_dropping_particle.as_deref(), Some("van"));
+        assert_eq!(parsed.family.as_deref(), Some("Gogh"));
+
+        let name: CslName = serde_json::from_str(
+            r#"{"family": "van Gogh", "given": "Vincent", "parse-names": false}"#,
+        )
+        .unwrap();
+        assert_eq!(name.parse_particles(), name);
+    }
+
+    #[test]
+    /// Builds initials for plain and hyphenated given names.
+    fn name_initials() {
+        assert_eq!(
+            CslName::new("Sartre", "Jean-Paul").initials(".").as_deref(),
+            Some("J.-P.")
+        );
+        assert_eq!(
+            CslName::new("Tolkien", "John Ronald Reuel")
+                .initials(". ")
+                .as_deref(),
+            Some("J. R. R.")
+        );
+        assert_eq!(
+            CslName::new("Beethoven", "L.").initials(".").as_deref(),
+            Some("L.")
+        );
+    }
+
+    #[test]
+    /// Builds sort keys and inverted names honoring particle demotion.
+    fn name_sort_keys() {
+        let name = CslName::parse("Vincent van Gogh");
+        assert_eq!(
+            name.sort_key(DemoteParticle::DisplayAndSort),
+            "gogh van vincent"
+        );
+        assert_eq!(name.sort_key(DemoteParticle::Never), "van gogh vincent");
+        assert_eq!(
+            name.inverted_name(DemoteParticle::DisplayAndSort),
+            "Gogh, Vincent van"
+        );
+        assert_eq!(
+            name.inverted_name(DemoteParticle::Never),
+            "van Gogh, Vincent"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+/// CSL `page-range-format` options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageRangeFormat {
+    /// Keep both ends as given.
+    #[default]
+    AsIs,
+    /// Write out the second number in full ("321–328").
+    Expanded,
+    /// Keep only the changed digits ("321–8").
+    Minimal,
+    /// Keep at least two digits ("321–28").
+    MinimalTwo,
+    /// Chicago Manual of Style (16th ed.) abbreviation rules.
+    Chicago,
+}
+
+/// One page or page span inside a `page` variable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PageSpan {
+    pub first: String,
+    pub last: Option<String>,
+}
+
+impl PageSpan {
+    /// Return the last page with abbreviated numbers expanded ("321-8" gives "328").
+    pub fn expanded_last(&self) -> Option<String> {
+        let last = self.last.as_deref()?;
+        let (first_prefix, first_digits) = split_digits(&self.first);
+        let (last_prefix, last_digits) = split_digits(last);
+        if first_digits.is_empty()
+            || last_digits.is_empty()
+            || !(last_prefix.is_empty() || last_prefix == first_prefix)
+            || last_digits.len() >= first_digits.len()
+        {
+            return Some(last.to_string());
+        }
+        let keep = first_digits.len() - last_digits.len();
+        Some(format!(
+            "{}{}{}",
+            first_prefix,
+            &first_digits[..keep],
+            last_digits
+        ))
+    }
+
+    /// Return the number of pages covered when both ends are plain numbers.
+    pub fn page_count(&self) -> Option<i64> {
+        let first: i64 = self.first.parse().ok()?;
+        let last: i64 = match self.expanded_last() {
+            Some(last) => last.parse().ok()?,
+            None => first,
+        };
+        (last >= first).then_some(last - first + 1)
+    }
+
+    /// Format the span using a CSL page range format and separator.
+    pub fn format(&self, format: PageRangeFormat, separator: &str) -> String {
+        let Some(last) = self.last.as_deref() else {
+            return self.first.clone();
+        };
+        let last = match format {
+            PageRangeFormat::AsIs => last.to_string(),
+            _ => {
+                let expanded = self.expanded_last().unwrap_or_else(|| last.to_string());
+                match format {
+                    PageRangeFormat::Minimal => minimize(&self.first, &expanded, 1),
+                    PageRangeFormat::MinimalTwo => minimize(&self.first, &expanded, 2),
+                    PageRangeFormat::Chicago => chicago(&self.first, &expanded),
+                    _ => expanded,
+                }
+            }
+        };
+        format!("{}{}{}", self.first, separator, last)
+    }
+}
+
+/// A parsed CSL `page` value such as `12-15`, `12–15`, `S12` or `1,3-5`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PageRange {
+    pub spans: Vec<PageSpan>,
+}
+
+impl PageRange {
+    /// Parse a page value, returning `None` for empty input.
+    pub fn parse(input: &str) -> Option<Self> {
+        let spans: Vec<PageSpan> = input
+            .split([',', '&'])
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| match item.split_once(['-', '–', '—']) {
+                Some((first, last)) => {
+                    let last = last.trim_start_matches(['-', '–', '—']).trim();
+                    PageSpan {
+                        first: first.trim().to_string(),
+                        last: (!last.is_empty()).then(|| last.to_string()),
+                    }
+                }
+                None => PageSpan {
+                    first: item.to_string(),
+                    last: None,
+                },
+            })
+            .collect();
+        (!spans.is_empty()).then_some(Self { spans })
+    }
+
+    /// Return the first page, as used for the CSL `page-first` variable.
+    pub fn first(&self) -> Option<&str> {
+        self.spans.first().map(|span| span.first.as_str())
+    }
+
+    /// Return the total number of pages when every span is numeric.
+    pub fn page_count(&self) -> Option<i64> {
+        self.spans.iter().map(PageSpan::page_count).sum()
+    }
+
+    /// Format every span, joining spans with ", ".
+    pub fn format(&self, format: PageRangeFormat, separator: &str) -> String {
+        self.spans
+            .iter()
+            .map(|span| span.format(format, separator))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for PageRange {
+    /// Display with an en dash between the ends of each span.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(PageRangeFormat::AsIs, "–"))
+    }
+}
+
+/// Split a page into its non-digit prefix and trailing digits.
+fn split_digits(page: &str) -> (&str, &str) {
+    let index = page
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_digit())
+        .last()
+        .map_or(page.len(), |(index, _)| index);
+    page.split_at(index)
+}
+
+/// Drop leading digits shared with the first page, keeping at least `min` digits.
+fn minimize(first: &str, last: &str, min: usize) -> String {
+    let (first_prefix, first_digits) = split_digits(first);
+    let (last_prefix, last_digits) = split_digits(last);
+    if first_prefix != last_prefix || first_digits.len() != last_digits.len() {
+        return last.to_string();
+    }
+    let shared = first_digits
+        .bytes()
+        .zip(last_digits.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let keep = (last_digits.len() - shared).max(min).min(last_digits.len());
+    last_digits[last_digits.len() - keep..].to_string()
+}
+
+/// Apply the Chicago (16th ed.) page range rules.
+fn chicago(first: &str, last: &str) -> String {
+    let Ok(number) = split_digits(first).1.parse::<u64>() else {
+        return last.to_string();
+    };
+    if number < 100 || number % 100 == 0 {
+        last.to_string()
+    } else if number % 100 < 10 {
+        minimize(first, last, 1)
+    } else {
+        minimize(first, last, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Parses single pages, ranges and lists.
+    fn page_range_parse() {
+        let range = PageRange::parse("1,3-5, S12–S15").unwrap();
+        assert_eq!(range.spans.len(), 3);
+        assert_eq!(range.first(), Some("1"));
+        assert_eq!(range.spans[1].last.as_deref(), Some("5"));
+        assert_eq!(range.spans[2].first, "S12");
+        assert_eq!(range.to_string(), "1, 3–5, S12–S15");
+        assert!(PageRange::parse(" ").is_none());
+    }
+
+    #[test]
+    /// Expands abbreviated ranges and counts pages.
+    fn page_range_expand_and_count() {
+        let range = PageRange::parse("321-28").unwrap();
+        assert_eq!(range.spans[0].expanded_last().as_deref(), Some("328"));
+        assert_eq!(range.page_count(), Some(8));
+        assert_eq!(PageRange::parse("e1234").unwrap().page_count(), None);
+    }
+
+    #[test]
+    /// Formats ranges with the CSL page-range-format options.
+    fn page_range_formats() {
+        let range = PageRange::parse("321-328").unwrap();
+        assert_eq!(range.format(PageRangeFormat::Expanded, "–"), "321–328");
+        assert_eq!(range.format(PageRangeFormat::Minimal, "–"), "321–8");
+        assert_eq!(range.format(PageRangeFormat::MinimalTwo, "–"), "321–28");
+        assert_eq!(range.format(PageRangeFormat::Chicago, "–"), "321–28");
+        let range = PageRange::parse("101-108").unwrap();
+        assert_eq!(range.format(PageRangeFormat::Chicago, "–"), "101–8");
+        let range = PageRange::parse("42-45").unwrap();
+        assert_eq!(range.format(PageRangeFormat::Chicago, "–"), "42–45");
+    }
+}
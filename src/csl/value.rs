@@ -1,113 +1,243 @@
 use std::fmt;
+use std::sync::LazyLock;
 
-use serde::{
-    Deserialize, Deserializer, Serialize, Serializer,
-    de::{SeqAccess, Visitor},
-};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::csl::page::PageRange;
+
+/// A CSL variable that may be sent as a string or a number (`volume`, `issue`, `page`, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CslValue {
     String(String),
     Number(i64),
 }
 
-/// year, month, day
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct ClsDate(pub i64, pub Option<i64>, pub Option<i64>);
-
-pub type ClsDatePart = Vec<ClsDate>;
-
-impl Serialize for ClsDate {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        match (self.1, self.2) {
-            (None, None) => [self.0].serialize(serializer),
-            (None, Some(_)) => [self.0].serialize(serializer),
-            (Some(month), None) => [self.0, month].serialize(serializer),
-            (Some(month), Some(day)) => [self.0, month, day].serialize(serializer),
-        }
+/// A single number with optional affixes, e.g. `S12`, `2nd` or `L2d`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumericToken {
+    pub prefix: String,
+    pub number: i64,
+    pub suffix: String,
+}
+
+/// A number or a range of numbers inside a numeric variable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumericRange {
+    pub start: NumericToken,
+    pub end: Option<NumericToken>,
+}
+
+/// Static regex for one numeric token: optional letter prefix, digits, optional letter suffix.
+static NUMERIC_TOKEN_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([^\d\s,&\-–—]*)(\d+)([^\d\s,&\-–—]*)$").unwrap());
+
+impl NumericToken {
+    /// Parse a single token such as `12`, `S12` or `2nd`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let caps = NUMERIC_TOKEN_REGEX.captures(input.trim())?;
+        Some(Self {
+            prefix: caps.get(1).map_or("", |m| m.as_str()).to_string(),
+            number: caps.get(2)?.as_str().parse().ok()?,
+            suffix: caps.get(3).map_or("", |m| m.as_str()).to_string(),
+        })
     }
 }
 
-impl<'de> Deserialize<'de> for ClsDate {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct ClsDateVisitor;
+impl fmt::Display for NumericToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.prefix, self.number, self.suffix)
+    }
+}
+
+impl CslValue {
+    /// Return the value as a string slice when it was sent as a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            CslValue::String(value) => Some(value.as_str()),
+            CslValue::Number(_) => None,
+        }
+    }
+
+    /// Return the value as an integer, parsing plain numeric strings.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            CslValue::Number(value) => Some(*value),
+            CslValue::String(value) => value.trim().parse().ok(),
+        }
+    }
 
-        impl<'de> Visitor<'de> for ClsDateVisitor {
-            type Value = ClsDate;
+    /// Return true when the value is numeric in the CSL sense.
+    ///
+    /// Numbers may carry affixes (`S12`, `2nd`) and be joined by hyphens,
+    /// en dashes, commas or ampersands (`12-15`, `12–15`, `1,3-5`, `2 & 4`).
+    pub fn is_numeric(&self) -> bool {
+        self.numeric_ranges().is_some()
+    }
 
-            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                write!(f, "an array of 1, 2 or 3 integers: [year, month?, day?]")
+    /// Split a numeric value into its numbers and ranges.
+    ///
+    /// Returns `None` when the value is not numeric.
+    pub fn numeric_ranges(&self) -> Option<Vec<NumericRange>> {
+        let text = match self {
+            CslValue::Number(value) => {
+                let start = NumericToken {
+                    prefix: String::new(),
+                    number: *value,
+                    suffix: String::new(),
+                };
+                return Some(vec![NumericRange { start, end: None }]);
             }
+            CslValue::String(value) => value.trim(),
+        };
+        if text.is_empty() {
+            return None;
+        }
+
+        text.split([',', '&'])
+            .map(|item| {
+                let item = item.trim();
+                match item.split_once(['-', '–', '—']) {
+                    Some((start, end)) => Some(NumericRange {
+                        start: NumericToken::parse(start)?,
+                        end: Some(NumericToken::parse(
+                            end.trim_start_matches(['-', '–', '—']),
+                        )?),
+                    }),
+                    None => Some(NumericRange {
+                        start: NumericToken::parse(item)?,
+                        end: None,
+                    }),
+                }
+            })
+            .collect()
+    }
 
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-            where
-                A: SeqAccess<'de>,
-            {
-                let year: i64 = seq
-                    .next_element()?
-                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+    /// Return the first number of a numeric value (`12` for `12-15`).
+    pub fn first_number(&self) -> Option<i64> {
+        self.numeric_ranges()?
+            .first()
+            .map(|range| range.start.number)
+    }
 
-                let month: Option<i64> = seq.next_element()?;
-                let day: Option<i64> = seq.next_element()?;
+    /// Parse the value as a page range.
+    pub fn page_range(&self) -> Option<PageRange> {
+        PageRange::parse(&self.to_string())
+    }
+}
 
-                Ok(ClsDate(year, month, day))
-            }
+impl fmt::Display for CslValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CslValue::String(value) => f.write_str(value),
+            CslValue::Number(value) => write!(f, "{value}"),
         }
+    }
+}
 
-        deserializer.deserialize_seq(ClsDateVisitor)
+impl From<&str> for CslValue {
+    fn from(value: &str) -> Self {
+        CslValue::String(value.to_string())
     }
 }
 
+impl From<String> for CslValue {
+    fn from(value: String) -> Self {
+        CslValue::String(value)
+    }
+}
+
+impl From<i64> for CslValue {
+    fn from(value: i64) -> Self {
+        CslValue::Number(value)
+    }
+}
+
+/// A string or list of strings, as sent by different metadata sources.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+/// Deserialize an optional string that some sources send as a list of strings.
+///
+/// Crossref emits title-like variables as arrays (often empty); the first
+/// entry is read and an empty list becomes `None`. [`DoiMetadata`] keeps the
+/// list itself in `extra`.
+///
+/// [`DoiMetadata`]: crate::csl::DoiMetadata
+pub(crate) fn string_or_first<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<OneOrMany>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| value.into_vec().into_iter().next()))
+}
+
+/// Deserialize a field that may be sent as a single string or a list of strings.
+pub(crate) fn string_or_seq<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<OneOrMany>::deserialize(deserializer)?;
+    Ok(value.map(OneOrMany::into_vec).unwrap_or_default())
+}
+
 #[cfg(test)]
 mod tests {
-
     use super::*;
 
     #[test]
-    fn test_deserialize_cls_date() {
-        let date = serde_json::from_str::<ClsDate>("[2019, 12, 1]");
-        assert!(date.is_ok());
-        let date = serde_json::from_str::<ClsDate>("[2019, 12]");
-        assert!(date.is_ok());
-        let date = serde_json::from_str::<ClsDate>("[2019]");
-        assert!(date.is_ok());
+    /// Reads strings and numbers and exposes typed accessors.
+    fn csl_value_accessors() {
+        let value: CslValue = serde_json::from_str("\"12\"").unwrap();
+        assert_eq!(value.as_str(), Some("12"));
+        assert_eq!(value.as_i64(), Some(12));
+        assert_eq!(value.to_string(), "12");
+
+        let value: CslValue = serde_json::from_str("5").unwrap();
+        assert_eq!(value.as_str(), None);
+        assert_eq!(value.as_i64(), Some(5));
+        assert_eq!(value, CslValue::Number(5));
     }
 
     #[test]
-    fn test_serialize_cls_date() {
-        let date = ClsDate(2019, Some(12), Some(1));
-        let serialized = serde_json::to_string(&date);
-        assert!(serialized.is_ok());
-        if let Ok(serialized) = serialized {
-            assert_eq!(serialized, "[2019,12,1]");
+    /// Recognises CSL numeric values with ranges, lists and affixes.
+    fn csl_value_numeric() {
+        for numeric in [
+            "12", "12-15", "12–15", "S12", "1,3-5", "2 & 4", "2nd", "L2d",
+        ] {
+            assert!(CslValue::from(numeric).is_numeric(), "{numeric}");
         }
-
-        let date = ClsDate(2019, Some(12), None);
-        let serialized = serde_json::to_string(&date);
-        assert!(serialized.is_ok());
-        if let Ok(serialized) = serialized {
-            assert_eq!(serialized, "[2019,12]");
-        }
-
-        let date = ClsDate(2019, None, None);
-        let serialized = serde_json::to_string(&date);
-        assert!(serialized.is_ok());
-        if let Ok(serialized) = serialized {
-            assert_eq!(serialized, "[2019]");
+        for text in ["", "Suppl", "12 Suppl 3", "1-", "e.g. 4"] {
+            assert!(!CslValue::from(text).is_numeric(), "{text}");
         }
+    }
 
-        let date = ClsDate(2019, None, Some(1));
-        let serialized = serde_json::to_string(&date);
-        assert!(serialized.is_ok());
-        if let Ok(serialized) = serialized {
-            assert_eq!(serialized, "[2019]");
-        }
+    #[test]
+    /// Splits numeric values into numbers and ranges.
+    fn csl_value_numeric_ranges() {
+        let ranges = CslValue::from("1,3-5").numeric_ranges().unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0].start.number, 1);
+        assert!(ranges[0].end.is_none());
+        assert_eq!(ranges[1].start.number, 3);
+        assert_eq!(ranges[1].end.as_ref().map(|end| end.number), Some(5));
+
+        let ranges = CslValue::from("S12").numeric_ranges().unwrap();
+        assert_eq!(ranges[0].start.prefix, "S");
+        assert_eq!(CslValue::from("12–15").first_number(), Some(12));
     }
 }
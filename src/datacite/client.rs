@@ -0,0 +1,440 @@
+use crate::Doi;
+use crate::csl::DoiMetadata;
+use crate::datacite::config::DataCiteConfig;
+use crate::datacite::error::*;
+use crate::datacite::query::{DataCiteList, DataCiteQuery, DataCiteStream};
+use crate::datacite::record::{DataCiteAttributes, DataCiteDoi, DataCiteResponse};
+use crate::http::{RateLimiter, is_not_found, user_agent_headers};
+use crate::source::error::{DataCiteSnafu, NotFoundSnafu, SourceError};
+use crate::source::{MetadataSource, Record};
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, HeaderValue};
+use serde::de::DeserializeOwned;
+use snafu::ResultExt;
+
+/// Client for the DataCite REST API.
+pub struct DataCiteClient {
+    client: reqwest::Client,
+    base_url: String,
+    limiter: RateLimiter,
+}
+
+impl DataCiteClient {
+    const JSON_API: &'static str = "application/vnd.api+json";
+    const DATACITE_XML: &'static str = "application/vnd.datacite.datacite+xml";
+
+    /// Build a DataCite client with configured defaults.
+    pub fn new(config: DataCiteConfig) -> std::result::Result<Self, DataCiteError> {
+        let base_url = config.base_url_value();
+        let mut headers = user_agent_headers(config.user_agent_value(), config.mailto_value());
+        headers.insert(ACCEPT, HeaderValue::from_static(Self::JSON_API));
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(config.timeout)
+            .build()
+            .context(RequestSnafu {
+                stage: "build-client",
+            })?;
+        let limiter = RateLimiter::new(Some(config.rate_limit_value()), config.concurrency);
+
+        Ok(Self {
+            client,
+            base_url,
+            limiter,
+        })
+    }
+
+    /// Fetch the DataCite record for a DOI.
+    pub async fn doi(&self, doi: &Doi) -> std::result::Result<DataCiteDoi, DataCiteError> {
+        self.doi_response(doi).await.map(|response| response.data)
+    }
+
+    /// Fetch the DataCite record for a DOI together with its JSON:API document.
+    pub async fn doi_response(
+        &self,
+        doi: &Doi,
+    ) -> std::result::Result<DataCiteResponse<DataCiteDoi>, DataCiteError> {
+        let path = format!("dois/{}", doi.as_str());
+        self.get_json(&path, &Self::detail_params()).await
+    }
+
+    /// Fetch the DataCite Kernel 4 XML for a DOI and parse it.
+    pub async fn doi_xml(
+        &self,
+        doi: &Doi,
+    ) -> std::result::Result<DataCiteAttributes, DataCiteError> {
+        let url = format!("{}/dois/{}", self.base_url, doi.as_str());
+        let _permit = self.limiter.acquire().await;
+
+        let xml = self
+            .client
+            .get(url)
+            .header(ACCEPT, Self::DATACITE_XML)
+            .send()
+            .await
+            .context(RequestSnafu {
+                stage: "send-request",
+            })?
+            .error_for_status()
+            .context(RequestSnafu {
+                stage: "http-status",
+            })?
+            .text()
+            .await
+            .context(RequestSnafu {
+                stage: "response-body",
+            })?;
+        DataCiteAttributes::from_xml(&xml)
+    }
+
+    /// Fetch the DataCite record for a DOI mapped into CSL-JSON metadata.
+    pub async fn metadata(&self, doi: &Doi) -> std::result::Result<DoiMetadata, DataCiteError> {
+        self.doi(doi).await.map(DoiMetadata::from)
+    }
+
+    /// Fetch a single page of `/dois` search results.
+    pub async fn dois(
+        &self,
+        query: &DataCiteQuery,
+    ) -> std::result::Result<DataCiteList, DataCiteError> {
+        self.dois_page(query, None).await
+    }
+
+    /// Stream every DOI matching the query, following `page[cursor]` links.
+    pub fn dois_stream(&self, query: DataCiteQuery) -> DataCiteStream<'_> {
+        DataCiteStream::new(self, query)
+    }
+
+    /// Fetch one page of `/dois` results.
+    pub(crate) async fn dois_page(
+        &self,
+        query: &DataCiteQuery,
+        cursor: Option<&str>,
+    ) -> std::result::Result<DataCiteList, DataCiteError> {
+        let mut params = query.to_query_pairs(cursor);
+        params.extend(Self::detail_params());
+        self.get_json("dois", &params).await
+    }
+
+    /// Ask for schema 4.5 affiliation and publisher objects instead of plain strings.
+    fn detail_params() -> Vec<(String, String)> {
+        vec![
+            ("affiliation".to_string(), "true".to_string()),
+            ("publisher".to_string(), "true".to_string()),
+        ]
+    }
+
+    /// Fetch a path relative to the base URL and parse the JSON body.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+    ) -> std::result::Result<T, DataCiteError> {
+        let url = format!("{}/{}", self.base_url, path);
+        let _permit = self.limiter.acquire().await;
+
+        let response = self
+            .client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .context(RequestSnafu {
+                stage: "send-request",
+            })?
+            .error_for_status()
+            .context(RequestSnafu {
+                stage: "http-status",
+            })?;
+
+        let text = response.text().await.context(RequestSnafu {
+            stage: "response-body",
+        })?;
+
+        let mut deserializer = serde_json::Deserializer::from_str(&text);
+
+        serde_path_to_error::deserialize::<_, T>(&mut deserializer).context(SerializePathSnafu {
+            stage: "parse-json",
+        })
+    }
+}
+
+#[async_trait]
+impl MetadataSource for DataCiteClient {
+    fn name(&self) -> &str {
+        "datacite"
+    }
+
+    async fn metadata(&self, doi: &Doi) -> std::result::Result<Record, SourceError> {
+        match DataCiteClient::metadata(self, doi).await {
+            Ok(metadata) => Ok(Record::new(metadata, MetadataSource::name(self))),
+            Err(DataCiteError::Request { source, .. }) if is_not_found(&source) => NotFoundSnafu {
+                source_name: MetadataSource::name(self),
+                doi: doi.as_str(),
+            }
+            .fail(),
+            Err(error) => Err(error).context(DataCiteSnafu { stage: "metadata" }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::{CslItemType, CslValue};
+    use crate::datacite::record::DataCiteContainer;
+    use futures::StreamExt;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const DOI_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/datacite/doi.json"
+    ));
+
+    /// Build a client pointed at the mock server.
+    fn client(server: &MockServer) -> DataCiteClient {
+        DataCiteClient::new(DataCiteConfig {
+            base_url: server.uri(),
+            rate_limit_per_sec: Some(0),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    /// Parses schema 4.x attributes of a DOI record.
+    async fn datacite_doi_parses_fixture() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/dois/10.5281/zenodo.1234567"))
+            .and(header("accept", "application/vnd.api+json"))
+            .and(query_param("publisher", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(DOI_FIXTURE))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5281/zenodo.1234567").unwrap();
+        let record = client(&server).doi(&doi).await.unwrap();
+        let attributes = &record.attributes;
+        assert_eq!(record.resource_type, "dois");
+        assert_eq!(
+            attributes.primary_title(),
+            Some("Ocean temperature profiles, North Atlantic")
+        );
+        assert_eq!(
+            attributes.types.resource_type_general.as_deref(),
+            Some("Dataset")
+        );
+        assert_eq!(
+            attributes.creators[0].orcid(),
+            Some("https://orcid.org/0000-0003-1419-2405")
+        );
+        assert_eq!(
+            attributes
+                .publisher
+                .as_ref()
+                .unwrap()
+                .publisher_identifier
+                .as_deref(),
+            Some("https://ror.org/02catss52")
+        );
+        assert_eq!(
+            attributes.related_identifiers[0].relation_type.as_deref(),
+            Some("IsVersionOf")
+        );
+        assert_eq!(
+            attributes.funding_references[0].award_number.as_deref(),
+            Some("654321")
+        );
+        assert_eq!(
+            attributes.rights_list[0].rights_identifier.as_deref(),
+            Some("cc-by-4.0")
+        );
+        let point = attributes.geo_locations[0]
+            .geo_location_point
+            .as_ref()
+            .unwrap();
+        assert_eq!(point.point_latitude, Some(45.5));
+        assert_eq!(attributes.publication_year, Some(2019));
+        assert_eq!(attributes.view_count, 120);
+        assert_eq!(attributes.citation_count, 3);
+    }
+
+    #[tokio::test]
+    /// Maps a DataCite record into CSL-JSON metadata.
+    async fn datacite_metadata_maps_to_csl() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/dois/10.5281/zenodo.1234567"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(DOI_FIXTURE))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5281/zenodo.1234567").unwrap();
+        let metadata = client(&server).metadata(&doi).await.unwrap();
+        assert_eq!(metadata.item_type, CslItemType::Dataset);
+        assert_eq!(metadata.doi.as_deref(), Some("10.5281/zenodo.1234567"));
+        assert_eq!(metadata.author[0].family.as_deref(), Some("Fenner"));
+        assert_eq!(metadata.author[0].given.as_deref(), Some("Martin"));
+        assert_eq!(
+            metadata.author[1].literal.as_deref(),
+            Some("Ocean Observatories Initiative")
+        );
+        assert_eq!(metadata.editor[0].family.as_deref(), Some("Garcia"));
+        assert_eq!(metadata.publisher.as_deref(), Some("Zenodo"));
+        assert_eq!(
+            metadata.issued.as_ref().and_then(|date| date.year()),
+            Some(2019)
+        );
+        assert_eq!(
+            metadata.version.as_ref().and_then(|v| v.as_str()),
+            Some("1.2")
+        );
+        assert_eq!(
+            metadata.license.as_deref(),
+            Some("https://creativecommons.org/licenses/by/4.0/legalcode")
+        );
+        assert_eq!(
+            metadata.keyword.as_deref(),
+            Some("oceanography, temperature")
+        );
+        assert!(metadata.abstract_text.unwrap().starts_with("Temperature"));
+        assert_eq!(metadata.is_referenced_by_count, Some(3));
+    }
+
+    #[test]
+    /// Keeps container pages verbatim, including article numbers.
+    fn datacite_pages_verbatim() {
+        let attributes = DataCiteAttributes {
+            doi: "10.5555/pages".to_string(),
+            container: Some(DataCiteContainer {
+                first_page: Some("e1002".to_string()),
+                last_page: Some("e1010".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let metadata = DoiMetadata::from(attributes);
+        assert_eq!(
+            metadata.page.as_ref().and_then(CslValue::as_str),
+            Some("e1002-e1010")
+        );
+        assert_eq!(
+            metadata.page_first.as_ref().and_then(CslValue::as_str),
+            Some("e1002")
+        );
+    }
+
+    #[tokio::test]
+    /// Requests DataCite XML through content negotiation.
+    async fn datacite_doi_xml_parses_fixture() {
+        let server = MockServer::start().await;
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/datacite/resource.xml"
+        ));
+        Mock::given(method("GET"))
+            .and(path("/dois/10.5281/zenodo.1234567"))
+            .and(header("accept", "application/vnd.datacite.datacite+xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(fixture))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5281/zenodo.1234567").unwrap();
+        let attributes = client(&server).doi_xml(&doi).await.unwrap();
+        assert_eq!(attributes.doi, "10.5281/ZENODO.1234567");
+        assert_eq!(attributes.creators.len(), 2);
+    }
+
+    /// Build a `/dois` list page with the given DOIs and next cursor.
+    fn doi_list(server: &MockServer, dois: &[&str], next: Option<&str>) -> String {
+        let data: Vec<serde_json::Value> = dois
+            .iter()
+            .map(|doi| {
+                serde_json::json!({
+                    "id": doi,
+                    "type": "dois",
+                    "attributes": {"doi": doi, "publisher": "Zenodo"}
+                })
+            })
+            .collect();
+        let next = next.map(|cursor| {
+            format!(
+                "{}/dois?page%5Bcursor%5D={}&page%5Bsize%5D=2",
+                server.uri(),
+                cursor
+            )
+        });
+        serde_json::json!({
+            "data": data,
+            "meta": {"total": 3, "totalPages": 2, "states": []},
+            "links": {"self": format!("{}/dois", server.uri()), "next": next}
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    /// Walks every page following `page[cursor]` links.
+    async fn datacite_dois_stream_follows_cursor() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/dois"))
+            .and(query_param("page[cursor]", "1"))
+            .and(query_param("client-id", "cern.zenodo"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(doi_list(
+                &server,
+                &["10.5281/zenodo.1", "10.5281/zenodo.2"],
+                Some("MTU2"),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/dois"))
+            .and(query_param("page[cursor]", "MTU2"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(doi_list(
+                &server,
+                &["10.5281/zenodo.3"],
+                None,
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        let query = DataCiteQuery::new().client_id("cern.zenodo").page_size(2);
+        let mut stream = client.dois_stream(query);
+        let mut dois = Vec::new();
+        while let Some(record) = stream.next().await {
+            dois.push(record.unwrap().id);
+        }
+        assert_eq!(
+            dois,
+            ["10.5281/zenodo.1", "10.5281/zenodo.2", "10.5281/zenodo.3"]
+        );
+        assert_eq!(stream.total_results(), Some(3));
+    }
+
+    #[tokio::test]
+    /// Surfaces HTTP errors for unknown DOIs.
+    async fn datacite_doi_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_string(
+                r#"{"errors":[{"status":"404","title":"The resource you are looking for doesn't exist."}]}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let doi = Doi::parse("10.5281/missing").unwrap();
+        let error = client(&server).doi(&doi).await.unwrap_err();
+        assert!(matches!(
+            error,
+            DataCiteError::Request {
+                stage: "http-status",
+                ..
+            }
+        ));
+    }
+}
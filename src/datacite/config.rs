@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+/// Configuration for the DataCite REST API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataCiteConfig {
+    /// Base URL for the DataCite REST API (default: https://api.datacite.org).
+    pub base_url: String,
+    /// HTTP client timeout applied to each request.
+    pub timeout: Duration,
+    /// Contact email appended to the User-Agent header.
+    pub mailto: Option<String>,
+    /// Application identifier used in the User-Agent header.
+    pub user_agent: Option<String>,
+    /// Maximum requests per second; `None` uses 10/s (DataCite allows 3000 per 5 minutes).
+    pub rate_limit_per_sec: Option<u32>,
+    /// Maximum concurrent requests; `None` disables the limit.
+    pub concurrency: Option<usize>,
+}
+
+impl Default for DataCiteConfig {
+    /// Return default DataCite client configuration values.
+    fn default() -> Self {
+        Self {
+            base_url: "https://api.datacite.org".to_string(),
+            timeout: Duration::from_secs(30),
+            mailto: None,
+            user_agent: None,
+            rate_limit_per_sec: None,
+            concurrency: None,
+        }
+    }
+}
+
+impl DataCiteConfig {
+    /// Return trimmed base URL without trailing slash.
+    pub fn base_url_value(&self) -> String {
+        self.base_url.trim_end_matches('/').to_string()
+    }
+
+    /// Return trimmed mailto when configured.
+    pub fn mailto_value(&self) -> Option<&str> {
+        self.mailto
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Return trimmed user-agent when configured.
+    pub fn user_agent_value(&self) -> Option<&str> {
+        self.user_agent
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Return the request rate, defaulting to 10/s.
+    pub fn rate_limit_value(&self) -> u32 {
+        self.rate_limit_per_sec.unwrap_or(10)
+    }
+
+    /// Configure a contact email for the User-Agent header.
+    pub fn polite(mut self, email: &str) -> Self {
+        let trimmed = email.trim();
+        if !trimmed.is_empty() {
+            self.mailto = Some(trimmed.to_string());
+        }
+        self
+    }
+}
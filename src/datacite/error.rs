@@ -0,0 +1,30 @@
+use snafu::Snafu;
+
+/// Errors returned by the DataCite REST API client.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum DataCiteError {
+    #[snafu(display("HTTP request failed at {stage}: {source}"))]
+    Request {
+        stage: &'static str,
+        source: reqwest::Error,
+    },
+
+    #[snafu(display("Failed to serialize at {stage}: {source}"))]
+    SerializePath {
+        source: serde_path_to_error::Error<serde_json::Error>,
+        stage: &'static str,
+    },
+
+    #[snafu(display("Failed to parse XML at {stage}: {source}"))]
+    Xml {
+        stage: &'static str,
+        source: roxmltree::Error,
+    },
+
+    #[snafu(display("Unexpected XML document at {stage}: {message}"))]
+    XmlRecord {
+        stage: &'static str,
+        message: &'static str,
+    },
+}
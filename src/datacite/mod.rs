@@ -0,0 +1,6 @@
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod query;
+pub mod record;
+pub mod xml;
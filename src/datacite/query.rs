@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use futures::Stream;
+
+use crate::datacite::client::DataCiteClient;
+use crate::datacite::error::DataCiteError;
+use crate::datacite::record::{DataCiteDoi, DataCiteResponse};
+
+/// One page of `/dois` search results.
+pub type DataCiteList = DataCiteResponse<Vec<DataCiteDoi>>;
+
+/// Builder for DataCite `/dois` search parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataCiteQuery {
+    query: Option<String>,
+    params: Vec<(String, String)>,
+    sort: Option<String>,
+    page_size: Option<u32>,
+}
+
+impl DataCiteQuery {
+    /// Create an empty query matching every DOI.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Elasticsearch query string, e.g. `creators.name:Fenner`.
+    pub fn query(mut self, text: &str) -> Self {
+        self.query = Some(text.to_string());
+        self
+    }
+
+    /// Restrict to a resource type general, e.g. `dataset` or `software`.
+    pub fn resource_type(self, resource_type: &str) -> Self {
+        self.param("resource-type-id", resource_type)
+    }
+
+    /// Restrict to a DOI prefix, e.g. `10.5281`.
+    pub fn prefix(self, prefix: &str) -> Self {
+        self.param("prefix", prefix)
+    }
+
+    /// Restrict to a repository, e.g. `cern.zenodo`.
+    pub fn client_id(self, client_id: &str) -> Self {
+        self.param("client-id", client_id)
+    }
+
+    /// Restrict to a publication year or year range (`2019,2020`).
+    pub fn published(self, years: &str) -> Self {
+        self.param("published", years)
+    }
+
+    /// Add any other query parameter.
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.params.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sort results, e.g. `-created` or `relevance`.
+    pub fn sort(mut self, sort: &str) -> Self {
+        self.sort = Some(sort.to_string());
+        self
+    }
+
+    /// Set results per page (DataCite allows up to 1000).
+    pub fn page_size(mut self, size: u32) -> Self {
+        self.page_size = Some(size);
+        self
+    }
+
+    /// Build the query-string pairs, adding the paging cursor when given.
+    pub fn to_query_pairs(&self, cursor: Option<&str>) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        if let Some(query) = &self.query {
+            pairs.push(("query".to_string(), query.clone()));
+        }
+        pairs.extend(self.params.iter().cloned());
+        if let Some(sort) = &self.sort {
+            pairs.push(("sort".to_string(), sort.clone()));
+        }
+        if let Some(size) = self.page_size {
+            pairs.push(("page[size]".to_string(), size.to_string()));
+        }
+        if let Some(cursor) = cursor {
+            pairs.push(("page[cursor]".to_string(), cursor.to_string()));
+        }
+        pairs
+    }
+}
+
+/// Paging state of a [`DataCiteStream`].
+struct PageState {
+    cursor: Option<String>,
+    buffer: VecDeque<DataCiteDoi>,
+    finished: bool,
+}
+
+/// An async stream of DOIs walking DataCite results with `page[cursor]` paging.
+pub struct DataCiteStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<DataCiteDoi, DataCiteError>> + Send + 'a>>,
+    total: Arc<OnceLock<u64>>,
+}
+
+impl<'a> DataCiteStream<'a> {
+    /// Build a stream over `/dois`.
+    pub(crate) fn new(client: &'a DataCiteClient, query: DataCiteQuery) -> Self {
+        let total = Arc::new(OnceLock::new());
+        let shared_total = Arc::clone(&total);
+        // DataCite switches to cursor paging when the first cursor is `1`.
+        let state = PageState {
+            cursor: Some("1".to_string()),
+            buffer: VecDeque::new(),
+            finished: false,
+        };
+
+        let inner = futures::stream::try_unfold(state, move |mut state| {
+            let query = query.clone();
+            let total = Arc::clone(&shared_total);
+            async move {
+                loop {
+                    if let Some(doi) = state.buffer.pop_front() {
+                        return Ok(Some((doi, state)));
+                    }
+                    if state.finished {
+                        return Ok(None);
+                    }
+
+                    let page = client.dois_page(&query, state.cursor.as_deref()).await?;
+                    if let Some(meta) = &page.meta {
+                        let _ = total.set(meta.total);
+                    }
+                    let next = page.links.as_ref().and_then(|links| links.next_cursor());
+                    state.finished = page.data.is_empty() || next.is_none() || next == state.cursor;
+                    state.cursor = next;
+                    state.buffer.extend(page.data);
+                }
+            }
+        });
+
+        Self {
+            inner: Box::pin(inner),
+            total,
+        }
+    }
+
+    /// Return `meta.total` once the first page has been fetched.
+    pub fn total_results(&self) -> Option<u64> {
+        self.total.get().copied()
+    }
+}
+
+impl Stream for DataCiteStream<'_> {
+    type Item = Result<DataCiteDoi, DataCiteError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
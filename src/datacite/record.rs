@@ -0,0 +1,672 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::de::{DeserializeOwned, Deserializer};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::csl::{Affiliation, CslDate, CslItemType, CslName, CslValue, DateParts, DoiMetadata};
+
+/// A JSON:API document returned by the DataCite REST API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataCiteResponse<T> {
+    /// Primary data: one resource or a list of resources.
+    pub data: T,
+    /// Related resources requested with `include`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub included: Vec<Value>,
+    /// Totals and facets of list responses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<DataCiteMeta>,
+    /// Navigation links of list responses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<DataCiteLinks>,
+}
+
+/// The `meta` object of a DataCite list response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteMeta {
+    /// Total number of matching DOIs.
+    #[serde(default)]
+    pub total: u64,
+    /// Total number of pages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<u64>,
+    /// Current page number (page-number paging only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
+    /// Facets (`states`, `resourceTypes`, `published`, ...).
+    #[serde(flatten)]
+    pub facets: BTreeMap<String, Value>,
+}
+
+/// The `links` object of a DataCite list response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataCiteLinks {
+    #[serde(rename = "self", skip_serializing_if = "Option::is_none")]
+    pub self_link: Option<String>,
+    /// URL of the next page, carrying `page[cursor]` when cursor paging.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+}
+
+impl DataCiteLinks {
+    /// Return the `page[cursor]` value of the next page link.
+    pub fn next_cursor(&self) -> Option<String> {
+        let next = reqwest::Url::parse(self.next.as_deref()?).ok()?;
+        next.query_pairs()
+            .find(|(key, _)| key == "page[cursor]")
+            .map(|(_, value)| value.into_owned())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// A DataCite DOI resource from `/dois/{doi}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataCiteDoi {
+    /// resource id (the lowercase DOI)
+    pub id: String,
+    /// resource type, always `dois`
+    #[serde(rename = "type", default)]
+    pub resource_type: String,
+    /// DataCite Metadata Schema attributes
+    #[serde(default)]
+    pub attributes: DataCiteAttributes,
+    /// JSON:API relationships (client, provider, citations, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relationships: Option<Value>,
+}
+
+/// DataCite Metadata Schema 4.x attributes of a DOI.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteAttributes {
+    /// record doi
+    #[serde(default)]
+    pub doi: String,
+    /// record doi prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// record doi suffix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// record identifiers besides the DOI
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identifiers: Vec<DataCiteIdentifier>,
+    /// record alternate identifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternate_identifiers: Vec<DataCiteAlternateIdentifier>,
+    /// record creators
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub creators: Vec<DataCiteCreator>,
+    /// record titles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub titles: Vec<DataCiteTitle>,
+    /// record publisher (a string, or an object with `publisher=true`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<DataCitePublisher>,
+    /// record container (journal, repository, series)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<DataCiteContainer>,
+    /// record publication year
+    #[serde(
+        default,
+        deserialize_with = "lenient_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub publication_year: Option<i32>,
+    /// record subjects
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subjects: Vec<DataCiteSubject>,
+    /// record contributors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<DataCiteCreator>,
+    /// record dates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dates: Vec<DataCiteDate>,
+    /// record language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// record resource types
+    #[serde(default)]
+    pub types: DataCiteTypes,
+    /// record related identifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_identifiers: Vec<DataCiteRelatedIdentifier>,
+    /// record related items (schema 4.4+)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_items: Vec<Value>,
+    /// record sizes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sizes: Vec<String>,
+    /// record formats
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<String>,
+    /// record version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// record rights
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rights_list: Vec<DataCiteRights>,
+    /// record descriptions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub descriptions: Vec<DataCiteDescription>,
+    /// record geo-locations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub geo_locations: Vec<DataCiteGeoLocation>,
+    /// record funding references
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub funding_references: Vec<DataCiteFundingReference>,
+    /// record landing page url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// record content urls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_url: Option<Vec<String>>,
+    /// record metadata version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_version: Option<u32>,
+    /// record schema version URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+    /// record state (`findable`, `registered`, `draft`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// record view count
+    #[serde(default)]
+    pub view_count: u64,
+    /// record download count
+    #[serde(default)]
+    pub download_count: u64,
+    /// record citation count
+    #[serde(default)]
+    pub citation_count: u64,
+    /// record reference count
+    #[serde(default)]
+    pub reference_count: u64,
+    /// record version count
+    #[serde(default)]
+    pub version_count: u64,
+    /// record version-of count
+    #[serde(default)]
+    pub version_of_count: u64,
+    /// record creation timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    /// record registration timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registered: Option<String>,
+    /// record publication timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    /// record update timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    /// Attributes without a field above, e.g. `viewsOverTime` or `citationsOverTime`.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+/// An identifier of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteIdentifier {
+    pub identifier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier_type: Option<String>,
+}
+
+/// An alternate identifier of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteAlternateIdentifier {
+    pub alternate_identifier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate_identifier_type: Option<String>,
+}
+
+/// A creator or contributor of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteCreator {
+    /// full name, "Family, Given" for personal names
+    #[serde(default)]
+    pub name: String,
+    /// `Personal` or `Organizational`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    /// contributor role (contributors only), e.g. `Editor`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contributor_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affiliation: Vec<Affiliation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub name_identifiers: Vec<DataCiteNameIdentifier>,
+}
+
+impl DataCiteCreator {
+    /// Return the ORCID iD URL from the name identifiers.
+    pub fn orcid(&self) -> Option<&str> {
+        self.name_identifiers
+            .iter()
+            .find(|identifier| {
+                identifier
+                    .name_identifier_scheme
+                    .as_deref()
+                    .is_some_and(|scheme| scheme.eq_ignore_ascii_case("ORCID"))
+            })
+            .map(|identifier| identifier.name_identifier.as_str())
+    }
+
+    /// Convert to a CSL name, keeping organizations as literal names.
+    pub fn to_csl_name(&self) -> CslName {
+        let organizational = self.name_type.as_deref() == Some("Organizational");
+        let mut name = match (&self.family_name, &self.given_name) {
+            (Some(family), given) if !organizational => {
+                CslName::new(family, given.as_deref().unwrap_or_default())
+            }
+            _ if organizational || !self.name.contains(',') => CslName::literal(&self.name),
+            _ => CslName::parse(&self.name),
+        };
+        name.orcid = self.orcid().map(str::to_string);
+        name.affiliation = self.affiliation.clone();
+        name
+    }
+}
+
+/// A name identifier such as an ORCID iD or ROR id.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteNameIdentifier {
+    pub name_identifier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_identifier_scheme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme_uri: Option<String>,
+}
+
+/// A title of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteTitle {
+    pub title: String,
+    /// `AlternativeTitle`, `Subtitle`, `TranslatedTitle` or `Other`; `None` for the main title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// The publisher of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCitePublisher {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher_identifier_scheme: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for DataCitePublisher {
+    /// Accept a plain publisher name or a schema 4.5 publisher object.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Object {
+            name: String,
+            publisher_identifier: Option<String>,
+            publisher_identifier_scheme: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Name(String),
+            Object(Object),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Name(name) => Self {
+                name,
+                ..Default::default()
+            },
+            Raw::Object(object) => Self {
+                name: object.name,
+                publisher_identifier: object.publisher_identifier,
+                publisher_identifier_scheme: object.publisher_identifier_scheme,
+            },
+        })
+    }
+}
+
+/// The container a DataCite record is part of.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteContainer {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub container_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_page: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_page: Option<String>,
+}
+
+/// A subject of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteSubject {
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_scheme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classification_code: Option<String>,
+}
+
+/// A dated event of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteDate {
+    /// date, date range (`2019/2020`) or timestamp
+    pub date: String,
+    /// `Issued`, `Created`, `Submitted`, `Available`, `Accepted`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_information: Option<String>,
+}
+
+/// The resource types of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteTypes {
+    /// controlled type, e.g. `Dataset`, `Software`, `Preprint`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type_general: Option<String>,
+    /// free-text type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_org: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bibtex: Option<String>,
+    /// CSL item type chosen by DataCite
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citeproc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ris: Option<String>,
+}
+
+/// A related identifier of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteRelatedIdentifier {
+    pub related_identifier: String,
+    /// `DOI`, `URL`, `arXiv`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_identifier_type: Option<String>,
+    /// `IsSupplementTo`, `IsVersionOf`, `References`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type_general: Option<String>,
+}
+
+/// A rights statement of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteRights {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rights: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rights_uri: Option<String>,
+    /// SPDX identifier, e.g. `cc-by-4.0`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rights_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rights_identifier_scheme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// A description of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteDescription {
+    pub description: String,
+    /// `Abstract`, `Methods`, `SeriesInformation`, `TechnicalInfo`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// A geo-location of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteGeoLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo_location_place: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo_location_point: Option<DataCiteGeoPoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo_location_box: Option<DataCiteGeoBox>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo_location_polygon: Option<Value>,
+}
+
+/// A point given by longitude and latitude.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteGeoPoint {
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub point_longitude: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub point_latitude: Option<f64>,
+}
+
+/// A bounding box given by its bounds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteGeoBox {
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub west_bound_longitude: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub east_bound_longitude: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub south_bound_latitude: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub north_bound_latitude: Option<f64>,
+}
+
+/// A funding reference of a DataCite record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCiteFundingReference {
+    pub funder_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funder_identifier: Option<String>,
+    /// `Crossref Funder ID`, `ROR`, `GRID`, `ISNI` or `Other`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funder_identifier_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub award_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub award_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub award_title: Option<String>,
+}
+
+impl DataCiteAttributes {
+    /// Return the main title (the first title without a title type).
+    pub fn primary_title(&self) -> Option<&str> {
+        self.titles
+            .iter()
+            .find(|title| title.title_type.is_none())
+            .or_else(|| self.titles.first())
+            .map(|title| title.title.as_str())
+    }
+
+    /// Return the first date of the given type (e.g. `Issued`).
+    pub fn date(&self, date_type: &str) -> Option<&str> {
+        self.dates
+            .iter()
+            .find(|date| date.date_type.as_deref() == Some(date_type))
+            .map(|date| date.date.as_str())
+    }
+
+    /// Return the CSL item type, preferring DataCite's own `citeproc` mapping.
+    pub fn csl_item_type(&self) -> CslItemType {
+        if let Some(citeproc) = &self.types.citeproc
+            && let Ok(item_type) = CslItemType::from_str(citeproc)
+            && !matches!(item_type, CslItemType::Other(_))
+        {
+            return item_type;
+        }
+        match self.types.resource_type_general.as_deref() {
+            Some("Audiovisual") => CslItemType::MotionPicture,
+            Some("Book") => CslItemType::Book,
+            Some("BookChapter") => CslItemType::Chapter,
+            Some("Collection") => CslItemType::Collection,
+            Some("ConferencePaper" | "ConferenceProceeding") => CslItemType::PaperConference,
+            Some("Dataset" | "DataPaper") => CslItemType::Dataset,
+            Some("Dissertation") => CslItemType::Thesis,
+            Some("Event") => CslItemType::Event,
+            Some("Image") => CslItemType::Graphic,
+            Some("JournalArticle" | "Text") => CslItemType::ArticleJournal,
+            Some("Preprint") => CslItemType::Article,
+            Some("Report") => CslItemType::Report,
+            Some("Software" | "ComputationalNotebook") => CslItemType::Software,
+            Some("Sound") => CslItemType::Song,
+            Some("Standard") => CslItemType::Standard,
+            _ => CslItemType::Document,
+        }
+    }
+}
+
+impl From<DataCiteDoi> for DoiMetadata {
+    /// Map a DataCite record onto CSL-JSON variables.
+    fn from(record: DataCiteDoi) -> Self {
+        let mut attributes = record.attributes;
+        if attributes.doi.is_empty() {
+            attributes.doi = record.id;
+        }
+        Self::from(attributes)
+    }
+}
+
+impl From<DataCiteAttributes> for DoiMetadata {
+    /// Map DataCite attributes onto CSL-JSON variables.
+    fn from(attributes: DataCiteAttributes) -> Self {
+        let doi = attributes.doi.clone();
+        let contributors = |role: &str| -> Vec<CslName> {
+            attributes
+                .contributors
+                .iter()
+                .filter(|contributor| contributor.contributor_type.as_deref() == Some(role))
+                .map(DataCiteCreator::to_csl_name)
+                .collect()
+        };
+        let issued = attributes
+            .date("Issued")
+            .map(CslDate::from_raw)
+            .filter(|date| !date.date_parts.is_empty())
+            .or_else(|| {
+                attributes
+                    .publication_year
+                    .map(|year| CslDate::new(DateParts::year_only(year)))
+            });
+        let container = attributes.container.clone().unwrap_or_default();
+        let page = match (&container.first_page, &container.last_page) {
+            (Some(first), Some(last)) => Some(format!("{}-{}", first, last)),
+            (Some(first), None) => Some(first.clone()),
+            _ => None,
+        };
+        let keywords: Vec<&str> = attributes
+            .subjects
+            .iter()
+            .map(|subject| subject.subject.as_str())
+            .collect();
+
+        Self {
+            id: Some(doi.clone()),
+            item_type: attributes.csl_item_type(),
+            title: attributes.primary_title().map(str::to_string),
+            author: attributes
+                .creators
+                .iter()
+                .map(DataCiteCreator::to_csl_name)
+                .collect(),
+            editor: contributors("Editor"),
+            contributor: contributors("Other"),
+            publisher: attributes
+                .publisher
+                .as_ref()
+                .map(|publisher| publisher.name.clone()),
+            container_title: container.title.clone(),
+            volume: container.volume.as_deref().map(CslValue::from),
+            issue: container.issue.as_deref().map(CslValue::from),
+            page_first: container.first_page.as_deref().map(CslValue::from),
+            page: page.as_deref().map(CslValue::from),
+            issued,
+            submitted: attributes.date("Submitted").map(CslDate::from_raw),
+            available_date: attributes.date("Available").map(CslDate::from_raw),
+            abstract_text: attributes
+                .descriptions
+                .iter()
+                .find(|description| description.description_type.as_deref() == Some("Abstract"))
+                .map(|description| description.description.clone()),
+            keyword: (!keywords.is_empty()).then(|| keywords.join(", ")),
+            language: attributes.language.clone(),
+            genre: attributes.types.resource_type.clone(),
+            version: attributes.version.as_deref().map(CslValue::from),
+            license: attributes
+                .rights_list
+                .iter()
+                .find_map(|rights| rights.rights_uri.clone().or_else(|| rights.rights.clone())),
+            url: attributes.url.clone(),
+            doi: Some(doi),
+            source: Some("DataCite".to_string()),
+            dimensions: (!attributes.sizes.is_empty()).then(|| attributes.sizes.join(", ")),
+            medium: (!attributes.formats.is_empty()).then(|| attributes.formats.join(", ")),
+            is_referenced_by_count: Some(attributes.citation_count as usize),
+            reference_count: Some(attributes.reference_count as usize),
+            ..Default::default()
+        }
+    }
+}
+
+/// Deserialize a number that may be sent as a JSON number or a string.
+fn lenient_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + DeserializeOwned,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Number(number)) => number.to_string().parse().ok(),
+        Some(Value::String(text)) => text.trim().parse().ok(),
+        _ => None,
+    })
+}
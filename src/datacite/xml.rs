@@ -0,0 +1,809 @@
+//! Parsing and serialization of DataCite Metadata Schema 4.x XML.
+//!
+//! The XML maps onto the same [`DataCiteAttributes`] used for the REST API,
+//! so records from either source convert into `DoiMetadata` the same way.
+
+use std::fmt::Write;
+
+use roxmltree::{Document, Node, ParsingOptions};
+use serde_json::{Value, json};
+use snafu::{ResultExt, ensure};
+
+use crate::csl::Affiliation;
+use crate::datacite::error::*;
+use crate::datacite::record::*;
+
+const KERNEL_NAMESPACE: &str = "http://datacite.org/schema/kernel-4";
+const SCHEMA_LOCATION: &str =
+    "http://datacite.org/schema/kernel-4 https://schema.datacite.org/meta/kernel-4/metadata.xsd";
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+impl DataCiteAttributes {
+    /// Parse a DataCite Kernel 4.x `<resource>` document.
+    pub fn from_xml(xml: &str) -> std::result::Result<Self, DataCiteError> {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document =
+            Document::parse_with_options(xml, options).context(XmlSnafu { stage: "parse-xml" })?;
+        let resource = document.root_element();
+        ensure!(
+            resource.tag_name().name() == "resource",
+            XmlRecordSnafu {
+                stage: "find-resource",
+                message: "root element is not <resource>",
+            }
+        );
+        Ok(parse_resource(resource))
+    }
+
+    /// Serialize as a DataCite Kernel 4 `<resource>` document.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<resource xmlns=\"{}\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:schemaLocation=\"{}\">",
+            KERNEL_NAMESPACE, SCHEMA_LOCATION
+        );
+        let mut writer = XmlWriter { xml, depth: 1 };
+        write_resource(&mut writer, self);
+        let mut xml = writer.xml;
+        xml.push_str("</resource>\n");
+        xml
+    }
+}
+
+/// Return every child element with the local name.
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Return the first child element with the local name.
+fn child<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'static str) -> Option<Node<'a, 'input>> {
+    children(node, name).next()
+}
+
+/// Return the elements of a wrapper, e.g. every `<title>` in `<titles>`.
+fn list<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    wrapper: &'static str,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    child(node, wrapper)
+        .into_iter()
+        .flat_map(move |wrapper| children(wrapper, name))
+}
+
+/// Return the element text with whitespace collapsed.
+fn text(node: Node) -> String {
+    let text: String = node
+        .descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Return the text of the first child element with the local name.
+fn child_text(node: Node, name: &'static str) -> Option<String> {
+    child(node, name)
+        .map(text)
+        .filter(|value| !value.is_empty())
+}
+
+/// Return an attribute value.
+fn attr(node: Node, name: &str) -> Option<String> {
+    node.attribute(name).map(str::to_string)
+}
+
+/// Return the `xml:lang` attribute.
+fn lang(node: Node) -> Option<String> {
+    node.attribute((XML_NAMESPACE, "lang")).map(str::to_string)
+}
+
+/// Read every supported element of a `<resource>`.
+fn parse_resource(resource: Node) -> DataCiteAttributes {
+    let doi = children(resource, "identifier")
+        .find(|node| node.attribute("identifierType") == Some("DOI"))
+        .map(text)
+        .unwrap_or_default();
+    let (prefix, suffix) = match doi.split_once('/') {
+        Some((prefix, suffix)) => (Some(prefix.to_string()), Some(suffix.to_string())),
+        None => (None, None),
+    };
+    let resource_type = child(resource, "resourceType");
+
+    DataCiteAttributes {
+        doi,
+        prefix,
+        suffix,
+        alternate_identifiers: list(resource, "alternateIdentifiers", "alternateIdentifier")
+            .map(|node| DataCiteAlternateIdentifier {
+                alternate_identifier: text(node),
+                alternate_identifier_type: attr(node, "alternateIdentifierType"),
+            })
+            .collect(),
+        creators: list(resource, "creators", "creator")
+            .map(|node| parse_creator(node, "creatorName"))
+            .collect(),
+        titles: list(resource, "titles", "title")
+            .map(|node| DataCiteTitle {
+                title: text(node),
+                title_type: attr(node, "titleType"),
+                lang: lang(node),
+            })
+            .collect(),
+        publisher: child(resource, "publisher").map(|node| DataCitePublisher {
+            name: text(node),
+            publisher_identifier: attr(node, "publisherIdentifier"),
+            publisher_identifier_scheme: attr(node, "publisherIdentifierScheme"),
+        }),
+        publication_year: child_text(resource, "publicationYear")
+            .and_then(|year| year.parse().ok()),
+        subjects: list(resource, "subjects", "subject")
+            .map(|node| DataCiteSubject {
+                subject: text(node),
+                subject_scheme: attr(node, "subjectScheme"),
+                scheme_uri: attr(node, "schemeURI"),
+                value_uri: attr(node, "valueURI"),
+                classification_code: attr(node, "classificationCode"),
+            })
+            .collect(),
+        contributors: list(resource, "contributors", "contributor")
+            .map(|node| parse_creator(node, "contributorName"))
+            .collect(),
+        dates: list(resource, "dates", "date")
+            .map(|node| DataCiteDate {
+                date: text(node),
+                date_type: attr(node, "dateType"),
+                date_information: attr(node, "dateInformation"),
+            })
+            .collect(),
+        language: child_text(resource, "language"),
+        types: DataCiteTypes {
+            resource_type_general: resource_type.and_then(|node| attr(node, "resourceTypeGeneral")),
+            resource_type: resource_type.map(text).filter(|value| !value.is_empty()),
+            ..Default::default()
+        },
+        related_identifiers: list(resource, "relatedIdentifiers", "relatedIdentifier")
+            .map(|node| DataCiteRelatedIdentifier {
+                related_identifier: text(node),
+                related_identifier_type: attr(node, "relatedIdentifierType"),
+                relation_type: attr(node, "relationType"),
+                resource_type_general: attr(node, "resourceTypeGeneral"),
+            })
+            .collect(),
+        sizes: list(resource, "sizes", "size").map(text).collect(),
+        formats: list(resource, "formats", "format").map(text).collect(),
+        version: child_text(resource, "version"),
+        rights_list: list(resource, "rightsList", "rights")
+            .map(|node| DataCiteRights {
+                rights: Some(text(node)).filter(|value| !value.is_empty()),
+                rights_uri: attr(node, "rightsURI"),
+                rights_identifier: attr(node, "rightsIdentifier"),
+                rights_identifier_scheme: attr(node, "rightsIdentifierScheme"),
+                lang: lang(node),
+            })
+            .collect(),
+        descriptions: list(resource, "descriptions", "description")
+            .map(|node| DataCiteDescription {
+                description: text(node),
+                description_type: attr(node, "descriptionType"),
+                lang: lang(node),
+            })
+            .collect(),
+        geo_locations: list(resource, "geoLocations", "geoLocation")
+            .map(parse_geo_location)
+            .collect(),
+        funding_references: list(resource, "fundingReferences", "fundingReference")
+            .map(|node| {
+                let funder = child(node, "funderIdentifier");
+                let award = child(node, "awardNumber");
+                DataCiteFundingReference {
+                    funder_name: child_text(node, "funderName").unwrap_or_default(),
+                    funder_identifier: funder.map(text),
+                    funder_identifier_type: funder
+                        .and_then(|node| attr(node, "funderIdentifierType")),
+                    award_number: award.map(text),
+                    award_uri: award.and_then(|node| attr(node, "awardURI")),
+                    award_title: child_text(node, "awardTitle"),
+                }
+            })
+            .collect(),
+        schema_version: resource.tag_name().namespace().map(str::to_string),
+        ..Default::default()
+    }
+}
+
+/// Parse a `<creator>` or `<contributor>`.
+fn parse_creator(node: Node, name_element: &'static str) -> DataCiteCreator {
+    let name = child(node, name_element);
+    DataCiteCreator {
+        name: name.map(text).unwrap_or_default(),
+        name_type: name.and_then(|name| attr(name, "nameType")),
+        given_name: child_text(node, "givenName"),
+        family_name: child_text(node, "familyName"),
+        contributor_type: attr(node, "contributorType"),
+        affiliation: children(node, "affiliation")
+            .map(|affiliation| {
+                let extra = [
+                    "affiliationIdentifier",
+                    "affiliationIdentifierScheme",
+                    "schemeURI",
+                ]
+                .into_iter()
+                .filter_map(|key| {
+                    let value = affiliation.attribute(key)?;
+                    let key = if key == "schemeURI" { "schemeUri" } else { key };
+                    Some((key.to_string(), Value::from(value)))
+                })
+                .collect();
+                Affiliation {
+                    name: text(affiliation),
+                    extra,
+                }
+            })
+            .collect(),
+        name_identifiers: children(node, "nameIdentifier")
+            .map(|identifier| DataCiteNameIdentifier {
+                name_identifier: text(identifier),
+                name_identifier_scheme: attr(identifier, "nameIdentifierScheme"),
+                scheme_uri: attr(identifier, "schemeURI"),
+            })
+            .collect(),
+    }
+}
+
+/// Parse a `<geoLocation>`.
+fn parse_geo_location(node: Node) -> DataCiteGeoLocation {
+    let number = |node: Node, name: &'static str| -> Option<f64> {
+        child_text(node, name).and_then(|value| value.parse().ok())
+    };
+    let point = |node: Node| DataCiteGeoPoint {
+        point_longitude: number(node, "pointLongitude"),
+        point_latitude: number(node, "pointLatitude"),
+    };
+    let polygons: Vec<Value> = children(node, "geoLocationPolygon")
+        .map(|polygon| {
+            let points: Vec<Value> = polygon
+                .children()
+                .filter(|node| node.is_element())
+                .map(|node| {
+                    let point = point(node);
+                    json!({
+                        node.tag_name().name(): {
+                            "pointLongitude": point.point_longitude,
+                            "pointLatitude": point.point_latitude,
+                        }
+                    })
+                })
+                .collect();
+            Value::from(points)
+        })
+        .collect();
+
+    DataCiteGeoLocation {
+        geo_location_place: child_text(node, "geoLocationPlace"),
+        geo_location_point: child(node, "geoLocationPoint").map(point),
+        geo_location_box: child(node, "geoLocationBox").map(|node| DataCiteGeoBox {
+            west_bound_longitude: number(node, "westBoundLongitude"),
+            east_bound_longitude: number(node, "eastBoundLongitude"),
+            south_bound_latitude: number(node, "southBoundLatitude"),
+            north_bound_latitude: number(node, "northBoundLatitude"),
+        }),
+        geo_location_polygon: match polygons.len() {
+            0 => None,
+            1 => polygons.into_iter().next(),
+            _ => Some(Value::from(polygons)),
+        },
+    }
+}
+
+/// Minimal indenting XML writer.
+struct XmlWriter {
+    xml: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    /// Write an element with attributes and text content.
+    fn element(&mut self, name: &str, attributes: &[(&str, Option<&str>)], text: &str) {
+        self.indent();
+        self.open_tag(name, attributes);
+        self.xml.push_str(&escape(text));
+        let _ = writeln!(self.xml, "</{}>", name);
+    }
+
+    /// Write an element when the text is present.
+    fn optional(&mut self, name: &str, text: Option<&str>) {
+        if let Some(text) = text {
+            self.element(name, &[], text);
+        }
+    }
+
+    /// Open a container element.
+    fn start(&mut self, name: &str, attributes: &[(&str, Option<&str>)]) {
+        self.indent();
+        self.open_tag(name, attributes);
+        self.xml.push('\n');
+        self.depth += 1;
+    }
+
+    /// Close a container element.
+    fn end(&mut self, name: &str) {
+        self.depth -= 1;
+        self.indent();
+        let _ = writeln!(self.xml, "</{}>", name);
+    }
+
+    fn indent(&mut self) {
+        self.xml.push_str(&"  ".repeat(self.depth));
+    }
+
+    fn open_tag(&mut self, name: &str, attributes: &[(&str, Option<&str>)]) {
+        let _ = write!(self.xml, "<{}", name);
+        for (key, value) in attributes {
+            if let Some(value) = value {
+                let _ = write!(self.xml, " {}=\"{}\"", key, escape(value));
+            }
+        }
+        self.xml.push('>');
+    }
+}
+
+/// Escape text and attribute values.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write every supported element of a `<resource>`.
+fn write_resource(writer: &mut XmlWriter, attributes: &DataCiteAttributes) {
+    writer.element(
+        "identifier",
+        &[("identifierType", Some("DOI"))],
+        &attributes.doi,
+    );
+
+    writer.start("creators", &[]);
+    for creator in &attributes.creators {
+        writer.start("creator", &[]);
+        write_person(writer, creator, "creatorName");
+        writer.end("creator");
+    }
+    writer.end("creators");
+
+    writer.start("titles", &[]);
+    for title in &attributes.titles {
+        writer.element(
+            "title",
+            &[
+                ("xml:lang", title.lang.as_deref()),
+                ("titleType", title.title_type.as_deref()),
+            ],
+            &title.title,
+        );
+    }
+    writer.end("titles");
+
+    if let Some(publisher) = &attributes.publisher {
+        writer.element(
+            "publisher",
+            &[
+                (
+                    "publisherIdentifier",
+                    publisher.publisher_identifier.as_deref(),
+                ),
+                (
+                    "publisherIdentifierScheme",
+                    publisher.publisher_identifier_scheme.as_deref(),
+                ),
+            ],
+            &publisher.name,
+        );
+    }
+    if let Some(year) = attributes.publication_year {
+        writer.element("publicationYear", &[], &year.to_string());
+    }
+    writer.element(
+        "resourceType",
+        &[(
+            "resourceTypeGeneral",
+            Some(
+                attributes
+                    .types
+                    .resource_type_general
+                    .as_deref()
+                    .unwrap_or("Other"),
+            ),
+        )],
+        attributes
+            .types
+            .resource_type
+            .as_deref()
+            .unwrap_or_default(),
+    );
+
+    if !attributes.subjects.is_empty() {
+        writer.start("subjects", &[]);
+        for subject in &attributes.subjects {
+            writer.element(
+                "subject",
+                &[
+                    ("subjectScheme", subject.subject_scheme.as_deref()),
+                    ("schemeURI", subject.scheme_uri.as_deref()),
+                    ("valueURI", subject.value_uri.as_deref()),
+                    ("classificationCode", subject.classification_code.as_deref()),
+                ],
+                &subject.subject,
+            );
+        }
+        writer.end("subjects");
+    }
+
+    if !attributes.contributors.is_empty() {
+        writer.start("contributors", &[]);
+        for contributor in &attributes.contributors {
+            writer.start(
+                "contributor",
+                &[("contributorType", contributor.contributor_type.as_deref())],
+            );
+            write_person(writer, contributor, "contributorName");
+            writer.end("contributor");
+        }
+        writer.end("contributors");
+    }
+
+    if !attributes.dates.is_empty() {
+        writer.start("dates", &[]);
+        for date in &attributes.dates {
+            writer.element(
+                "date",
+                &[
+                    ("dateType", date.date_type.as_deref()),
+                    ("dateInformation", date.date_information.as_deref()),
+                ],
+                &date.date,
+            );
+        }
+        writer.end("dates");
+    }
+    writer.optional("language", attributes.language.as_deref());
+
+    if !attributes.alternate_identifiers.is_empty() {
+        writer.start("alternateIdentifiers", &[]);
+        for identifier in &attributes.alternate_identifiers {
+            writer.element(
+                "alternateIdentifier",
+                &[(
+                    "alternateIdentifierType",
+                    identifier.alternate_identifier_type.as_deref(),
+                )],
+                &identifier.alternate_identifier,
+            );
+        }
+        writer.end("alternateIdentifiers");
+    }
+
+    if !attributes.related_identifiers.is_empty() {
+        writer.start("relatedIdentifiers", &[]);
+        for identifier in &attributes.related_identifiers {
+            writer.element(
+                "relatedIdentifier",
+                &[
+                    (
+                        "relatedIdentifierType",
+                        identifier.related_identifier_type.as_deref(),
+                    ),
+                    ("relationType", identifier.relation_type.as_deref()),
+                    (
+                        "resourceTypeGeneral",
+                        identifier.resource_type_general.as_deref(),
+                    ),
+                ],
+                &identifier.related_identifier,
+            );
+        }
+        writer.end("relatedIdentifiers");
+    }
+
+    for (wrapper, name, values) in [
+        ("sizes", "size", &attributes.sizes),
+        ("formats", "format", &attributes.formats),
+    ] {
+        if !values.is_empty() {
+            writer.start(wrapper, &[]);
+            for value in values {
+                writer.element(name, &[], value);
+            }
+            writer.end(wrapper);
+        }
+    }
+    writer.optional("version", attributes.version.as_deref());
+
+    if !attributes.rights_list.is_empty() {
+        writer.start("rightsList", &[]);
+        for rights in &attributes.rights_list {
+            writer.element(
+                "rights",
+                &[
+                    ("xml:lang", rights.lang.as_deref()),
+                    ("rightsURI", rights.rights_uri.as_deref()),
+                    ("rightsIdentifier", rights.rights_identifier.as_deref()),
+                    (
+                        "rightsIdentifierScheme",
+                        rights.rights_identifier_scheme.as_deref(),
+                    ),
+                ],
+                rights.rights.as_deref().unwrap_or_default(),
+            );
+        }
+        writer.end("rightsList");
+    }
+
+    if !attributes.descriptions.is_empty() {
+        writer.start("descriptions", &[]);
+        for description in &attributes.descriptions {
+            writer.element(
+                "description",
+                &[
+                    ("xml:lang", description.lang.as_deref()),
+                    ("descriptionType", description.description_type.as_deref()),
+                ],
+                &description.description,
+            );
+        }
+        writer.end("descriptions");
+    }
+
+    if !attributes.geo_locations.is_empty() {
+        writer.start("geoLocations", &[]);
+        for location in &attributes.geo_locations {
+            write_geo_location(writer, location);
+        }
+        writer.end("geoLocations");
+    }
+
+    if !attributes.funding_references.is_empty() {
+        writer.start("fundingReferences", &[]);
+        for funding in &attributes.funding_references {
+            writer.start("fundingReference", &[]);
+            writer.element("funderName", &[], &funding.funder_name);
+            if let Some(identifier) = &funding.funder_identifier {
+                writer.element(
+                    "funderIdentifier",
+                    &[(
+                        "funderIdentifierType",
+                        funding.funder_identifier_type.as_deref(),
+                    )],
+                    identifier,
+                );
+            }
+            if let Some(award) = &funding.award_number {
+                writer.element(
+                    "awardNumber",
+                    &[("awardURI", funding.award_uri.as_deref())],
+                    award,
+                );
+            }
+            writer.optional("awardTitle", funding.award_title.as_deref());
+            writer.end("fundingReference");
+        }
+        writer.end("fundingReferences");
+    }
+}
+
+/// Write the name parts of a creator or contributor.
+fn write_person(writer: &mut XmlWriter, person: &DataCiteCreator, name_element: &str) {
+    writer.element(
+        name_element,
+        &[("nameType", person.name_type.as_deref())],
+        &person.name,
+    );
+    writer.optional("givenName", person.given_name.as_deref());
+    writer.optional("familyName", person.family_name.as_deref());
+    for identifier in &person.name_identifiers {
+        writer.element(
+            "nameIdentifier",
+            &[
+                (
+                    "nameIdentifierScheme",
+                    identifier.name_identifier_scheme.as_deref(),
+                ),
+                ("schemeURI", identifier.scheme_uri.as_deref()),
+            ],
+            &identifier.name_identifier,
+        );
+    }
+    for affiliation in &person.affiliation {
+        let extra = |key: &str| affiliation.extra.get(key).and_then(Value::as_str);
+        writer.element(
+            "affiliation",
+            &[
+                ("affiliationIdentifier", extra("affiliationIdentifier")),
+                (
+                    "affiliationIdentifierScheme",
+                    extra("affiliationIdentifierScheme"),
+                ),
+                ("schemeURI", extra("schemeUri")),
+            ],
+            &affiliation.name,
+        );
+    }
+}
+
+/// Write a `<geoLocation>`.
+fn write_geo_location(writer: &mut XmlWriter, location: &DataCiteGeoLocation) {
+    let write_point = |writer: &mut XmlWriter, name: &str, point: &DataCiteGeoPoint| {
+        writer.start(name, &[]);
+        if let Some(longitude) = point.point_longitude {
+            writer.element("pointLongitude", &[], &longitude.to_string());
+        }
+        if let Some(latitude) = point.point_latitude {
+            writer.element("pointLatitude", &[], &latitude.to_string());
+        }
+        writer.end(name);
+    };
+
+    writer.start("geoLocation", &[]);
+    writer.optional("geoLocationPlace", location.geo_location_place.as_deref());
+    if let Some(point) = &location.geo_location_point {
+        write_point(writer, "geoLocationPoint", point);
+    }
+    if let Some(bounds) = &location.geo_location_box {
+        writer.start("geoLocationBox", &[]);
+        for (name, value) in [
+            ("westBoundLongitude", bounds.west_bound_longitude),
+            ("eastBoundLongitude", bounds.east_bound_longitude),
+            ("southBoundLatitude", bounds.south_bound_latitude),
+            ("northBoundLatitude", bounds.north_bound_latitude),
+        ] {
+            if let Some(value) = value {
+                writer.element(name, &[], &value.to_string());
+            }
+        }
+        writer.end("geoLocationBox");
+    }
+    if let Some(polygon) = &location.geo_location_polygon {
+        // A single polygon is a list of points; several polygons are a list of lists.
+        let polygons: Vec<&Value> = match polygon.as_array() {
+            Some(items) if items.iter().all(Value::is_array) => items.iter().collect(),
+            _ => vec![polygon],
+        };
+        for polygon in polygons {
+            writer.start("geoLocationPolygon", &[]);
+            for item in polygon.as_array().into_iter().flatten() {
+                for (name, point) in item.as_object().into_iter().flatten() {
+                    if let Ok(point) = serde_json::from_value::<DataCiteGeoPoint>(point.clone()) {
+                        write_point(writer, name, &point);
+                    }
+                }
+            }
+            writer.end("geoLocationPolygon");
+        }
+    }
+    writer.end("geoLocation");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::{CslItemType, DoiMetadata};
+
+    const RESOURCE_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/datacite/resource.xml"
+    ));
+
+    #[test]
+    /// Parses every supported Kernel 4 element.
+    fn datacite_xml_parses_fixture() {
+        let attributes = DataCiteAttributes::from_xml(RESOURCE_FIXTURE).unwrap();
+        assert_eq!(attributes.doi, "10.5281/ZENODO.1234567");
+        assert_eq!(attributes.prefix.as_deref(), Some("10.5281"));
+
+        let creator = &attributes.creators[0];
+        assert_eq!(creator.family_name.as_deref(), Some("Fenner"));
+        assert_eq!(
+            creator.orcid(),
+            Some("https://orcid.org/0000-0003-1419-2405")
+        );
+        assert_eq!(creator.affiliation[0].name, "DataCite");
+        assert_eq!(
+            creator.affiliation[0].extra["affiliationIdentifierScheme"],
+            "ROR"
+        );
+        assert_eq!(
+            attributes.creators[1].name_type.as_deref(),
+            Some("Organizational")
+        );
+
+        assert_eq!(attributes.titles[1].title_type.as_deref(), Some("Subtitle"));
+        assert_eq!(attributes.titles[0].lang.as_deref(), Some("en"));
+        assert_eq!(
+            attributes.subjects[1].classification_code.as_deref(),
+            Some("1.5")
+        );
+        assert_eq!(
+            attributes.contributors[0].affiliation[0].name,
+            "Woods Hole & Partners"
+        );
+        assert_eq!(
+            attributes.dates[1].date_information.as_deref(),
+            Some("glider season")
+        );
+        assert_eq!(
+            attributes.related_identifiers[1]
+                .resource_type_general
+                .as_deref(),
+            Some("Preprint")
+        );
+        assert_eq!(
+            attributes.rights_list[0].rights_identifier.as_deref(),
+            Some("cc-by-4.0")
+        );
+        let location = &attributes.geo_locations[0];
+        assert_eq!(
+            location
+                .geo_location_box
+                .as_ref()
+                .unwrap()
+                .north_bound_latitude,
+            Some(60.0)
+        );
+        assert_eq!(
+            location.geo_location_polygon.as_ref().unwrap()[0]["polygonPoint"]["pointLatitude"],
+            30.0
+        );
+        assert_eq!(
+            attributes.funding_references[0].award_uri.as_deref(),
+            Some("https://www.nsf.gov/awardsearch/showAward?AWD_ID=654321")
+        );
+    }
+
+    #[test]
+    /// Serializes to XML that parses back to the same attributes.
+    fn datacite_xml_round_trip() {
+        let attributes = DataCiteAttributes::from_xml(RESOURCE_FIXTURE).unwrap();
+        let xml = attributes.to_xml();
+        assert!(xml.contains("<affiliation>Woods Hole &amp; Partners</affiliation>"));
+        assert!(xml.contains("xml:lang=\"en\""));
+        assert_eq!(DataCiteAttributes::from_xml(&xml).unwrap(), attributes);
+    }
+
+    #[test]
+    /// Converts parsed XML into CSL-JSON metadata.
+    fn datacite_xml_to_metadata() {
+        let attributes = DataCiteAttributes::from_xml(RESOURCE_FIXTURE).unwrap();
+        let metadata = DoiMetadata::from(attributes);
+        assert_eq!(metadata.item_type, CslItemType::Dataset);
+        assert_eq!(
+            metadata.author[1].literal.as_deref(),
+            Some("Ocean Observatories Initiative")
+        );
+        assert_eq!(metadata.editor[0].given.as_deref(), Some("Ana"));
+        assert_eq!(metadata.publisher.as_deref(), Some("Zenodo"));
+        assert_eq!(metadata.genre.as_deref(), Some("Glider profiles"));
+    }
+
+    #[test]
+    /// Rejects documents that are not a DataCite resource.
+    fn datacite_xml_rejects_other_documents() {
+        assert!(matches!(
+            DataCiteAttributes::from_xml("<doi_records/>"),
+            Err(DataCiteError::XmlRecord { .. })
+        ));
+        assert!(matches!(
+            DataCiteAttributes::from_xml("<resource>"),
+            Err(DataCiteError::Xml { .. })
+        ));
+    }
+}
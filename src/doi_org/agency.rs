@@ -0,0 +1,91 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A DOI registration agency, as reported by `https://doi.org/ra/{doi}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RegistrationAgency {
+    Crossref,
+    DataCite,
+    Medra,
+    Jalc,
+    Kisti,
+    Airiti,
+    Cnki,
+    Eidr,
+    Op,
+    /// Any agency not listed above, with its name as reported.
+    Other(String),
+}
+
+impl RegistrationAgency {
+    /// Return the agency name as used by doi.org.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RegistrationAgency::Crossref => "Crossref",
+            RegistrationAgency::DataCite => "DataCite",
+            RegistrationAgency::Medra => "mEDRA",
+            RegistrationAgency::Jalc => "JaLC",
+            RegistrationAgency::Kisti => "KISTI",
+            RegistrationAgency::Airiti => "Airiti",
+            RegistrationAgency::Cnki => "CNKI",
+            RegistrationAgency::Eidr => "EIDR",
+            RegistrationAgency::Op => "OP",
+            RegistrationAgency::Other(name) => name,
+        }
+    }
+}
+
+impl FromStr for RegistrationAgency {
+    type Err = std::convert::Infallible;
+
+    /// Parse an agency name case-insensitively.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        Ok(match value.to_ascii_lowercase().as_str() {
+            "crossref" => RegistrationAgency::Crossref,
+            "datacite" => RegistrationAgency::DataCite,
+            "medra" => RegistrationAgency::Medra,
+            "jalc" => RegistrationAgency::Jalc,
+            "kisti" => RegistrationAgency::Kisti,
+            "airiti" => RegistrationAgency::Airiti,
+            "cnki" => RegistrationAgency::Cnki,
+            "eidr" => RegistrationAgency::Eidr,
+            "op" => RegistrationAgency::Op,
+            _ => RegistrationAgency::Other(value.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for RegistrationAgency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for RegistrationAgency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RegistrationAgency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(value.parse().unwrap_or(RegistrationAgency::Other(value)))
+    }
+}
+
+/// One entry of the `/ra/{doi}` response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgencyEntry {
+    #[serde(rename = "DOI")]
+    pub doi: String,
+    /// The agency, absent when the DOI is unknown.
+    #[serde(rename = "RA", skip_serializing_if = "Option::is_none")]
+    pub agency: Option<RegistrationAgency>,
+    /// Status message such as `DOI does not exist`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
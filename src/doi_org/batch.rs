@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::future::ready;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use futures::stream::{self, BoxStream, StreamExt};
+
+use crate::Doi;
+use crate::csl::DoiMetadata;
+use crate::doi_org::client::DoiOrgClient;
+use crate::doi_org::error::DoiOrgError;
+
+/// A stream of `(doi, result)` pairs from [`DoiOrgClient::metadata_many`].
+pub type BatchStream<'a> = BoxStream<'a, (Doi, Result<DoiMetadata, DoiOrgError>)>;
+
+/// Progress reported after each DOI of a batch completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// DOIs finished so far, successfully or not.
+    pub completed: usize,
+    /// DOIs that failed so far.
+    pub failed: usize,
+    /// Distinct DOIs in the batch.
+    pub total: usize,
+}
+
+/// A handle that stops a batch from starting further requests.
+///
+/// Requests already in flight still complete and are yielded.
+#[derive(Debug, Clone, Default)]
+pub struct BatchCancel(Arc<AtomicBool>);
+
+impl BatchCancel {
+    /// Build a handle that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop starting new requests.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Return true once [`cancel`](Self::cancel) was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type ProgressFn = Arc<dyn Fn(BatchProgress) + Send + Sync>;
+
+/// Options for [`DoiOrgClient::metadata_many_with`].
+#[derive(Clone)]
+pub struct BatchOptions {
+    concurrency: usize,
+    ordered: bool,
+    progress: Option<ProgressFn>,
+    cancel: BatchCancel,
+}
+
+impl Default for BatchOptions {
+    /// Eight requests in flight, results in input order.
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            ordered: true,
+            progress: None,
+            cancel: BatchCancel::new(),
+        }
+    }
+}
+
+impl BatchOptions {
+    /// Build default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how many DOIs are fetched at once (at least one).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Yield results as they complete instead of in input order.
+    pub fn unordered(mut self) -> Self {
+        self.ordered = false;
+        self
+    }
+
+    /// Call a function after each DOI completes.
+    pub fn on_progress(mut self, progress: impl Fn(BatchProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Use a cancellation handle.
+    pub fn cancel(mut self, cancel: BatchCancel) -> Self {
+        self.cancel = cancel;
+        self
+    }
+}
+
+impl fmt::Debug for BatchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchOptions")
+            .field("concurrency", &self.concurrency)
+            .field("ordered", &self.ordered)
+            .field("progress", &self.progress.is_some())
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+impl DoiOrgClient {
+    /// Fetch metadata for many DOIs with default [`BatchOptions`].
+    pub fn metadata_many(&self, dois: impl IntoIterator<Item = Doi>) -> BatchStream<'_> {
+        self.metadata_many_with(dois, BatchOptions::default())
+    }
+
+    /// Fetch metadata for many DOIs with bounded concurrency.
+    ///
+    /// DOIs equal up to case are fetched and yielded once. The client's rate
+    /// limit and its retries of `429`/`503` answers still apply to every request.
+    pub fn metadata_many_with(
+        &self,
+        dois: impl IntoIterator<Item = Doi>,
+        options: BatchOptions,
+    ) -> BatchStream<'_> {
+        let mut seen = HashSet::new();
+        let unique: Vec<Doi> = dois
+            .into_iter()
+            .filter(|doi| seen.insert(doi.canonical()))
+            .collect();
+        let total = unique.len();
+
+        let cancel = options.cancel.clone();
+        let requests = stream::iter(unique)
+            .take_while(move |_| ready(!cancel.is_cancelled()))
+            .map(move |doi| async move {
+                let result = self.metadata(&doi).await;
+                (doi, result)
+            });
+        let results: BatchStream<'_> = if options.ordered {
+            requests.buffered(options.concurrency).boxed()
+        } else {
+            requests.buffer_unordered(options.concurrency).boxed()
+        };
+
+        let Some(progress) = options.progress else {
+            return results;
+        };
+        let completed = AtomicUsize::new(0);
+        let failed = AtomicUsize::new(0);
+        results
+            .inspect(move |(_, result)| {
+                if result.is_err() {
+                    failed.fetch_add(1, Ordering::Relaxed);
+                }
+                progress(BatchProgress {
+                    completed: completed.fetch_add(1, Ordering::Relaxed) + 1,
+                    failed: failed.load(Ordering::Relaxed),
+                    total,
+                });
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doi_org::config::DoiOrgConfig;
+    use std::sync::Mutex;
+    use wiremock::matchers::{method, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    /// Dedups DOIs, keeps input order and reports progress and failures.
+    async fn metadata_many_dedups_in_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/10.5555/(a|b)$"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"type":"article"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/10.5555/missing$"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            rate_limit_per_sec: Some(100),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let dois = ["10.5555/a", "10.5555/missing", "10.5555/A", "10.5555/b"]
+            .map(|doi| Doi::parse(doi).unwrap());
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let options = BatchOptions::new()
+            .concurrency(2)
+            .on_progress(move |progress| sink.lock().unwrap().push(progress));
+
+        let results: Vec<_> = client.metadata_many_with(dois, options).collect().await;
+        let order: Vec<_> = results.iter().map(|(doi, _)| doi.as_str()).collect();
+        assert_eq!(order, ["10.5555/a", "10.5555/missing", "10.5555/b"]);
+        assert!(results[1].1.is_err());
+
+        let last = *reports.lock().unwrap().last().unwrap();
+        assert_eq!(
+            last,
+            BatchProgress {
+                completed: 3,
+                failed: 1,
+                total: 3
+            }
+        );
+    }
+
+    #[tokio::test]
+    /// Stops starting requests once cancelled.
+    async fn metadata_many_cancel() {
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: "http://127.0.0.1:9".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let cancel = BatchCancel::new();
+        cancel.cancel();
+        let options = BatchOptions::new().unordered().cancel(cancel);
+        let dois = [Doi::parse("10.5555/a").unwrap()];
+        assert_eq!(client.metadata_many_with(dois, options).count().await, 0);
+    }
+
+    #[tokio::test]
+    /// Retries `429` and `503` answers after the `Retry-After` wait.
+    async fn metadata_many_retries_throttled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/10.5555/busy$"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/10.5555/down$"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .insert_header("retry-after", "Wed, 21 Oct 2015 07:28:00 GMT"),
+            )
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/10.5555/(busy|down)$"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"type":"article"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let config = DoiOrgConfig {
+            base_url: server.uri(),
+            ..Default::default()
+        };
+        assert_eq!(config.rate_limit_value(), 5);
+        assert_eq!(
+            config.clone().polite("me@example.org").rate_limit_value(),
+            10
+        );
+        let client = DoiOrgClient::new(config).unwrap();
+
+        let dois = ["10.5555/busy", "10.5555/down"].map(|doi| Doi::parse(doi).unwrap());
+        let results: Vec<_> = client.metadata_many(dois).collect().await;
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            max_retries: 0,
+            ..Default::default()
+        })
+        .unwrap();
+        Mock::given(method("GET"))
+            .and(path_regex("^/10.5555/limited$"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let error = client
+            .metadata(&Doi::parse("10.5555/limited").unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(429));
+    }
+}
@@ -0,0 +1,142 @@
+//! A metadata source that reads doi.org BibTeX.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::Doi;
+use crate::bibtex::import::parse_bibtex;
+use crate::doi_org::client::DoiOrgClient;
+use crate::source::error::*;
+use crate::source::{MetadataSource, Record};
+use snafu::ResultExt;
+
+/// Fetches `application/x-bibtex` from doi.org and converts it with [`parse_bibtex`].
+///
+/// Some registration agencies serve BibTeX but no CSL-JSON, so this source
+/// is a last resort at the end of a [`FallbackResolver`](crate::FallbackResolver) chain.
+#[derive(Clone)]
+pub struct DoiOrgBibtexSource {
+    client: Arc<DoiOrgClient>,
+}
+
+impl DoiOrgBibtexSource {
+    /// The media type requested from doi.org.
+    pub const ACCEPT: &'static str = "application/x-bibtex";
+
+    /// Wrap a doi.org client.
+    pub fn new(client: DoiOrgClient) -> Self {
+        Self::from_arc(Arc::new(client))
+    }
+
+    /// Wrap a shared doi.org client.
+    pub fn from_arc(client: Arc<DoiOrgClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl MetadataSource for DoiOrgBibtexSource {
+    fn name(&self) -> &str {
+        "doi.org-bibtex"
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        let response = self
+            .client
+            .fetch_raw(doi, Self::ACCEPT, None)
+            .await
+            .context(DoiOrgSnafu { stage: "bibtex" })?;
+        match response.status {
+            404 => {
+                return NotFoundSnafu {
+                    source_name: self.name(),
+                    doi: doi.as_str(),
+                }
+                .fail();
+            }
+            406 => {
+                return NotAcceptableSnafu {
+                    source_name: self.name(),
+                    doi: doi.as_str(),
+                    accept: Self::ACCEPT,
+                }
+                .fail();
+            }
+            200..=299 => {}
+            status => {
+                return OtherSnafu {
+                    stage: "http-status",
+                    source_name: self.name(),
+                    message: format!("HTTP {status}"),
+                }
+                .fail();
+            }
+        }
+
+        let Some(mut metadata) = parse_bibtex(&response.body).items.into_iter().next() else {
+            return OtherSnafu {
+                stage: "parse-bibtex",
+                source_name: self.name(),
+                message: "response has no BibTeX entry",
+            }
+            .fail();
+        };
+        if metadata.doi.is_none() {
+            metadata.doi = Some(doi.as_str().to_string());
+        }
+        Ok(Record::new(metadata, self.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DoiOrgConfig;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    /// Parses BibTeX, and reports 404 and 406 as distinct errors.
+    async fn bibtex_and_status_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/10.1000/xyz123"))
+            .and(header("accept", DoiOrgBibtexSource::ACCEPT))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                " @article{Smith_2020, title={A study of things}, volume={12}, \
+                 journal={Journal of Studies}, author={Smith, John and Jones, Kate}, year={2020}}",
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/no-bibtex"))
+            .respond_with(ResponseTemplate::new(406))
+            .mount(&server)
+            .await;
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            ..Default::default()
+        })
+        .unwrap();
+        let source = DoiOrgBibtexSource::new(client);
+
+        let doi = Doi::parse("10.1000/xyz123").unwrap();
+        let record = source.metadata(&doi).await.unwrap();
+        assert_eq!(record.source, "doi.org-bibtex");
+        assert_eq!(record.metadata.title.as_deref(), Some("A study of things"));
+        assert_eq!(record.metadata.author.len(), 2);
+        assert_eq!(record.metadata.doi.as_deref(), Some("10.1000/xyz123"));
+
+        let missing = Doi::parse("10.5555/missing").unwrap();
+        assert!(source.metadata(&missing).await.unwrap_err().is_not_found());
+        let unsupported = Doi::parse("10.5555/no-bibtex").unwrap();
+        let error = source.metadata(&unsupported).await.unwrap_err();
+        assert!(error.is_not_acceptable());
+    }
+}
@@ -1,17 +1,48 @@
+use crate::doi_org::agency::{AgencyEntry, RegistrationAgency};
+use crate::doi_org::coalesce::{CoalescingStats, InFlight};
 use crate::doi_org::config::DoiOrgConfig;
 use crate::doi_org::error::*;
+use crate::doi_org::handle::HandleResponse;
+use crate::http::{RateLimiter, is_retryable, retry_delay, user_agent_headers};
+use crate::source::error::{DoiOrgSnafu, NotAcceptableSnafu, NotFoundSnafu, SourceError};
+use crate::source::resolver::AgencyLookup;
+use crate::source::{MetadataSource, Record};
 use crate::{Doi, csl::*};
-use reqwest::header::{ACCEPT, USER_AGENT};
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, IF_NONE_MATCH};
 use snafu::ResultExt;
+use std::collections::BTreeMap;
+use tokio::sync::SemaphorePermit;
+
+/// An undecoded doi.org response, as returned by [`DoiOrgClient::fetch_raw`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers with lowercase names.
+    pub headers: BTreeMap<String, String>,
+    /// Response body.
+    pub body: String,
+}
+
+impl RawResponse {
+    /// Return a header value by lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
 
 /// Client for doi.org content negotiation.
 pub struct DoiOrgClient {
     client: reqwest::Client,
     base_url: String,
+    limiter: RateLimiter,
+    max_retries: u32,
+    in_flight: InFlight,
 }
 
 impl DoiOrgClient {
-    const CSL_JSON_ACCEPT: &'static str = "application/vnd.citationstyles.csl+json";
+    pub(crate) const CSL_JSON_ACCEPT: &'static str = "application/vnd.citationstyles.csl+json";
 
     /// Build a doi.org client with configured defaults.
     pub fn new(config: DoiOrgConfig) -> std::result::Result<Self, DoiOrgError> {
@@ -24,26 +55,43 @@ impl DoiOrgClient {
                 stage: "build-client",
             })?;
 
-        Ok(Self { client, base_url })
+        let limiter = RateLimiter::new(Some(config.rate_limit_value()), config.concurrency);
+
+        Ok(Self {
+            client,
+            base_url,
+            limiter,
+            max_retries: config.max_retries,
+            in_flight: InFlight::default(),
+        })
     }
 
     /// Fetch CSL-JSON metadata for a DOI via doi.org content negotiation.
+    ///
+    /// Concurrent calls for the same canonical DOI share one HTTP request.
+    /// When it fails, the call that sent it gets the error itself and the
+    /// calls that waited for it get [`DoiOrgError::Coalesced`] with its status
+    /// and message. If the sending call is dropped, one waiter sends the
+    /// request again.
     pub async fn metadata(&self, doi: &Doi) -> std::result::Result<DoiMetadata, DoiOrgError> {
-        let url = self.build_url(doi);
+        self.in_flight.run(doi, || self.fetch_metadata(doi)).await
+    }
+
+    /// Return how many `metadata` calls were sent or coalesced.
+    pub fn coalescing_stats(&self) -> CoalescingStats {
+        self.in_flight.stats()
+    }
 
-        let response = self
+    /// Fetch CSL-JSON metadata with one HTTP request.
+    async fn fetch_metadata(&self, doi: &Doi) -> std::result::Result<DoiMetadata, DoiOrgError> {
+        let request = self
             .client
-            .get(url)
-            .header(ACCEPT, Self::CSL_JSON_ACCEPT)
-            .send()
-            .await
-            .context(RequestSnafu {
-                stage: "send-request",
-            })?
-            .error_for_status()
-            .context(RequestSnafu {
-                stage: "http-status",
-            })?;
+            .get(self.build_url(doi))
+            .header(ACCEPT, Self::CSL_JSON_ACCEPT);
+        let (response, _permit) = self.send(request).await?;
+        let response = response.error_for_status().context(RequestSnafu {
+            stage: "http-status",
+        })?;
 
         let text = response.text().await.context(RequestSnafu {
             stage: "response-body",
@@ -58,6 +106,123 @@ impl DoiOrgClient {
         )
     }
 
+    /// Fetch a DOI with any `Accept` type without checking the status.
+    ///
+    /// When `etag` is given it is sent as `If-None-Match`, so doi.org may answer `304`.
+    pub async fn fetch_raw(
+        &self,
+        doi: &Doi,
+        accept: &str,
+        etag: Option<&str>,
+    ) -> std::result::Result<RawResponse, DoiOrgError> {
+        let mut request = self.client.get(self.build_url(doi)).header(ACCEPT, accept);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let (response, _permit) = self.send(request).await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                Some((name.as_str().to_string(), value.to_string()))
+            })
+            .collect();
+        let body = response.text().await.context(RequestSnafu {
+            stage: "response-body",
+        })?;
+
+        Ok(RawResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    /// Look up the registration agency of a DOI via `/ra/{doi}`.
+    ///
+    /// Returns `None` when doi.org does not know the DOI.
+    pub async fn registration_agency(
+        &self,
+        doi: &Doi,
+    ) -> std::result::Result<Option<RegistrationAgency>, DoiOrgError> {
+        let url = format!("{}/ra/{}", self.base_url, doi.as_str());
+        let (response, _permit) = self.send(self.client.get(url)).await?;
+
+        let text = response
+            .error_for_status()
+            .context(RequestSnafu {
+                stage: "http-status",
+            })?
+            .text()
+            .await
+            .context(RequestSnafu {
+                stage: "response-body",
+            })?;
+
+        let mut deserializer = serde_json::Deserializer::from_str(&text);
+        let entries: Vec<AgencyEntry> = serde_path_to_error::deserialize(&mut deserializer)
+            .context(SerializePathSnafu {
+                stage: "parse-agency",
+            })?;
+        Ok(entries.into_iter().find_map(|entry| entry.agency))
+    }
+
+    /// Return the landing page URL a DOI resolves to, via the Handle System API.
+    ///
+    /// Returns `None` when the DOI does not exist.
+    pub async fn landing_url(&self, doi: &Doi) -> std::result::Result<Option<String>, DoiOrgError> {
+        let url = format!("{}/api/handles/{}", self.base_url, doi.as_str());
+        let (response, _permit) = self.send(self.client.get(url)).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let text = response
+            .error_for_status()
+            .context(RequestSnafu {
+                stage: "http-status",
+            })?
+            .text()
+            .await
+            .context(RequestSnafu {
+                stage: "response-body",
+            })?;
+
+        let mut deserializer = serde_json::Deserializer::from_str(&text);
+        let handle: HandleResponse =
+            serde_path_to_error::deserialize(&mut deserializer).context(SerializePathSnafu {
+                stage: "parse-handle",
+            })?;
+        Ok(handle.url().map(str::to_string))
+    }
+
+    /// Send a request within the rate limit, retrying `429` and `503` answers
+    /// up to `max_retries` times. The permit must be held until the body is read.
+    async fn send(
+        &self,
+        mut request: reqwest::RequestBuilder,
+    ) -> std::result::Result<(reqwest::Response, Option<SemaphorePermit<'_>>), DoiOrgError> {
+        let mut attempt = 0;
+        loop {
+            let retry = (attempt < self.max_retries)
+                .then(|| request.try_clone())
+                .flatten();
+            let permit = self.limiter.acquire().await;
+            let response = request.send().await.context(RequestSnafu {
+                stage: "send-request",
+            })?;
+            let Some(retry) = retry.filter(|_| is_retryable(response.status())) else {
+                return Ok((response, permit));
+            };
+            drop(permit);
+            tokio::time::sleep(retry_delay(response.headers(), attempt)).await;
+            request = retry;
+            attempt += 1;
+        }
+    }
+
     /// Build the doi.org URL for a DOI.
     fn build_url(&self, doi: &Doi) -> String {
         format!("{}/{}", self.base_url, doi.as_str())
@@ -65,22 +230,105 @@ impl DoiOrgClient {
 
     /// Build default headers for the doi.org client.
     fn default_headers(config: &DoiOrgConfig) -> reqwest::header::HeaderMap {
-        let mut headers = reqwest::header::HeaderMap::new();
-        if let Some(user_agent) = Self::user_agent_header_value(config)
-            && let Ok(value) = reqwest::header::HeaderValue::from_str(&user_agent)
-        {
-            headers.insert(USER_AGENT, value);
-        }
-        headers
+        user_agent_headers(config.user_agent_value(), config.mailto_value())
+    }
+}
+
+#[async_trait]
+impl MetadataSource for DoiOrgClient {
+    fn name(&self) -> &str {
+        "doi.org"
     }
 
-    /// Build the User-Agent header value when configured.
-    fn user_agent_header_value(config: &DoiOrgConfig) -> Option<String> {
-        match (config.user_agent_value(), config.mailto_value()) {
-            (Some(agent), Some(mailto)) => Some(format!("{} mailto:{}", agent, mailto)),
-            (Some(agent), None) => Some(agent.to_string()),
-            (None, Some(mailto)) => Some(format!("mailto:{}", mailto)),
-            (None, None) => None,
+    async fn metadata(&self, doi: &Doi) -> std::result::Result<Record, SourceError> {
+        match DoiOrgClient::metadata(self, doi).await {
+            Ok(metadata) => Ok(Record::new(metadata, MetadataSource::name(self))),
+            Err(error) if error.is_not_found() => NotFoundSnafu {
+                source_name: MetadataSource::name(self),
+                doi: doi.as_str(),
+            }
+            .fail(),
+            Err(error) if error.status() == Some(406) => NotAcceptableSnafu {
+                source_name: MetadataSource::name(self),
+                doi: doi.as_str(),
+                accept: Self::CSL_JSON_ACCEPT,
+            }
+            .fail(),
+            Err(error) => Err(error).context(DoiOrgSnafu { stage: "metadata" }),
         }
     }
 }
+
+#[async_trait]
+impl AgencyLookup for DoiOrgClient {
+    async fn agency(
+        &self,
+        doi: &Doi,
+    ) -> std::result::Result<Option<RegistrationAgency>, SourceError> {
+        self.registration_agency(doi)
+            .await
+            .context(DoiOrgSnafu { stage: "agency" })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    /// Reads the registration agency and landing URL, with `None` for unknown DOIs.
+    async fn agency_and_landing_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ra/10.5281/zenodo.1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"[{"DOI":"10.5281/zenodo.1","RA":"DataCite"}]"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ra/10.5555/missing"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(
+                    r#"[{"DOI":"10.5555/missing","status":"DOI does not exist"}]"#,
+                ),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/handles/10.5281/zenodo.1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"responseCode":1,"handle":"10.5281/zenodo.1","values":[
+                {"index":100,"type":"HS_ADMIN","data":{"format":"admin","value":{}}},
+                {"index":1,"type":"URL","data":{"format":"string","value":"https://zenodo.org/record/1"}}]}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/handles/10.5555/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string(r#"{"responseCode":100}"#))
+            .mount(&server)
+            .await;
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let doi = Doi::parse("10.5281/zenodo.1").unwrap();
+        let missing = Doi::parse("10.5555/missing").unwrap();
+        assert_eq!(
+            client.registration_agency(&doi).await.unwrap(),
+            Some(RegistrationAgency::DataCite)
+        );
+        assert_eq!(client.registration_agency(&missing).await.unwrap(), None);
+        assert_eq!(
+            client.landing_url(&doi).await.unwrap().as_deref(),
+            Some("https://zenodo.org/record/1")
+        );
+        assert_eq!(client.landing_url(&missing).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use tokio::sync::broadcast;
+
+use crate::Doi;
+use crate::csl::DoiMetadata;
+use crate::doi_org::error::DoiOrgError;
+
+/// Counters for single-flight request coalescing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoalescingStats {
+    /// `metadata` calls that issued their own HTTP request.
+    pub requests: u64,
+    /// `metadata` calls that shared another call's request.
+    pub coalesced: u64,
+}
+
+/// A failed shared request as the waiting calls see it.
+#[derive(Debug, Clone)]
+struct Failure {
+    status: Option<u16>,
+    message: String,
+}
+
+type Shared = Result<DoiMetadata, Failure>;
+
+/// Tracks in-flight `metadata` calls by canonical DOI.
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    calls: Mutex<HashMap<String, broadcast::Sender<Shared>>>,
+    requests: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl InFlight {
+    /// Run `fetch` unless a call for the same DOI is already in flight, in
+    /// which case wait for and clone its result. The call that runs `fetch`
+    /// gets its error as is; the waiting calls get [`DoiOrgError::Coalesced`].
+    pub(crate) async fn run<F, Fut>(&self, doi: &Doi, fetch: F) -> Result<DoiMetadata, DoiOrgError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<DoiMetadata, DoiOrgError>>,
+    {
+        let key = doi.canonical();
+        loop {
+            let mut receiver = {
+                let mut calls = self.lock();
+                match calls.get(&key) {
+                    Some(sender) => sender.subscribe(),
+                    None => {
+                        calls.insert(key.clone(), broadcast::channel(1).0);
+                        break;
+                    }
+                }
+            };
+            // A closed channel means the leading call was dropped; the first
+            // waiter to get here takes over and the rest wait for it.
+            if let Ok(shared) = receiver.recv().await {
+                self.coalesced.fetch_add(1, Ordering::Relaxed);
+                return shared.map_err(|failure| DoiOrgError::Coalesced {
+                    doi: doi.as_str().to_string(),
+                    status: failure.status,
+                    message: failure.message,
+                });
+            }
+        }
+
+        let guard = LeaderGuard {
+            flight: self,
+            key: Some(key),
+        };
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let result = fetch().await;
+        // No receivers is the common, uncontended case.
+        if let Some(sender) = guard.finish().filter(|sender| sender.receiver_count() > 0) {
+            let _ = sender.send(match &result {
+                Ok(metadata) => Ok(metadata.clone()),
+                Err(error) => Err(Failure {
+                    status: error.status(),
+                    message: error.to_string(),
+                }),
+            });
+        }
+        result
+    }
+
+    /// Return the current counters.
+    pub(crate) fn stats(&self) -> CoalescingStats {
+        CoalescingStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, broadcast::Sender<Shared>>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Removes the in-flight entry when the leading call finishes or is dropped.
+struct LeaderGuard<'a> {
+    flight: &'a InFlight,
+    key: Option<String>,
+}
+
+impl LeaderGuard<'_> {
+    /// Remove the entry and return its sender.
+    fn finish(mut self) -> Option<broadcast::Sender<Shared>> {
+        let key = self.key.take()?;
+        self.flight.lock().remove(&key)
+    }
+}
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.flight.lock().remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Doi;
+    use crate::doi_org::client::DoiOrgClient;
+    use crate::doi_org::config::DoiOrgConfig;
+    use crate::doi_org::error::DoiOrgError;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    /// Concurrent calls for the same DOI share one request, including its failure.
+    async fn coalesces_concurrent_calls() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/shared"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"type":"article","title":"Shared"}"#)
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/gone"))
+            .respond_with(ResponseTemplate::new(404).set_delay(Duration::from_millis(200)))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let shared = Doi::parse("10.5555/shared").unwrap();
+        let upper = Doi::parse("10.5555/SHARED").unwrap();
+        let results = futures::future::join_all([
+            client.metadata(&shared),
+            client.metadata(&upper),
+            client.metadata(&shared),
+        ])
+        .await;
+        for result in results {
+            assert_eq!(result.unwrap().title.as_deref(), Some("Shared"));
+        }
+
+        // The call that sent the request gets its error, the waiting call a copy.
+        let gone = Doi::parse("10.5555/gone").unwrap();
+        let (first, second) = tokio::join!(client.metadata(&gone), client.metadata(&gone));
+        let (first, second) = (first.unwrap_err(), second.unwrap_err());
+        assert!(matches!(first, DoiOrgError::Request { .. }));
+        assert!(matches!(second, DoiOrgError::Coalesced { .. }));
+        assert!(first.is_not_found() && second.is_not_found());
+        let alone = client.metadata(&gone).await.unwrap_err();
+        assert!(matches!(alone, DoiOrgError::Request { .. }));
+
+        let stats = client.coalescing_stats();
+        assert_eq!((stats.requests, stats.coalesced), (3, 3));
+    }
+
+    #[tokio::test]
+    /// When the call that sent the request is dropped, one waiter sends it again.
+    async fn waiter_takes_over_from_dropped_call() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"type":"article","title":"Slow"}"#)
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+        let client = DoiOrgClient::new(DoiOrgConfig {
+            base_url: server.uri(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let slow = Doi::parse("10.5555/slow").unwrap();
+        let (dropped, first, second) = tokio::join!(
+            tokio::time::timeout(Duration::from_millis(50), client.metadata(&slow)),
+            client.metadata(&slow),
+            client.metadata(&slow),
+        );
+        assert!(dropped.is_err());
+        assert_eq!(first.unwrap().title.as_deref(), Some("Slow"));
+        assert_eq!(second.unwrap().title.as_deref(), Some("Slow"));
+        let stats = client.coalescing_stats();
+        assert_eq!((stats.requests, stats.coalesced), (2, 1));
+    }
+}
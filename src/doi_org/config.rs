@@ -11,6 +11,13 @@ pub struct DoiOrgConfig {
     pub mailto: Option<String>,
     /// Application identifier used in the User-Agent header.
     pub user_agent: Option<String>,
+    /// Maximum requests per second; `None` uses 10/s with a `mailto` and 5/s
+    /// otherwise, as doi.org hands most requests to Crossref; `Some(0)` disables the limit.
+    pub rate_limit_per_sec: Option<u32>,
+    /// Maximum concurrent requests; `None` disables the limit.
+    pub concurrency: Option<usize>,
+    /// Retries after a `429` or `503` answer, waiting as `Retry-After` asks.
+    pub max_retries: u32,
 }
 
 impl Default for DoiOrgConfig {
@@ -21,6 +28,9 @@ impl Default for DoiOrgConfig {
             timeout: Duration::from_secs(30),
             mailto: None,
             user_agent: None,
+            rate_limit_per_sec: None,
+            concurrency: None,
+            max_retries: 3,
         }
     }
 }
@@ -47,6 +57,12 @@ impl DoiOrgConfig {
             .filter(|value| !value.is_empty())
     }
 
+    /// Return the request rate, defaulting to 10/s with a `mailto` and 5/s otherwise.
+    pub fn rate_limit_value(&self) -> u32 {
+        self.rate_limit_per_sec
+            .unwrap_or(if self.mailto_value().is_some() { 10 } else { 5 })
+    }
+
     /// Configure polite access with a contact email.
     pub fn polite(mut self, email: &str) -> Self {
         let trimmed = email.trim();
@@ -15,4 +15,27 @@ pub enum DoiOrgError {
         source: serde_path_to_error::Error<serde_json::Error>,
         stage: &'static str,
     },
+
+    #[snafu(display("Shared request for {doi} failed: {message}"))]
+    Coalesced {
+        doi: String,
+        status: Option<u16>,
+        message: String,
+    },
+}
+
+impl DoiOrgError {
+    /// Return the HTTP status of a failed request, when there was one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            DoiOrgError::Request { source, .. } => source.status().map(|status| status.as_u16()),
+            DoiOrgError::Coalesced { status, .. } => *status,
+            DoiOrgError::SerializePath { .. } => None,
+        }
+    }
+
+    /// Return true when doi.org answered 404 for the DOI.
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
 }
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A response of the Handle System REST API at `/api/handles/{doi}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleResponse {
+    /// `1` on success, `100` when the handle does not exist.
+    pub response_code: i64,
+    /// The handle (DOI) that was looked up.
+    #[serde(default)]
+    pub handle: Option<String>,
+    /// The handle values, including the `URL` the DOI resolves to.
+    #[serde(default)]
+    pub values: Vec<HandleValue>,
+}
+
+impl HandleResponse {
+    /// Return the first `URL` value, i.e. the landing page.
+    pub fn url(&self) -> Option<&str> {
+        self.values
+            .iter()
+            .filter(|value| value.value_type == "URL")
+            .find_map(|value| value.data.value.as_str())
+    }
+}
+
+/// One handle value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandleValue {
+    pub index: i64,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub data: HandleData,
+}
+
+/// The data of a handle value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandleData {
+    pub format: String,
+    pub value: Value,
+}
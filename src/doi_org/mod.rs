@@ -1,3 +1,8 @@
+pub mod agency;
+pub mod batch;
+pub mod bibtex;
 pub mod client;
+pub mod coalesce;
 pub mod config;
 pub mod error;
+pub mod handle;
@@ -0,0 +1,312 @@
+use serde_json::{Map, Value, json};
+
+use crate::csl::{CslItemType, CslName, DoiMetadata};
+use crate::embed::{abstract_text, display_name, iso_date, orcid_url, page_bounds, plain_text};
+
+/// Return the schema.org type used for a CSL item type.
+pub fn schema_type(item_type: &CslItemType) -> &'static str {
+    match item_type {
+        CslItemType::Article
+        | CslItemType::ArticleJournal
+        | CslItemType::PaperConference
+        | CslItemType::Review
+        | CslItemType::ReviewBook => "ScholarlyArticle",
+        CslItemType::ArticleMagazine
+        | CslItemType::Entry
+        | CslItemType::EntryDictionary
+        | CslItemType::EntryEncyclopedia => "Article",
+        CslItemType::ArticleNewspaper => "NewsArticle",
+        CslItemType::Book | CslItemType::Classic => "Book",
+        CslItemType::Chapter => "Chapter",
+        CslItemType::Collection => "Collection",
+        CslItemType::Dataset => "Dataset",
+        CslItemType::Event => "Event",
+        CslItemType::Figure | CslItemType::Graphic => "ImageObject",
+        CslItemType::Legislation | CslItemType::Bill | CslItemType::Regulation => "Legislation",
+        CslItemType::Manuscript => "Manuscript",
+        CslItemType::Map => "Map",
+        CslItemType::MotionPicture | CslItemType::Broadcast => "VideoObject",
+        CslItemType::MusicalScore => "MusicComposition",
+        CslItemType::Song => "MusicRecording",
+        CslItemType::Periodical => "Periodical",
+        CslItemType::Post => "SocialMediaPosting",
+        CslItemType::PostWeblog => "BlogPosting",
+        CslItemType::Report => "Report",
+        CslItemType::Software => "SoftwareSourceCode",
+        CslItemType::Thesis => "Thesis",
+        CslItemType::Webpage => "WebPage",
+        _ => "CreativeWork",
+    }
+}
+
+/// Build a schema.org JSON-LD object for an item.
+///
+/// People become `Person` nodes whose `@id` is their ORCID; institutional
+/// names become `Organization` nodes. Articles are linked to their journal
+/// through `PublicationIssue`/`PublicationVolume`/`Periodical` nodes.
+pub fn to_json_ld(metadata: &DoiMetadata) -> Value {
+    let kind = schema_type(&metadata.item_type);
+    let mut node = Map::new();
+    node.insert("@context".into(), json!("https://schema.org"));
+    node.insert("@type".into(), json!(kind));
+    if let Some(doi) = metadata.doi.as_deref().filter(|doi| !doi.is_empty()) {
+        let url = format!("https://doi.org/{doi}");
+        node.insert("@id".into(), json!(url));
+        node.insert(
+            "identifier".into(),
+            json!({"@type": "PropertyValue", "propertyID": "DOI", "value": doi, "url": url}),
+        );
+    }
+    if let Some(url) = &metadata.url {
+        node.insert("url".into(), json!(url));
+    }
+    if let Some(title) = &metadata.title {
+        let title = plain_text(title);
+        if kind.ends_with("Article") || kind.ends_with("Posting") {
+            node.insert("headline".into(), json!(title));
+        }
+        node.insert("name".into(), json!(title));
+    }
+    if let Some(short) = metadata
+        .title_short
+        .as_ref()
+        .or(metadata.short_title.as_ref())
+    {
+        node.insert("alternateName".into(), json!(plain_text(short)));
+    }
+    // Google's dataset search reads `creator`, everything else `author`.
+    let author_key = if kind == "Dataset" {
+        "creator"
+    } else {
+        "author"
+    };
+    insert_people(&mut node, author_key, &metadata.author);
+    insert_people(&mut node, "editor", &metadata.editor);
+    insert_people(&mut node, "translator", &metadata.translator);
+    if let Some(date) = metadata.issued.as_ref().and_then(iso_date) {
+        node.insert("datePublished".into(), json!(date));
+    }
+    if let Some(text) = &metadata.abstract_text {
+        node.insert("description".into(), json!(abstract_text(text)));
+    }
+    if let Some(keywords) = &metadata.keyword {
+        let keywords: Vec<&str> = keywords
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+            .collect();
+        node.insert("keywords".into(), json!(keywords));
+    }
+    if let Some(language) = &metadata.language {
+        node.insert("inLanguage".into(), json!(language));
+    }
+    if let Some(license) = &metadata.license {
+        node.insert("license".into(), json!(license));
+    }
+    if let Some(publisher) = &metadata.publisher {
+        let mut organization = json!({"@type": "Organization", "name": publisher});
+        if let Some(place) = &metadata.publisher_place {
+            organization["location"] = json!(place);
+        }
+        node.insert("publisher".into(), organization);
+    }
+    if let Some(version) = &metadata.version {
+        node.insert("version".into(), json!(version.to_string()));
+    }
+    if let Some(page) = &metadata.page {
+        let (start, end) = page_bounds(page);
+        node.insert("pagination".into(), json!(page.to_string()));
+        node.insert("pageStart".into(), json!(start));
+        if let Some(end) = end {
+            node.insert("pageEnd".into(), json!(end));
+        }
+    }
+    if kind == "Book" {
+        if !metadata.isbn.is_empty() {
+            node.insert("isbn".into(), json!(metadata.isbn));
+        }
+        if let Some(edition) = &metadata.edition {
+            node.insert("bookEdition".into(), json!(edition.to_string()));
+        }
+        if let Some(pages) = &metadata.number_of_pages {
+            node.insert("numberOfPages".into(), json!(pages.to_string()));
+        }
+    }
+    if let Some(container) = container(metadata, kind) {
+        node.insert("isPartOf".into(), container);
+    }
+    Value::Object(node)
+}
+
+/// Add `Person`/`Organization` nodes under a key, skipping empty lists.
+fn insert_people(node: &mut Map<String, Value>, key: &str, names: &[CslName]) {
+    let people: Vec<Value> = names.iter().filter_map(person).collect();
+    if !people.is_empty() {
+        node.insert(key.into(), Value::Array(people));
+    }
+}
+
+fn person(name: &CslName) -> Option<Value> {
+    let full = display_name(name)?;
+    if name.family.is_none() {
+        return Some(json!({"@type": "Organization", "name": full}));
+    }
+    let mut person = Map::new();
+    person.insert("@type".into(), json!("Person"));
+    if let Some(orcid) = orcid_url(name) {
+        person.insert("@id".into(), json!(orcid));
+    }
+    person.insert("name".into(), json!(full));
+    if let Some(given) = &name.given {
+        person.insert("givenName".into(), json!(given));
+    }
+    if let Some(family) = name.family_with_particle() {
+        person.insert("familyName".into(), json!(family));
+    }
+    let affiliations: Vec<Value> = name
+        .affiliation
+        .iter()
+        .filter(|affiliation| !affiliation.name.is_empty())
+        .map(|affiliation| json!({"@type": "Organization", "name": affiliation.name}))
+        .collect();
+    if !affiliations.is_empty() {
+        person.insert("affiliation".into(), Value::Array(affiliations));
+    }
+    Some(Value::Object(person))
+}
+
+/// Describe the work the item is published in.
+fn container(metadata: &DoiMetadata, kind: &str) -> Option<Value> {
+    let title = metadata.container_title.as_deref().map(plain_text);
+    if kind == "Chapter" {
+        let mut book = json!({"@type": "Book", "name": title?});
+        if !metadata.isbn.is_empty() {
+            book["isbn"] = json!(metadata.isbn);
+        }
+        return Some(book);
+    }
+    if kind != "ScholarlyArticle" && kind != "Article" {
+        return title.map(|title| json!({"@type": "CreativeWork", "name": title}));
+    }
+    let mut part = json!({"@type": "Periodical", "name": title?});
+    if !metadata.issn.is_empty() {
+        part["issn"] = json!(metadata.issn);
+    }
+    if let Some(volume) = &metadata.volume {
+        part = json!({
+            "@type": "PublicationVolume",
+            "volumeNumber": volume.to_string(),
+            "isPartOf": part,
+        });
+    }
+    if let Some(issue) = &metadata.issue {
+        part = json!({
+            "@type": "PublicationIssue",
+            "issueNumber": issue.to_string(),
+            "isPartOf": part,
+        });
+    }
+    Some(part)
+}
+
+/// Wrap the JSON-LD for an item in a `<script type="application/ld+json">` element.
+pub fn json_ld_script(metadata: &DoiMetadata) -> String {
+    let json = serde_json::to_string_pretty(&to_json_ld(metadata)).unwrap_or_default();
+    // `</script>` inside a string would end the element early.
+    format!(
+        "<script type=\"application/ld+json\">\n{}\n</script>",
+        json.replace("</", "<\\/")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::{Affiliation, CslDate, CslValue, DateParts};
+
+    #[test]
+    /// Maps an article with ORCID authors and its journal chain.
+    fn article_json_ld() {
+        let mut author = CslName::new("Curie", "Marie");
+        author.orcid = Some("http://orcid.org/0000-0002-1825-0097".to_string());
+        author.affiliation.push(Affiliation {
+            name: "Sorbonne".to_string(),
+            ..Default::default()
+        });
+        let item = DoiMetadata {
+            item_type: CslItemType::ArticleJournal,
+            doi: Some("10.1000/xyz".to_string()),
+            title: Some("On <i>radium</i>".to_string()),
+            author: vec![author, CslName::literal("Radium Institute")],
+            container_title: Some("Annales".to_string()),
+            issn: vec!["1234-5678".to_string()],
+            volume: Some(CslValue::Number(3)),
+            issue: Some(CslValue::String("2".to_string())),
+            page: Some(CslValue::String("10-20".to_string())),
+            issued: Some(CslDate::new(DateParts::year_month(1904, 5))),
+            abstract_text: Some("<jats:p>We   report.</jats:p>".to_string()),
+            ..Default::default()
+        };
+        let json = to_json_ld(&item);
+        assert_eq!(json["@type"], "ScholarlyArticle");
+        assert_eq!(json["@id"], "https://doi.org/10.1000/xyz");
+        assert_eq!(json["headline"], "On radium");
+        assert_eq!(json["datePublished"], "1904-05");
+        assert_eq!(json["description"], "We report.");
+        assert_eq!(json["pageStart"], "10");
+        assert_eq!(json["pageEnd"], "20");
+        assert_eq!(
+            json["author"][0],
+            json!({
+                "@type": "Person",
+                "@id": "https://orcid.org/0000-0002-1825-0097",
+                "name": "Marie Curie",
+                "givenName": "Marie",
+                "familyName": "Curie",
+                "affiliation": [{"@type": "Organization", "name": "Sorbonne"}],
+            })
+        );
+        assert_eq!(
+            json["author"][1],
+            json!({"@type": "Organization", "name": "Radium Institute"})
+        );
+        assert_eq!(json["isPartOf"]["@type"], "PublicationIssue");
+        assert_eq!(json["isPartOf"]["isPartOf"]["volumeNumber"], "3");
+        assert_eq!(
+            json["isPartOf"]["isPartOf"]["isPartOf"],
+            json!({"@type": "Periodical", "name": "Annales", "issn": ["1234-5678"]})
+        );
+    }
+
+    #[test]
+    /// Uses `creator` for datasets, book fields for books and escapes the script.
+    fn dataset_and_book() {
+        let dataset = DoiMetadata {
+            item_type: CslItemType::Dataset,
+            title: Some("Readings".to_string()),
+            keyword: Some("x</script>".to_string()),
+            author: vec![CslName::new("Doe", "Jane")],
+            version: Some(CslValue::String("1.2".to_string())),
+            ..Default::default()
+        };
+        let json = to_json_ld(&dataset);
+        assert_eq!(json["@type"], "Dataset");
+        assert_eq!(json["creator"][0]["familyName"], "Doe");
+        assert!(json.get("author").is_none());
+        assert_eq!(json["version"], "1.2");
+        assert!(json_ld_script(&dataset).contains("x<\\/script>"));
+
+        let book = DoiMetadata {
+            item_type: CslItemType::Book,
+            title: Some("A Book".to_string()),
+            isbn: vec!["978-3-16-148410-0".to_string()],
+            publisher: Some("Press".to_string()),
+            ..Default::default()
+        };
+        let json = to_json_ld(&book);
+        assert_eq!(json["@type"], "Book");
+        assert_eq!(json["isbn"], json!(["978-3-16-148410-0"]));
+        assert_eq!(json["publisher"]["name"], "Press");
+        assert!(json.get("headline").is_none());
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt;
+
+use crate::csl::{CslDate, CslItemType, DoiMetadata};
+use crate::embed::{abstract_text, inverted_name, iso_date, page_bounds, plain_text};
+
+/// One `<meta name="..." content="...">` tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaTag {
+    pub name: String,
+    pub content: String,
+}
+
+impl MetaTag {
+    fn new(name: &str, content: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+impl fmt::Display for MetaTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<meta name=\"{}\" content=\"{}\">",
+            escape(&self.name),
+            escape(&self.content)
+        )
+    }
+}
+
+/// Escape an attribute value.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Tags collected in output order, skipping empty values.
+#[derive(Default)]
+struct Tags(Vec<MetaTag>);
+
+impl Tags {
+    fn push(&mut self, name: &str, content: Option<impl AsRef<str>>) {
+        if let Some(content) = content {
+            let content = content.as_ref().trim();
+            if !content.is_empty() {
+                self.0.push(MetaTag::new(name, content));
+            }
+        }
+    }
+}
+
+/// Return the DCMI type vocabulary term for a CSL item type.
+pub fn dcmi_type(item_type: &CslItemType) -> &'static str {
+    match item_type {
+        CslItemType::Dataset => "Dataset",
+        CslItemType::Software => "Software",
+        CslItemType::Figure | CslItemType::Graphic | CslItemType::Map => "StillImage",
+        CslItemType::MotionPicture | CslItemType::Broadcast => "MovingImage",
+        CslItemType::Song => "Sound",
+        CslItemType::Event | CslItemType::Performance => "Event",
+        CslItemType::Collection => "Collection",
+        CslItemType::Webpage | CslItemType::Post | CslItemType::PostWeblog => "InteractiveResource",
+        _ => "Text",
+    }
+}
+
+/// Build Highwire Press `citation_*` tags as read by Google Scholar and Zotero.
+pub fn highwire_tags(metadata: &DoiMetadata) -> Vec<MetaTag> {
+    let mut tags = Tags::default();
+    tags.push("citation_title", metadata.title.as_deref().map(plain_text));
+    for name in &metadata.author {
+        tags.push("citation_author", inverted_name(name));
+        if let Some(orcid) = name.orcid.as_deref() {
+            tags.push("citation_author_orcid", Some(orcid));
+        }
+        for affiliation in &name.affiliation {
+            tags.push("citation_author_institution", Some(&affiliation.name));
+        }
+    }
+    for name in &metadata.editor {
+        tags.push("citation_editor", inverted_name(name));
+    }
+    tags.push(
+        "citation_publication_date",
+        metadata.issued.as_ref().and_then(slash_date),
+    );
+    tags.push(
+        "citation_online_date",
+        metadata.published_online.as_ref().and_then(slash_date),
+    );
+    let container = metadata.container_title.as_deref().map(plain_text);
+    match metadata.item_type {
+        CslItemType::PaperConference => tags.push("citation_conference_title", container),
+        CslItemType::Chapter => tags.push("citation_inbook_title", container),
+        CslItemType::Thesis => tags.push(
+            "citation_dissertation_institution",
+            metadata.publisher.as_ref(),
+        ),
+        CslItemType::Report => {
+            tags.push(
+                "citation_technical_report_institution",
+                metadata.publisher.as_ref(),
+            );
+            tags.push(
+                "citation_technical_report_number",
+                metadata.number.as_ref().map(ToString::to_string),
+            );
+        }
+        _ => {
+            tags.push("citation_journal_title", container);
+            tags.push(
+                "citation_journal_abbrev",
+                metadata
+                    .container_title_short
+                    .as_ref()
+                    .or(metadata.journal_abbreviation.as_ref()),
+            );
+        }
+    }
+    for issn in &metadata.issn {
+        tags.push("citation_issn", Some(issn));
+    }
+    for isbn in &metadata.isbn {
+        tags.push("citation_isbn", Some(isbn));
+    }
+    tags.push(
+        "citation_volume",
+        metadata.volume.as_ref().map(ToString::to_string),
+    );
+    tags.push(
+        "citation_issue",
+        metadata.issue.as_ref().map(ToString::to_string),
+    );
+    if let Some(page) = &metadata.page {
+        let (first, last) = page_bounds(page);
+        tags.push("citation_firstpage", Some(first));
+        tags.push("citation_lastpage", last);
+    }
+    tags.push("citation_doi", metadata.doi.as_ref());
+    if !matches!(
+        metadata.item_type,
+        CslItemType::Thesis | CslItemType::Report
+    ) {
+        tags.push("citation_publisher", metadata.publisher.as_ref());
+    }
+    tags.push(
+        "citation_abstract",
+        metadata.abstract_text.as_deref().map(abstract_text),
+    );
+    tags.push(
+        "citation_keywords",
+        metadata
+            .keyword
+            .as_deref()
+            .map(|keywords| keywords.replace(',', ";")),
+    );
+    tags.push("citation_language", metadata.language.as_ref());
+    tags.push("citation_abstract_html_url", metadata.url.as_ref());
+    tags.0
+}
+
+/// Build Dublin Core `DC.*` tags.
+///
+/// Pages should also declare the namespace with
+/// `<link rel="schema.DC" href="http://purl.org/dc/elements/1.1/">`.
+pub fn dublin_core_tags(metadata: &DoiMetadata) -> Vec<MetaTag> {
+    let mut tags = Tags::default();
+    tags.push("DC.title", metadata.title.as_deref().map(plain_text));
+    for name in &metadata.author {
+        tags.push("DC.creator", inverted_name(name));
+    }
+    for name in metadata.editor.iter().chain(&metadata.translator) {
+        tags.push("DC.contributor", inverted_name(name));
+    }
+    tags.push("DC.date", metadata.issued.as_ref().and_then(iso_date));
+    tags.push("DC.publisher", metadata.publisher.as_ref());
+    tags.push("DC.type", Some(dcmi_type(&metadata.item_type)));
+    tags.push(
+        "DC.identifier",
+        metadata
+            .doi
+            .as_deref()
+            .map(|doi| format!("https://doi.org/{doi}")),
+    );
+    for isbn in &metadata.isbn {
+        tags.push("DC.identifier", Some(format!("urn:isbn:{isbn}")));
+    }
+    for issn in &metadata.issn {
+        tags.push("DC.relation", Some(format!("urn:issn:{issn}")));
+    }
+    tags.push(
+        "DC.source",
+        metadata.container_title.as_deref().map(plain_text),
+    );
+    tags.push(
+        "DC.description",
+        metadata.abstract_text.as_deref().map(abstract_text),
+    );
+    if let Some(keywords) = &metadata.keyword {
+        for keyword in keywords.split([',', ';']) {
+            tags.push("DC.subject", Some(keyword));
+        }
+    }
+    tags.push("DC.language", metadata.language.as_ref());
+    tags.push("DC.rights", metadata.license.as_ref());
+    tags.0
+}
+
+/// Format a date as `YYYY/MM/DD`, `YYYY/MM` or `YYYY`, as Google Scholar prefers.
+fn slash_date(date: &CslDate) -> Option<String> {
+    iso_date(date).map(|date| date.replace('-', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::{CslName, CslValue, DateParts};
+
+    #[test]
+    /// Builds Highwire and Dublin Core tags with type-specific names.
+    fn builds_tags() {
+        let mut author = CslName::new("Doe", "Jane");
+        author.orcid = Some("https://orcid.org/0000-0002-1825-0097".to_string());
+        let item = DoiMetadata {
+            item_type: CslItemType::PaperConference,
+            title: Some("Fast \"Q\" & <b>A</b>s".to_string()),
+            author: vec![author],
+            container_title: Some("Proc. of CO<sub>2</sub> Things".to_string()),
+            issued: Some(CslDate::new(DateParts::ymd(2020, 3, 15))),
+            page: Some(CslValue::String("5-9".to_string())),
+            doi: Some("10.1000/q".to_string()),
+            keyword: Some("alpha, beta".to_string()),
+            ..Default::default()
+        };
+        let highwire: Vec<String> = highwire_tags(&item)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            highwire,
+            [
+                r#"<meta name="citation_title" content="Fast &quot;Q&quot; &amp; As">"#,
+                r#"<meta name="citation_author" content="Doe, Jane">"#,
+                r#"<meta name="citation_author_orcid" content="https://orcid.org/0000-0002-1825-0097">"#,
+                r#"<meta name="citation_publication_date" content="2020/03/15">"#,
+                r#"<meta name="citation_conference_title" content="Proc. of CO₂ Things">"#,
+                r#"<meta name="citation_firstpage" content="5">"#,
+                r#"<meta name="citation_lastpage" content="9">"#,
+                r#"<meta name="citation_doi" content="10.1000/q">"#,
+                r#"<meta name="citation_keywords" content="alpha; beta">"#,
+            ]
+        );
+
+        let dublin_core: Vec<(String, String)> = dublin_core_tags(&item)
+            .into_iter()
+            .map(|tag| (tag.name, tag.content))
+            .collect();
+        let get = |name: &str| {
+            dublin_core
+                .iter()
+                .filter(|(tag, _)| tag == name)
+                .map(|(_, content)| content.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(get("DC.creator"), ["Doe, Jane"]);
+        assert_eq!(get("DC.date"), ["2020-03-15"]);
+        assert_eq!(get("DC.type"), ["Text"]);
+        assert_eq!(get("DC.identifier"), ["https://doi.org/10.1000/q"]);
+        assert_eq!(get("DC.subject"), ["alpha", "beta"]);
+        assert_eq!(dcmi_type(&CslItemType::Dataset), "Dataset");
+    }
+
+    #[test]
+    /// Keeps a page list with several ranges as the first page.
+    fn keeps_page_lists_whole() {
+        let item = DoiMetadata {
+            page: Some(CslValue::String("10-20, 30-40".to_string())),
+            ..Default::default()
+        };
+        let highwire: Vec<String> = highwire_tags(&item)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert!(
+            highwire.contains(
+                &r#"<meta name="citation_firstpage" content="10-20, 30-40">"#.to_string()
+            )
+        );
+        assert!(!highwire.iter().any(|tag| tag.contains("citation_lastpage")));
+    }
+}
@@ -0,0 +1,66 @@
+//! Embedded metadata for landing pages.
+//!
+//! [`to_json_ld`](jsonld::to_json_ld) builds a schema.org JSON-LD object and
+//! [`highwire_tags`](meta::highwire_tags)/[`dublin_core_tags`](meta::dublin_core_tags)
+//! build the `<meta>` tags read by Google Scholar, Zotero and other harvesters.
+
+pub mod jsonld;
+pub mod meta;
+
+use crate::citeproc::output::OutputFormat;
+use crate::csl::{CslDate, CslName, CslValue};
+use crate::jats::JatsAbstract;
+
+/// Return a title or name with markup such as `<i>` or `<jats:sub>` removed,
+/// on one line.
+fn plain_text(text: &str) -> String {
+    abstract_text(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Return a JATS abstract as plain text, paragraphs separated by blank lines.
+fn abstract_text(text: &str) -> String {
+    JatsAbstract::parse(text).render(OutputFormat::Text)
+}
+
+/// Return the ORCID as an `https://orcid.org/` URL.
+fn orcid_url(name: &CslName) -> Option<String> {
+    let orcid = name.orcid.as_deref()?.trim();
+    let id = orcid.rsplit('/').next().unwrap_or(orcid);
+    (!id.is_empty()).then(|| format!("https://orcid.org/{id}"))
+}
+
+/// Return the name in display order, or the literal name.
+fn display_name(name: &CslName) -> Option<String> {
+    let display = name.display_name();
+    (!display.trim().is_empty()).then_some(display)
+}
+
+/// Return the name as `Family, Given`, or the literal name.
+fn inverted_name(name: &CslName) -> Option<String> {
+    match name.family_with_particle() {
+        Some(family) => Some(match name.given.as_deref() {
+            Some(given) => format!("{family}, {given}"),
+            None => family,
+        }),
+        None => name.literal.clone().or(name.given.clone()),
+    }
+}
+
+/// Split a page range into its first and last page.
+fn page_bounds(page: &CslValue) -> (String, Option<String>) {
+    let page = page.to_string();
+    match page.split_once(['-', '–']) {
+        Some((start, end)) if !page.contains(',') => {
+            (start.trim().to_string(), Some(end.trim().to_string()))
+        }
+        _ => (page, None),
+    }
+}
+
+/// Return the date in ISO 8601 form (`2020`, `2020-03` or `2020-03-15`).
+fn iso_date(date: &CslDate) -> Option<String> {
+    date.start().map(|start| start.to_string())
+}
@@ -0,0 +1,112 @@
+//! Shared HTTP helpers for the metadata clients.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER, USER_AGENT};
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio::time::{Instant, sleep_until};
+
+/// Longest wait taken before retrying, whatever `Retry-After` asks for.
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// Build the User-Agent header value from an application name and contact email.
+///
+/// The contact is appended as `mailto:...` so registration agencies can route
+/// the client into their polite pools.
+pub(crate) fn user_agent_value(agent: Option<&str>, mailto: Option<&str>) -> Option<String> {
+    match (agent, mailto) {
+        (Some(agent), Some(mailto)) => Some(format!("{} mailto:{}", agent, mailto)),
+        (Some(agent), None) => Some(agent.to_string()),
+        (None, Some(mailto)) => Some(format!("mailto:{}", mailto)),
+        (None, None) => None,
+    }
+}
+
+/// Build default headers carrying the User-Agent when configured.
+pub(crate) fn user_agent_headers(agent: Option<&str>, mailto: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(user_agent) = user_agent_value(agent, mailto)
+        && let Ok(value) = HeaderValue::from_str(&user_agent)
+    {
+        headers.insert(USER_AGENT, value);
+    }
+    headers
+}
+
+/// Return true when a request failed with HTTP 404.
+pub(crate) fn is_not_found(error: &reqwest::Error) -> bool {
+    error.status() == Some(reqwest::StatusCode::NOT_FOUND)
+}
+
+/// Return true for answers that ask the client to slow down and try again.
+pub(crate) fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    )
+}
+
+/// Return how long to wait before retry number `attempt` (from 0): the
+/// `Retry-After` header in seconds or as an HTTP date, or else 1s, 2s, 4s, ….
+pub(crate) fn retry_delay(headers: &HeaderMap, attempt: u32) -> Duration {
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .and_then(|value| match value.parse::<u64>() {
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            Err(_) => {
+                let date = DateTime::parse_from_rfc2822(value).ok()?;
+                Some(
+                    (date.with_timezone(&Utc) - Utc::now())
+                        .to_std()
+                        .unwrap_or_default(),
+                )
+            }
+        });
+    retry_after
+        .unwrap_or_else(|| Duration::from_secs(1 << attempt.min(6)))
+        .min(MAX_RETRY_WAIT)
+}
+
+/// Limits request rate and the number of requests in flight.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    interval: Option<Duration>,
+    next: Mutex<Instant>,
+    permits: Semaphore,
+}
+
+impl RateLimiter {
+    /// Build a limiter; `None` disables the corresponding limit.
+    pub(crate) fn new(per_second: Option<u32>, concurrency: Option<usize>) -> Self {
+        let interval = per_second
+            .filter(|rate| *rate > 0)
+            .map(|rate| Duration::from_secs(1) / rate);
+        let permits = concurrency
+            .filter(|limit| *limit > 0)
+            .unwrap_or(Semaphore::MAX_PERMITS);
+        Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+            permits: Semaphore::new(permits),
+        }
+    }
+
+    /// Wait for a free slot; the returned permit must be held for the request.
+    pub(crate) async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        // The semaphore is never closed, so acquire only fails in theory.
+        let permit = self.permits.acquire().await.ok();
+        if let Some(interval) = self.interval {
+            let mut next = self.next.lock().await;
+            let now = Instant::now();
+            if *next > now {
+                sleep_until(*next).await;
+            }
+            *next = (*next).max(now) + interval;
+        }
+        permit
+    }
+}
@@ -0,0 +1,362 @@
+//! MathML to TeX conversion.
+
+use roxmltree::Node;
+
+/// Unicode characters written as TeX commands.
+const SYMBOLS: &[(char, &str)] = &[
+    ('α', "\\alpha"),
+    ('β', "\\beta"),
+    ('γ', "\\gamma"),
+    ('δ', "\\delta"),
+    ('ε', "\\epsilon"),
+    ('ϵ', "\\epsilon"),
+    ('ζ', "\\zeta"),
+    ('η', "\\eta"),
+    ('θ', "\\theta"),
+    ('ι', "\\iota"),
+    ('κ', "\\kappa"),
+    ('λ', "\\lambda"),
+    ('μ', "\\mu"),
+    ('µ', "\\mu"),
+    ('ν', "\\nu"),
+    ('ξ', "\\xi"),
+    ('π', "\\pi"),
+    ('ρ', "\\rho"),
+    ('σ', "\\sigma"),
+    ('τ', "\\tau"),
+    ('υ', "\\upsilon"),
+    ('φ', "\\phi"),
+    ('ϕ', "\\phi"),
+    ('χ', "\\chi"),
+    ('ψ', "\\psi"),
+    ('ω', "\\omega"),
+    ('Γ', "\\Gamma"),
+    ('Δ', "\\Delta"),
+    ('Θ', "\\Theta"),
+    ('Λ', "\\Lambda"),
+    ('Ξ', "\\Xi"),
+    ('Π', "\\Pi"),
+    ('Σ', "\\Sigma"),
+    ('Φ', "\\Phi"),
+    ('Ψ', "\\Psi"),
+    ('Ω', "\\Omega"),
+    ('×', "\\times"),
+    ('·', "\\cdot"),
+    ('⋅', "\\cdot"),
+    ('÷', "\\div"),
+    ('±', "\\pm"),
+    ('∓', "\\mp"),
+    ('−', "-"),
+    ('≤', "\\leq"),
+    ('≥', "\\geq"),
+    ('≠', "\\neq"),
+    ('≈', "\\approx"),
+    ('∼', "\\sim"),
+    ('≡', "\\equiv"),
+    ('∝', "\\propto"),
+    ('≪', "\\ll"),
+    ('≫', "\\gg"),
+    ('→', "\\rightarrow"),
+    ('←', "\\leftarrow"),
+    ('↔', "\\leftrightarrow"),
+    ('⇒', "\\Rightarrow"),
+    ('⇔', "\\Leftrightarrow"),
+    ('∞', "\\infty"),
+    ('∑', "\\sum"),
+    ('∏', "\\prod"),
+    ('∫', "\\int"),
+    ('∮', "\\oint"),
+    ('∂', "\\partial"),
+    ('∇', "\\nabla"),
+    ('∈', "\\in"),
+    ('∉', "\\notin"),
+    ('⊂', "\\subset"),
+    ('⊆', "\\subseteq"),
+    ('∪', "\\cup"),
+    ('∩', "\\cap"),
+    ('∅', "\\emptyset"),
+    ('∀', "\\forall"),
+    ('∃', "\\exists"),
+    ('¬', "\\neg"),
+    ('∧', "\\wedge"),
+    ('∨', "\\vee"),
+    ('′', "'"),
+    ('″', "''"),
+    ('…', "\\ldots"),
+    ('⋯', "\\cdots"),
+    ('°', "^\\circ"),
+    ('ℏ', "\\hbar"),
+    ('ℓ', "\\ell"),
+    ('⟨', "\\langle"),
+    ('⟩', "\\rangle"),
+    ('‖', "\\|"),
+    ('{', "\\{"),
+    ('}', "\\}"),
+    ('%', "\\%"),
+    ('#', "\\#"),
+    ('&', "\\&"),
+    ('_', "\\_"),
+    ('\u{2061}', ""),
+    ('\u{2062}', ""),
+    ('\u{2063}', ""),
+];
+
+/// Multi-letter identifiers TeX writes as operators.
+const FUNCTIONS: &[&str] = &[
+    "arccos", "arcsin", "arctan", "cos", "cosh", "cot", "det", "exp", "gcd", "inf", "lim", "ln",
+    "log", "max", "min", "sin", "sinh", "sup", "tan", "tanh",
+];
+
+/// Accents drawn over a base by `<mover accent="true">`.
+const ACCENTS: &[(&str, &str)] = &[
+    ("¯", "\\bar"),
+    ("‾", "\\bar"),
+    ("^", "\\hat"),
+    ("ˆ", "\\hat"),
+    ("~", "\\tilde"),
+    ("˜", "\\tilde"),
+    ("→", "\\vec"),
+    ("⃗", "\\vec"),
+    ("˙", "\\dot"),
+    ("¨", "\\ddot"),
+];
+
+/// Convert a MathML element (usually `<math>`) to TeX without delimiters.
+pub(crate) fn to_tex(node: Node) -> String {
+    // Authors sometimes ship the TeX source as an annotation.
+    if let Some(tex) = node
+        .descendants()
+        .filter(|child| child.tag_name().name() == "annotation")
+        .find(|child| {
+            child
+                .attribute("encoding")
+                .is_some_and(|encoding| encoding.contains("tex"))
+        })
+        .and_then(|child| child.text())
+    {
+        return tex.trim().to_string();
+    }
+    element(node).trim().to_string()
+}
+
+fn elements<'a, 'input>(node: Node<'a, 'input>) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(Node::is_element)
+}
+
+/// Convert the children of a node and join them.
+fn row(node: Node) -> String {
+    let mut out = String::new();
+    for child in elements(node) {
+        push(&mut out, &element(child));
+    }
+    out
+}
+
+/// Append a piece, keeping `\alpha x` from becoming `\alphax`.
+fn push(out: &mut String, piece: &str) {
+    let command_end = out.rsplit_once('\\').is_some_and(|(_, tail)| {
+        !tail.is_empty() && tail.chars().all(|ch| ch.is_ascii_alphabetic())
+    });
+    if command_end && piece.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+        out.push(' ');
+    }
+    out.push_str(piece);
+}
+
+/// Brace an argument unless it is a single character or command.
+fn group(tex: &str) -> String {
+    let single = tex.chars().count() == 1
+        || (tex.starts_with('\\') && tex[1..].chars().all(|ch| ch.is_ascii_alphabetic()));
+    if single && !tex.is_empty() {
+        tex.to_string()
+    } else {
+        format!("{{{tex}}}")
+    }
+}
+
+/// The `n`th element child converted to TeX.
+fn argument(node: Node, n: usize) -> String {
+    elements(node).nth(n).map(element).unwrap_or_default()
+}
+
+fn element(node: Node) -> String {
+    let text = || node.text().unwrap_or_default().trim();
+    match node.tag_name().name() {
+        "mi" => identifier(text(), node.attribute("mathvariant")),
+        "mn" => symbols(text()),
+        "mo" => {
+            let op = symbols(text());
+            if matches!(
+                text(),
+                "=" | "<" | ">" | "+" | "," | "≤" | "≥" | "≠" | "≈" | "→"
+            ) {
+                format!(" {op} ").replace("  ", " ")
+            } else {
+                op
+            }
+        }
+        "mtext" | "ms" => {
+            let text = text();
+            if text.is_empty() {
+                String::new()
+            } else {
+                format!("\\text{{{text}}}")
+            }
+        }
+        "mspace" => "\\,".to_string(),
+        "msub" => format!(
+            "{}_{}",
+            group(&argument(node, 0)),
+            group(&argument(node, 1))
+        ),
+        "msup" => format!(
+            "{}^{}",
+            group(&argument(node, 0)),
+            group(&argument(node, 1))
+        ),
+        "msubsup" => format!(
+            "{}_{}^{}",
+            group(&argument(node, 0)),
+            group(&argument(node, 1)),
+            group(&argument(node, 2))
+        ),
+        "munder" => {
+            let base = argument(node, 0);
+            let under = argument(node, 1);
+            if is_large_operator(&base) {
+                format!("{base}_{}", group(&under))
+            } else {
+                format!("\\underset{{{under}}}{{{base}}}")
+            }
+        }
+        "mover" => {
+            let base = argument(node, 0);
+            let over_text = elements(node)
+                .nth(1)
+                .and_then(|over| over.text())
+                .unwrap_or_default()
+                .trim();
+            if let Some((_, accent)) = ACCENTS.iter().find(|(mark, _)| *mark == over_text) {
+                format!("{accent}{{{base}}}")
+            } else if is_large_operator(&base) {
+                format!("{base}^{}", group(&argument(node, 1)))
+            } else {
+                format!("\\overset{{{}}}{{{base}}}", argument(node, 1))
+            }
+        }
+        "munderover" => format!(
+            "{}_{}^{}",
+            argument(node, 0),
+            group(&argument(node, 1)),
+            group(&argument(node, 2))
+        ),
+        "mfrac" => format!("\\frac{{{}}}{{{}}}", argument(node, 0), argument(node, 1)),
+        "msqrt" => format!("\\sqrt{{{}}}", row(node)),
+        "mroot" => format!("\\sqrt[{}]{{{}}}", argument(node, 1), argument(node, 0)),
+        "mfenced" => {
+            let open = symbols(node.attribute("open").unwrap_or("("));
+            let close = symbols(node.attribute("close").unwrap_or(")"));
+            let separator = node.attribute("separators").unwrap_or(",").trim();
+            let parts: Vec<String> = elements(node).map(element).collect();
+            format!(
+                "\\left{open}{}\\right{close}",
+                parts.join(&format!("{separator} "))
+            )
+        }
+        "mtable" => {
+            let rows: Vec<String> = elements(node)
+                .map(|row| {
+                    elements(row)
+                        .map(|cell| self::row(cell))
+                        .collect::<Vec<_>>()
+                        .join(" & ")
+                })
+                .collect();
+            format!("\\begin{{matrix}}{}\\end{{matrix}}", rows.join(" \\\\ "))
+        }
+        "semantics" => elements(node).next().map(element).unwrap_or_default(),
+        "annotation" | "annotation-xml" | "mphantom" | "none" | "mprescripts" => String::new(),
+        _ => row(node),
+    }
+}
+
+fn is_large_operator(tex: &str) -> bool {
+    matches!(
+        tex,
+        "\\sum" | "\\prod" | "\\int" | "\\oint" | "\\lim" | "\\max" | "\\min" | "\\sup" | "\\inf"
+    )
+}
+
+fn identifier(text: &str, variant: Option<&str>) -> String {
+    if FUNCTIONS.contains(&text) {
+        return format!("\\{text}");
+    }
+    let tex = symbols(text);
+    match variant {
+        Some("bold") => format!("\\mathbf{{{tex}}}"),
+        Some("normal") if text.chars().count() == 1 && text.is_ascii() => {
+            format!("\\mathrm{{{tex}}}")
+        }
+        _ if text.chars().count() > 1 && text.chars().all(char::is_alphabetic) => {
+            format!("\\mathrm{{{tex}}}")
+        }
+        _ => tex,
+    }
+}
+
+/// Replace Unicode symbols with TeX commands.
+fn symbols(text: &str) -> String {
+    let mut out = String::new();
+    for ch in text.chars() {
+        match SYMBOLS.iter().find(|(symbol, _)| *symbol == ch) {
+            Some((_, tex)) => push(&mut out, tex),
+            None => push(&mut out, ch.encode_utf8(&mut [0; 4])),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(mathml: &str) -> String {
+        let document = roxmltree::Document::parse(mathml).unwrap();
+        to_tex(document.root_element())
+    }
+
+    #[test]
+    /// Converts scripts, fractions, roots, operators and accents.
+    fn converts_mathml() {
+        assert_eq!(
+            convert(
+                "<math><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><msub><mi>y</mi><mrow><mi>i</mi><mi>j</mi></mrow></msub></math>"
+            ),
+            "x^2 + y_{ij}"
+        );
+        assert_eq!(
+            convert(
+                "<math><mfrac><mrow><mi>α</mi><mi>x</mi></mrow><msqrt><mn>10</mn></msqrt></mfrac></math>"
+            ),
+            "\\frac{\\alpha x}{\\sqrt{10}}"
+        );
+        assert_eq!(
+            convert(
+                "<math><munderover><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover><mover accent=\"true\"><mi>v</mi><mo>→</mo></mover></math>"
+            ),
+            "\\sum_{i = 1}^n\\vec{v}"
+        );
+        assert_eq!(
+            convert(
+                "<math><mi>sin</mi><mi>θ</mi><mo>≤</mo><mn>1</mn><mtext>for all</mtext></math>"
+            ),
+            "\\sin\\theta \\leq 1\\text{for all}"
+        );
+        assert_eq!(
+            convert(
+                "<math><semantics><mi>x</mi><annotation encoding=\"application/x-tex\">x_0</annotation></semantics></math>"
+            ),
+            "x_0"
+        );
+    }
+}
@@ -0,0 +1,725 @@
+//! JATS abstract conversion.
+//!
+//! Crossref sends abstracts as JATS XML fragments (`<jats:p>`, `<jats:sec>`,
+//! `<jats:italic>`, MathML). [`JatsAbstract::parse`] reads them into blocks
+//! of inline content that [`JatsAbstract::render`] writes as plain text,
+//! Markdown or sanitized HTML, with formulas as TeX.
+
+mod math;
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::citeproc::output::OutputFormat;
+
+/// Namespace prefixes on element and attribute names (`jats:p`, `xlink:href`).
+static PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(</?|\s)[A-Za-z][\w.-]*:([A-Za-z][\w.-]*)").unwrap());
+/// Any tag, for the fallback when the markup is not well-formed.
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+/// Paragraph-level closing tags, for the fallback.
+static BLOCK_END: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)</(?:[\w.-]+:)?(?:p|title|sec|list-item)>|<(?:[\w.-]+:)?break\s*/>").unwrap()
+});
+
+/// HTML entities that show up in abstracts but are not defined in XML.
+const ENTITIES: &[(&str, &str)] = &[
+    ("nbsp", "&#160;"),
+    ("ndash", "&#8211;"),
+    ("mdash", "&#8212;"),
+    ("hellip", "&#8230;"),
+    ("lsquo", "&#8216;"),
+    ("rsquo", "&#8217;"),
+    ("ldquo", "&#8220;"),
+    ("rdquo", "&#8221;"),
+    ("times", "&#215;"),
+    ("deg", "&#176;"),
+    ("plusmn", "&#177;"),
+    ("minus", "&#8722;"),
+    ("le", "&#8804;"),
+    ("ge", "&#8805;"),
+    ("micro", "&#181;"),
+    ("alpha", "&#945;"),
+    ("beta", "&#946;"),
+    ("gamma", "&#947;"),
+    ("delta", "&#948;"),
+    ("mu", "&#956;"),
+    ("copy", "&#169;"),
+    ("reg", "&#174;"),
+    ("trade", "&#8482;"),
+];
+
+/// Characters with a Unicode superscript form.
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'),
+    ('1', '¹'),
+    ('2', '²'),
+    ('3', '³'),
+    ('4', '⁴'),
+    ('5', '⁵'),
+    ('6', '⁶'),
+    ('7', '⁷'),
+    ('8', '⁸'),
+    ('9', '⁹'),
+    ('+', '⁺'),
+    ('-', '⁻'),
+    ('−', '⁻'),
+    ('=', '⁼'),
+    ('(', '⁽'),
+    (')', '⁾'),
+    ('i', 'ⁱ'),
+    ('n', 'ⁿ'),
+];
+
+/// Characters with a Unicode subscript form.
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'),
+    ('1', '₁'),
+    ('2', '₂'),
+    ('3', '₃'),
+    ('4', '₄'),
+    ('5', '₅'),
+    ('6', '₆'),
+    ('7', '₇'),
+    ('8', '₈'),
+    ('9', '₉'),
+    ('+', '₊'),
+    ('-', '₋'),
+    ('−', '₋'),
+    ('=', '₌'),
+    ('(', '₍'),
+    (')', '₎'),
+    ('a', 'ₐ'),
+    ('e', 'ₑ'),
+    ('h', 'ₕ'),
+    ('k', 'ₖ'),
+    ('l', 'ₗ'),
+    ('m', 'ₘ'),
+    ('n', 'ₙ'),
+    ('o', 'ₒ'),
+    ('p', 'ₚ'),
+    ('s', 'ₛ'),
+    ('t', 'ₜ'),
+    ('x', 'ₓ'),
+];
+
+/// A paragraph-level part of an abstract.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// A section title; `level` is 1 for top-level sections.
+    Title {
+        level: usize,
+        content: Vec<Inline>,
+    },
+    Paragraph(Vec<Inline>),
+    List {
+        ordered: bool,
+        items: Vec<Vec<Inline>>,
+    },
+}
+
+/// Inline formatting applied to a run of content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineStyle {
+    Italic,
+    Bold,
+    Monospace,
+    SmallCaps,
+    Underline,
+    Superscript,
+    Subscript,
+}
+
+/// Inline content of a paragraph or title.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Styled(InlineStyle, Vec<Inline>),
+    /// A formula as TeX, without delimiters.
+    Math {
+        tex: String,
+        display: bool,
+    },
+    Link {
+        href: String,
+        content: Vec<Inline>,
+    },
+}
+
+/// A parsed JATS abstract.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JatsAbstract {
+    pub blocks: Vec<Block>,
+}
+
+impl JatsAbstract {
+    /// Parse JATS markup; text that is not well-formed XML has its tags stripped.
+    pub fn parse(input: &str) -> Self {
+        let doctype: String = ENTITIES
+            .iter()
+            .map(|(name, value)| format!("<!ENTITY {name} \"{value}\">"))
+            .collect();
+        let xml = format!(
+            "<!DOCTYPE abstract [{doctype}]><abstract>{}</abstract>",
+            PREFIX.replace_all(input, "$1$2")
+        );
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let Ok(document) = Document::parse_with_options(&xml, options) else {
+            return Self::fallback(input);
+        };
+        let mut parser = Parser::default();
+        parser.container(document.root_element(), 0);
+        parser.flush();
+        Self {
+            blocks: parser.blocks,
+        }
+    }
+
+    fn fallback(input: &str) -> Self {
+        let text = BLOCK_END.replace_all(input, "\n\n");
+        let text = TAG.replace_all(&text, "");
+        let text = text
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&nbsp;", "\u{a0}")
+            .replace("&amp;", "&");
+        let blocks = text
+            .split("\n\n")
+            .map(collapse)
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| Block::Paragraph(vec![Inline::Text(paragraph)]))
+            .collect();
+        Self { blocks }
+    }
+
+    /// Write the abstract as plain text, Markdown or sanitized HTML.
+    ///
+    /// Blocks are separated by blank lines (newlines in HTML). Formulas are
+    /// written as `$tex$` in text and Markdown and as `\(tex\)` in HTML, ready
+    /// for MathJax or KaTeX.
+    pub fn render(&self, format: OutputFormat) -> String {
+        let blocks: Vec<String> = self
+            .blocks
+            .iter()
+            .map(|block| render_block(block, format))
+            .collect();
+        match format {
+            OutputFormat::Html => blocks.join("\n"),
+            _ => blocks.join("\n\n"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    blocks: Vec<Block>,
+    /// Loose inline content outside a `<p>`.
+    pending: Vec<Inline>,
+}
+
+impl Parser {
+    /// Read block-level children of `<abstract>` or `<sec>`.
+    fn container(&mut self, node: Node, level: usize) {
+        for child in node.children() {
+            if child.is_text() {
+                push_text(&mut self.pending, child.text().unwrap_or_default());
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+            match child.tag_name().name() {
+                "abstract" | "trans-abstract" => self.container(child, level),
+                "sec" => {
+                    self.flush();
+                    self.container(child, level + 1);
+                }
+                "title" => {
+                    self.flush();
+                    let content = trim(inline_children(child));
+                    let is_heading = level == 0 && plain(&content).eq_ignore_ascii_case("abstract");
+                    if !content.is_empty() && !is_heading {
+                        self.blocks.push(Block::Title {
+                            level: level.max(1),
+                            content,
+                        });
+                    }
+                }
+                "p" => {
+                    self.flush();
+                    self.paragraph(child);
+                }
+                "list" => {
+                    self.flush();
+                    self.list(child);
+                }
+                "disp-formula" => {
+                    self.flush();
+                    if let Some(math) = formula(child, true) {
+                        self.blocks.push(Block::Paragraph(vec![math]));
+                    }
+                }
+                "label" | "object-id" | "kwd-group" => {}
+                _ => self.pending.extend(inline(child)),
+            }
+        }
+    }
+
+    /// Read a `<p>`, splitting it around nested lists.
+    fn paragraph(&mut self, node: Node) {
+        for child in node.children() {
+            if child.has_tag_name("list") {
+                self.flush();
+                self.list(child);
+            } else if child.is_text() {
+                push_text(&mut self.pending, child.text().unwrap_or_default());
+            } else {
+                self.pending.extend(inline(child));
+            }
+        }
+        self.flush();
+    }
+
+    fn list(&mut self, node: Node) {
+        let ordered = node.attribute("list-type").is_some_and(|kind| {
+            kind.starts_with("order") || kind.contains("alpha") || kind.contains("roman")
+        });
+        let items = node
+            .children()
+            .filter(|child| child.has_tag_name("list-item"))
+            .map(|item| trim(inline_children(item)))
+            .filter(|item| !item.is_empty())
+            .collect();
+        self.blocks.push(Block::List { ordered, items });
+    }
+
+    fn flush(&mut self) {
+        let content = trim(std::mem::take(&mut self.pending));
+        if !content.is_empty() {
+            self.blocks.push(Block::Paragraph(content));
+        }
+    }
+}
+
+/// Read the inline content of an element's children.
+fn inline_children(node: Node) -> Vec<Inline> {
+    let mut out = Vec::new();
+    for child in node.children() {
+        if child.is_text() {
+            push_text(&mut out, child.text().unwrap_or_default());
+        } else if child.is_element() {
+            // Paragraphs inside list items and similar wrappers run together.
+            if child.has_tag_name("p") && !out.is_empty() {
+                push_text(&mut out, " ");
+            }
+            out.extend(inline(child));
+        }
+    }
+    out
+}
+
+/// Read one inline element.
+fn inline(node: Node) -> Vec<Inline> {
+    let style = match node.tag_name().name() {
+        "italic" | "i" | "em" => Some(InlineStyle::Italic),
+        "bold" | "b" | "strong" => Some(InlineStyle::Bold),
+        "monospace" | "tt" | "code" => Some(InlineStyle::Monospace),
+        "sc" => Some(InlineStyle::SmallCaps),
+        "underline" | "u" => Some(InlineStyle::Underline),
+        "sup" => Some(InlineStyle::Superscript),
+        "sub" => Some(InlineStyle::Subscript),
+        _ => None,
+    };
+    if let Some(style) = style {
+        let content = inline_children(node);
+        return if content.is_empty() {
+            content
+        } else {
+            vec![Inline::Styled(style, content)]
+        };
+    }
+    match node.tag_name().name() {
+        "inline-formula" => formula(node, false).into_iter().collect(),
+        "disp-formula" => formula(node, true).into_iter().collect(),
+        "math" => vec![Inline::Math {
+            tex: math::to_tex(node),
+            display: node.attribute("display") == Some("block"),
+        }],
+        "tex-math" => vec![Inline::Math {
+            tex: tex_math(node),
+            display: false,
+        }],
+        "ext-link" | "uri" | "a" => {
+            let content = inline_children(node);
+            let href = node
+                .attribute("href")
+                .map(str::to_string)
+                .unwrap_or_else(|| plain(&content));
+            vec![Inline::Link { href, content }]
+        }
+        "break" => vec![Inline::Text(" ".to_string())],
+        "label" | "fn" | "object-id" => Vec::new(),
+        _ => inline_children(node),
+    }
+}
+
+/// Read an `<inline-formula>` or `<disp-formula>`, preferring TeX over MathML.
+fn formula(node: Node, display: bool) -> Option<Inline> {
+    let descendants = || node.descendants().filter(Node::is_element);
+    let tex = descendants()
+        .find(|child| child.has_tag_name("tex-math"))
+        .map(tex_math)
+        .or_else(|| {
+            descendants()
+                .find(|child| child.has_tag_name("math"))
+                .map(math::to_tex)
+        })
+        .filter(|tex| !tex.is_empty());
+    match tex {
+        Some(tex) => Some(Inline::Math { tex, display }),
+        None => {
+            let text = collapse(
+                &node
+                    .descendants()
+                    .filter(Node::is_text)
+                    .filter_map(|child| child.text())
+                    .collect::<String>(),
+            );
+            (!text.is_empty()).then_some(Inline::Text(text))
+        }
+    }
+}
+
+/// Return the body of `<tex-math>` without the document wrapper or delimiters.
+fn tex_math(node: Node) -> String {
+    let text: String = node
+        .descendants()
+        .filter(Node::is_text)
+        .filter_map(|child| child.text())
+        .collect();
+    let body = match (text.find("\\begin{document}"), text.find("\\end{document}")) {
+        (Some(start), Some(end)) if start < end => &text[start + "\\begin{document}".len()..end],
+        _ => text.as_str(),
+    };
+    let body = body.trim();
+    for (open, close) in [("$$", "$$"), ("$", "$"), ("\\(", "\\)"), ("\\[", "\\]")] {
+        if let Some(inner) = body
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        {
+            return inner.trim().to_string();
+        }
+    }
+    body.to_string()
+}
+
+/// Append text, merging with a preceding text run.
+fn push_text(out: &mut Vec<Inline>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match out.last_mut() {
+        Some(Inline::Text(last)) => last.push_str(text),
+        _ => out.push(Inline::Text(text.to_string())),
+    }
+}
+
+/// Collapse whitespace runs and trim the ends of a run of inline content.
+fn trim(content: Vec<Inline>) -> Vec<Inline> {
+    let mut content: Vec<Inline> = content.into_iter().map(normalize).collect();
+    if let Some(Inline::Text(first)) = content.first_mut() {
+        *first = first.trim_start().to_string();
+    }
+    if let Some(Inline::Text(last)) = content.last_mut() {
+        *last = last.trim_end().to_string();
+    }
+    content.retain(|inline| !matches!(inline, Inline::Text(text) if text.is_empty()));
+    content
+}
+
+fn normalize(inline: Inline) -> Inline {
+    match inline {
+        Inline::Text(text) => {
+            let mut out = String::with_capacity(text.len());
+            let mut space = false;
+            for ch in text.chars() {
+                if ch.is_whitespace() && ch != '\u{a0}' {
+                    space = true;
+                } else {
+                    if space {
+                        out.push(' ');
+                        space = false;
+                    }
+                    out.push(ch);
+                }
+            }
+            if space {
+                out.push(' ');
+            }
+            Inline::Text(out)
+        }
+        Inline::Styled(style, content) => {
+            Inline::Styled(style, content.into_iter().map(normalize).collect())
+        }
+        Inline::Link { href, content } => Inline::Link {
+            href,
+            content: content.into_iter().map(normalize).collect(),
+        },
+        math => math,
+    }
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Return the text of inline content without markup.
+fn plain(content: &[Inline]) -> String {
+    render_inline(content, OutputFormat::Text)
+}
+
+fn render_block(block: &Block, format: OutputFormat) -> String {
+    match (block, format) {
+        (Block::Title { level, content }, OutputFormat::Html) => {
+            let level = (level + 1).min(6);
+            format!("<h{level}>{}</h{level}>", render_inline(content, format))
+        }
+        (Block::Title { level, content }, OutputFormat::Markdown) => format!(
+            "{} {}",
+            "#".repeat((level + 1).min(6)),
+            render_inline(content, format)
+        ),
+        (Block::Title { content, .. }, OutputFormat::Text) => render_inline(content, format),
+        (Block::Paragraph(content), OutputFormat::Html) => {
+            format!("<p>{}</p>", render_inline(content, format))
+        }
+        (Block::Paragraph(content), _) => render_inline(content, format),
+        (Block::List { ordered, items }, OutputFormat::Html) => {
+            let tag = if *ordered { "ol" } else { "ul" };
+            let items: String = items
+                .iter()
+                .map(|item| format!("<li>{}</li>", render_inline(item, format)))
+                .collect();
+            format!("<{tag}>{items}</{tag}>")
+        }
+        (Block::List { ordered, items }, _) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let marker = if *ordered {
+                    format!("{}.", index + 1)
+                } else {
+                    "-".to_string()
+                };
+                format!("{marker} {}", render_inline(item, format))
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn render_inline(content: &[Inline], format: OutputFormat) -> String {
+    let mut out = String::new();
+    for inline in content {
+        match inline {
+            Inline::Text(text) => out.push_str(&match format {
+                OutputFormat::Text => text.clone(),
+                OutputFormat::Html => escape_html(text),
+                OutputFormat::Markdown => escape_markdown(text),
+            }),
+            Inline::Styled(style, content) => {
+                let inner = render_inline(content, format);
+                out.push_str(&styled(*style, inner, format));
+            }
+            Inline::Math { tex, display } => out.push_str(&match (format, display) {
+                (OutputFormat::Html, false) => {
+                    format!(
+                        "<span class=\"math inline\">\\({}\\)</span>",
+                        escape_html(tex)
+                    )
+                }
+                (OutputFormat::Html, true) => {
+                    format!(
+                        "<span class=\"math display\">\\[{}\\]</span>",
+                        escape_html(tex)
+                    )
+                }
+                (_, false) => format!("${tex}$"),
+                (_, true) => format!("$${tex}$$"),
+            }),
+            Inline::Link { href, content } => {
+                let text = render_inline(content, format);
+                out.push_str(&match format {
+                    OutputFormat::Text => text,
+                    OutputFormat::Html if is_safe_url(href) => {
+                        format!("<a href=\"{}\">{text}</a>", escape_html(href))
+                    }
+                    OutputFormat::Html => text,
+                    OutputFormat::Markdown if is_safe_url(href) => {
+                        format!("[{text}]({})", href.replace(' ', "%20").replace(')', "%29"))
+                    }
+                    OutputFormat::Markdown => text,
+                });
+            }
+        }
+    }
+    out
+}
+
+fn styled(style: InlineStyle, inner: String, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => match style {
+            InlineStyle::Superscript => script(&inner, SUPERSCRIPTS, '^'),
+            InlineStyle::Subscript => script(&inner, SUBSCRIPTS, '_'),
+            _ => inner,
+        },
+        OutputFormat::Html => match style {
+            InlineStyle::Italic => format!("<i>{inner}</i>"),
+            InlineStyle::Bold => format!("<b>{inner}</b>"),
+            InlineStyle::Monospace => format!("<code>{inner}</code>"),
+            InlineStyle::SmallCaps => {
+                format!("<span style=\"font-variant:small-caps;\">{inner}</span>")
+            }
+            InlineStyle::Underline => format!("<u>{inner}</u>"),
+            InlineStyle::Superscript => format!("<sup>{inner}</sup>"),
+            InlineStyle::Subscript => format!("<sub>{inner}</sub>"),
+        },
+        // Markdown has no sub/superscript, so those keep their HTML tags.
+        OutputFormat::Markdown => match style {
+            InlineStyle::Italic => format!("*{inner}*"),
+            InlineStyle::Bold => format!("**{inner}**"),
+            InlineStyle::Monospace => format!("`{inner}`"),
+            InlineStyle::SmallCaps | InlineStyle::Underline => inner,
+            InlineStyle::Superscript => format!("<sup>{inner}</sup>"),
+            InlineStyle::Subscript => format!("<sub>{inner}</sub>"),
+        },
+    }
+}
+
+/// Write a sub/superscript with Unicode characters, or as `^{…}`/`_{…}` when
+/// some character has no Unicode form.
+fn script(inner: &str, table: &[(char, char)], marker: char) -> String {
+    let mapped: Option<String> = inner
+        .chars()
+        .map(|ch| {
+            table
+                .iter()
+                .find(|(from, _)| *from == ch)
+                .map(|(_, to)| *to)
+        })
+        .collect();
+    match mapped {
+        Some(mapped) if !mapped.is_empty() => mapped,
+        _ => format!("{marker}{{{inner}}}"),
+    }
+}
+
+/// Allow only web and mail links in rendered output.
+fn is_safe_url(href: &str) -> bool {
+    let href = href.trim().to_ascii_lowercase();
+    ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| href.starts_with(scheme))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '[' | ']' | '`' | '<' | '$') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABSTRACT: &str = r#"<jats:title>Abstract</jats:title>
+<jats:sec><jats:title>Background</jats:title>
+  <jats:p>We study <jats:italic>E. coli</jats:italic> in H<jats:sub>2</jats:sub>O &amp; CO<jats:sub>2</jats:sub>
+  at 10<jats:sup>3</jats:sup>&nbsp;K, where
+  <jats:inline-formula><mml:math><mml:msup><mml:mi>x</mml:mi><mml:mn>2</mml:mn></mml:msup></mml:math></jats:inline-formula> holds.</jats:p>
+</jats:sec>
+<jats:sec><jats:title>Results</jats:title>
+  <jats:p>See <jats:ext-link xlink:href="https://example.org/a">the data</jats:ext-link>
+  and <jats:ext-link xlink:href="javascript:alert(1)">this</jats:ext-link>:</jats:p>
+  <jats:list list-type="bullet"><jats:list-item><jats:p><jats:bold>fast</jats:bold></jats:p></jats:list-item><jats:list-item><jats:p>cheap</jats:p></jats:list-item></jats:list>
+  <jats:disp-formula><jats:tex-math>$$E = mc^2$$</jats:tex-math></jats:disp-formula>
+</jats:sec>"#;
+
+    #[test]
+    /// Renders sections, emphasis, scripts, links, lists and formulas in every format.
+    fn converts_abstract() {
+        let parsed = JatsAbstract::parse(ABSTRACT);
+        assert_eq!(
+            parsed.render(OutputFormat::Text),
+            "Background\n\n\
+             We study E. coli in H₂O & CO₂ at 10³\u{a0}K, where $x^2$ holds.\n\n\
+             Results\n\n\
+             See the data and this:\n\n\
+             - fast\n- cheap\n\n\
+             $$E = mc^2$$"
+        );
+        assert_eq!(
+            JatsAbstract::parse(
+                "<jats:p>T<jats:sub>c</jats:sub> of x<jats:sup>n-1</jats:sup></jats:p>"
+            )
+            .render(OutputFormat::Text),
+            "T_{c} of xⁿ⁻¹"
+        );
+        assert_eq!(
+            parsed.render(OutputFormat::Markdown),
+            "## Background\n\n\
+             We study *E. coli* in H<sub>2</sub>O & CO<sub>2</sub> at 10<sup>3</sup>\u{a0}K, where $x^2$ holds.\n\n\
+             ## Results\n\n\
+             See [the data](https://example.org/a) and this:\n\n\
+             - **fast**\n- cheap\n\n\
+             $$E = mc^2$$"
+        );
+        assert_eq!(
+            parsed.render(OutputFormat::Html),
+            "<h2>Background</h2>\n\
+             <p>We study <i>E. coli</i> in H<sub>2</sub>O &amp; CO<sub>2</sub> at 10<sup>3</sup>\u{a0}K, where <span class=\"math inline\">\\(x^2\\)</span> holds.</p>\n\
+             <h2>Results</h2>\n\
+             <p>See <a href=\"https://example.org/a\">the data</a> and this:</p>\n\
+             <ul><li><b>fast</b></li><li>cheap</li></ul>\n\
+             <p><span class=\"math display\">\\[E = mc^2\\]</span></p>"
+        );
+    }
+
+    #[test]
+    /// Handles plain text, loose inline markup and malformed XML.
+    fn handles_loose_and_broken_markup() {
+        assert_eq!(
+            JatsAbstract::parse("Plain <i>text</i> & <b>more</b>").render(OutputFormat::Html),
+            "<p>Plain text &amp; more</p>"
+        );
+        assert_eq!(
+            JatsAbstract::parse("Plain <jats:italic>text</jats:italic>")
+                .render(OutputFormat::Markdown),
+            "Plain *text*"
+        );
+        assert_eq!(
+            JatsAbstract::parse("<jats:p>One <script>x</script></jats:p><jats:p>Two</jats:p>")
+                .render(OutputFormat::Html),
+            "<p>One x</p>\n<p>Two</p>"
+        );
+        assert_eq!(JatsAbstract::parse("").blocks, []);
+    }
+}
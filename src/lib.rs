@@ -1,14 +1,79 @@
 //! DOI (Digital Object Identifier) parsing and normalization library
 
+pub mod bibtex;
+pub mod cache;
+pub mod citeproc;
+pub mod crossref;
 pub mod csl;
+pub mod datacite;
 pub mod doi_org;
+pub mod embed;
+mod http;
+pub mod jats;
 pub mod parse;
+pub mod ris;
+pub mod source;
+#[cfg(feature = "sqlite")]
+pub mod store;
 
+pub use bibtex::error::BibtexError;
+pub use bibtex::export::{BibtexOptions, default_citation_key, write_bibtex};
+pub use bibtex::import::{BibImport, parse_bibtex};
+pub use bibtex::key::{CitationKeyGenerator, DEFAULT_KEY_PATTERN};
+pub use bibtex::parse::{BibFile, BibWarning};
+pub use bibtex::{BibDialect, BibEntry, BibValue};
+pub use cache::client::CachedDoiOrgClient;
+pub use cache::config::CacheConfig;
+pub use cache::error::CacheError;
+pub use cache::fs::FsCacheStore;
+pub use cache::{CacheEntry, CacheKey, CacheStore};
+pub use citeproc::error::CiteprocError;
+pub use citeproc::locale::Locale;
+pub use citeproc::output::OutputFormat;
+pub use citeproc::style::{BundledStyle, Style, StyleClass};
+pub use citeproc::{Bibliography, BibliographyEntry, CitationItem, Processor};
+pub use crossref::client::CrossrefClient;
+pub use crossref::config::CrossrefConfig;
+pub use crossref::entity::{
+    CrossrefFunderRecord, CrossrefJournal, CrossrefMember, CrossrefPrefix, CrossrefType,
+};
+pub use crossref::error::CrossrefError;
+pub use crossref::query::{SortOrder, WorksFilter, WorksQuery, WorksSort, WorksStream};
+pub use crossref::unixsd::{UnixsdKind, UnixsdRecord};
+pub use crossref::work::{CrossrefResponse, CrossrefWork};
 pub use csl::*;
-pub use doi_org::client::DoiOrgClient;
+pub use datacite::client::DataCiteClient;
+pub use datacite::config::DataCiteConfig;
+pub use datacite::error::DataCiteError;
+pub use datacite::query::{DataCiteList, DataCiteQuery, DataCiteStream};
+pub use datacite::record::{DataCiteAttributes, DataCiteDoi, DataCiteResponse};
+pub use doi_org::agency::RegistrationAgency;
+pub use doi_org::batch::{BatchCancel, BatchOptions, BatchProgress, BatchStream};
+pub use doi_org::bibtex::DoiOrgBibtexSource;
+pub use doi_org::client::{DoiOrgClient, RawResponse};
+pub use doi_org::coalesce::CoalescingStats;
 pub use doi_org::config::DoiOrgConfig;
 pub use doi_org::error::DoiOrgError;
+pub use embed::jsonld::{json_ld_script, schema_type, to_json_ld};
+pub use embed::meta::{MetaTag, dcmi_type, dublin_core_tags, highwire_tags};
+pub use jats::{Block, Inline, InlineStyle, JatsAbstract};
 
 pub use parse::Doi;
 pub use parse::DoiParseError;
 pub use parse::extract_doi_from_url;
+pub use ris::export::write_ris;
+pub use ris::import::{RisImport, RisWarning, parse_ris};
+pub use source::error::SourceError;
+pub use source::memory::MemorySource;
+pub use source::merge::{
+    ConflictValue, FieldConflict, FieldProvenance, MergePolicy, MergeRules, MergedRecord,
+    merge_records,
+};
+pub use source::resolver::{
+    AgencyLookup, Attempt, AttemptOutcome, FallbackResolver, Resolution, ResolverSource,
+};
+pub use source::{MetadataSource, Record};
+#[cfg(feature = "sqlite")]
+pub use store::error::StoreError;
+#[cfg(feature = "sqlite")]
+pub use store::sqlite::SqliteStore;
@@ -34,6 +34,12 @@ impl Doi {
         self.value.as_str()
     }
 
+    /// Return the canonical form used for comparison: DOIs are case-insensitive,
+    /// so this is the lowercased DOI.
+    pub fn canonical(&self) -> String {
+        self.value.to_lowercase()
+    }
+
     /// Return the DOI prefix portion (e.g. "10.1000")
     pub fn prefix(&self) -> Option<&str> {
         let (prefix, _) = self.value.split_once('/')?;
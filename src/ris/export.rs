@@ -0,0 +1,218 @@
+use crate::csl::{CslDate, CslName, DoiMetadata};
+use crate::ris::ris_type;
+
+/// Write CSL items as RIS records with CRLF line endings.
+pub fn write_ris<'a>(items: impl IntoIterator<Item = &'a DoiMetadata>) -> String {
+    items.into_iter().map(write_record).collect()
+}
+
+fn write_record(metadata: &DoiMetadata) -> String {
+    let mut record = Record::default();
+    record.push("TY", ris_type(&metadata.item_type));
+    record.push_opt(
+        "ID",
+        metadata.citation_key.as_deref().or(metadata.id.as_deref()),
+    );
+    for name in &metadata.author {
+        record.push_opt("AU", format_name(name).as_deref());
+    }
+    for name in &metadata.editor {
+        record.push_opt("ED", format_name(name).as_deref());
+    }
+    for name in &metadata.collection_editor {
+        record.push_opt("A3", format_name(name).as_deref());
+    }
+    for name in &metadata.translator {
+        record.push_opt("A4", format_name(name).as_deref());
+    }
+    record.push_opt("TI", metadata.title.as_deref());
+    record.push_opt("T2", metadata.container_title.as_deref());
+    record.push_opt(
+        "J2",
+        metadata
+            .container_title_short
+            .as_deref()
+            .or(metadata.journal_abbreviation.as_deref()),
+    );
+    record.push_opt("T3", metadata.collection_title.as_deref());
+    if let Some(issued) = &metadata.issued {
+        record.push_opt("PY", issued.year().map(|year| year.to_string()).as_deref());
+        record.push_opt("DA", format_date(issued).as_deref());
+    }
+    record.push_opt(
+        "Y2",
+        metadata.accessed.as_ref().and_then(format_date).as_deref(),
+    );
+    record.push_opt(
+        "VL",
+        metadata.volume.as_ref().map(ToString::to_string).as_deref(),
+    );
+    record.push_opt(
+        "IS",
+        metadata.issue.as_ref().map(ToString::to_string).as_deref(),
+    );
+    if let Some(page) = &metadata.page {
+        let page = page.to_string();
+        match page.split_once(['-', '–']) {
+            Some((start, end)) if !page.contains(',') => {
+                record.push("SP", start.trim());
+                record.push("EP", end.trim());
+            }
+            _ => record.push("SP", &page),
+        }
+    }
+    for number in metadata.issn.iter().chain(&metadata.isbn) {
+        record.push("SN", number);
+    }
+    record.push_opt("PB", metadata.publisher.as_deref());
+    record.push_opt("CY", metadata.publisher_place.as_deref());
+    record.push_opt(
+        "ET",
+        metadata
+            .edition
+            .as_ref()
+            .map(ToString::to_string)
+            .as_deref(),
+    );
+    record.push_opt("DO", metadata.doi.as_deref());
+    record.push_opt("UR", metadata.url.as_deref());
+    record.push_opt("AB", metadata.abstract_text.as_deref());
+    if let Some(keywords) = &metadata.keyword {
+        for keyword in keywords.split([',', ';']) {
+            record.push("KW", keyword.trim());
+        }
+    }
+    record.push_opt("LA", metadata.language.as_deref());
+    record.push_opt("N1", metadata.note.as_deref());
+    record.0.push_str("ER  - \r\n");
+    record.0
+}
+
+/// Format a name as `Family, Given, Suffix`, or the literal name.
+fn format_name(name: &CslName) -> Option<String> {
+    let Some(family) = name
+        .family_with_particle()
+        .filter(|family| !family.is_empty())
+    else {
+        return name.literal.clone().or(name.given.clone());
+    };
+    let parts: Vec<&str> = [
+        Some(family.as_str()),
+        name.given.as_deref(),
+        name.suffix.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    Some(parts.join(", "))
+}
+
+/// Format a date as `YYYY/MM/DD/`, leaving unknown parts empty.
+fn format_date(date: &CslDate) -> Option<String> {
+    let Some(start) = date.start() else {
+        return date.literal.clone();
+    };
+    let part = |value: Option<u8>| value.map(|value| format!("{value:02}")).unwrap_or_default();
+    Some(format!(
+        "{:04}/{}/{}/",
+        start.year(),
+        part(start.month()),
+        part(start.day())
+    ))
+}
+
+#[derive(Default)]
+struct Record(String);
+
+impl Record {
+    /// Append one tag; line breaks inside the value become continuation lines.
+    fn push(&mut self, tag: &str, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        let value = value
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        self.0.push_str(&format!("{tag}  - {value}\r\n"));
+    }
+
+    fn push_opt(&mut self, tag: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.push(tag, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::{CslItemType, CslValue, DateParts};
+    use crate::ris::import::parse_ris;
+
+    #[test]
+    /// Writes tags in order and reads back the same item.
+    fn round_trips_with_reader() {
+        let item = DoiMetadata {
+            item_type: CslItemType::ArticleJournal,
+            author: vec![
+                CslName::new("Smith", "John"),
+                CslName::literal("The Consortium"),
+            ],
+            title: Some("A study".to_string()),
+            container_title: Some("Journal of Studies".to_string()),
+            issued: Some(CslDate::new(DateParts::year_month(2021, 4))),
+            volume: Some(CslValue::Number(7)),
+            page: Some(CslValue::String("10-20".to_string())),
+            doi: Some("10.1000/abc".to_string()),
+            abstract_text: Some("Line one.\nLine two.".to_string()),
+            keyword: Some("alpha, beta".to_string()),
+            ..Default::default()
+        };
+        let ris = write_ris([&item]);
+        assert_eq!(
+            ris,
+            "TY  - JOUR\r\n\
+             AU  - Smith, John\r\n\
+             AU  - The Consortium\r\n\
+             TI  - A study\r\n\
+             T2  - Journal of Studies\r\n\
+             PY  - 2021\r\n\
+             DA  - 2021/04//\r\n\
+             VL  - 7\r\n\
+             SP  - 10\r\n\
+             EP  - 20\r\n\
+             DO  - 10.1000/abc\r\n\
+             AB  - Line one.\r\n\
+             Line two.\r\n\
+             KW  - alpha\r\n\
+             KW  - beta\r\n\
+             ER  - \r\n"
+        );
+
+        let import = parse_ris(&ris);
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let back = &import.items[0];
+        assert_eq!(back.author[0], item.author[0]);
+        assert_eq!(back.title, item.title);
+        assert_eq!(back.issued, item.issued);
+        assert_eq!(back.volume, Some(CslValue::String("7".to_string())));
+        assert_eq!(back.page, item.page);
+        assert_eq!(back.abstract_text, item.abstract_text);
+        assert_eq!(back.keyword, item.keyword);
+    }
+
+    #[test]
+    /// Keeps a page list with several ranges in SP.
+    fn keeps_page_lists_whole() {
+        let item = DoiMetadata {
+            page: Some(CslValue::String("10-20, 30-40".to_string())),
+            ..Default::default()
+        };
+        let ris = write_ris([&item]);
+        assert!(ris.contains("SP  - 10-20, 30-40\r\n"), "{ris}");
+        assert!(!ris.contains("EP  - "), "{ris}");
+    }
+}
@@ -0,0 +1,440 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::Doi;
+use crate::csl::{CslDate, CslItemType, CslName, CslValue, DateParts, DoiMetadata};
+use crate::ris::csl_type;
+
+/// `TY  - JOUR`, also accepting the single-space and missing-value variants
+/// some exporters write.
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Z][A-Z0-9])\s{1,2}-(?:\s(.*))?$").unwrap());
+
+/// A problem found while reading a RIS file; reading continues after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RisWarning {
+    /// 1-based line where the problem was found.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RisWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// CSL items read from a RIS file, with everything that needed attention.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RisImport {
+    pub items: Vec<DoiMetadata>,
+    pub warnings: Vec<RisWarning>,
+}
+
+/// The tags of one record in file order; continuation lines are already joined.
+#[derive(Debug, Default)]
+struct Record {
+    line: usize,
+    tags: Vec<(String, String)>,
+}
+
+impl Record {
+    fn all<'a>(&'a self, names: &'a [&str]) -> impl Iterator<Item = &'a str> + 'a {
+        self.tags
+            .iter()
+            .filter(move |(tag, _)| names.contains(&tag.as_str()))
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Return the first non-empty value among tags, in order of preference.
+    fn first(&self, names: &[&str]) -> Option<String> {
+        names
+            .iter()
+            .find_map(|name| {
+                self.tags
+                    .iter()
+                    .find(|(tag, value)| tag == name && !value.is_empty())
+            })
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Parse a RIS file into CSL items.
+pub fn parse_ris(input: &str) -> RisImport {
+    let mut warnings = Vec::new();
+    let mut records = Vec::new();
+    let mut current: Option<Record> = None;
+    let input = input.trim_start_matches('\u{feff}');
+
+    for (index, raw) in input.lines().enumerate() {
+        let line = index + 1;
+        let text = raw.trim_end();
+        let Some(captures) = TAG.captures(text) else {
+            if text.trim().is_empty() {
+                continue;
+            }
+            // Lines without a tag continue the previous field.
+            match current.as_mut().and_then(|record| record.tags.last_mut()) {
+                Some((tag, value)) => {
+                    let separator = if matches!(tag.as_str(), "AB" | "N2" | "N1") {
+                        "\n"
+                    } else {
+                        " "
+                    };
+                    if !value.is_empty() {
+                        value.push_str(separator);
+                    }
+                    value.push_str(text.trim());
+                }
+                None => warnings.push(RisWarning {
+                    line,
+                    message: "text outside a record".to_string(),
+                }),
+            }
+            continue;
+        };
+        let tag = captures[1].to_string();
+        let value = captures.get(2).map_or("", |value| value.as_str()).trim();
+        match tag.as_str() {
+            "TY" => {
+                if let Some(open) = current.take() {
+                    warnings.push(RisWarning {
+                        line: open.line,
+                        message: "record without `ER` tag".to_string(),
+                    });
+                    records.push(open);
+                }
+                current = Some(Record {
+                    line,
+                    tags: vec![(tag, value.to_string())],
+                });
+            }
+            "ER" => match current.take() {
+                Some(record) => records.push(record),
+                None => warnings.push(RisWarning {
+                    line,
+                    message: "`ER` without a record".to_string(),
+                }),
+            },
+            _ => match current.as_mut() {
+                Some(record) => record.tags.push((tag, value.to_string())),
+                None => warnings.push(RisWarning {
+                    line,
+                    message: format!("`{tag}` outside a record"),
+                }),
+            },
+        }
+    }
+    if let Some(open) = current {
+        warnings.push(RisWarning {
+            line: open.line,
+            message: "record without `ER` tag".to_string(),
+        });
+        records.push(open);
+    }
+
+    let items = records
+        .iter()
+        .map(|record| convert(record, &mut warnings))
+        .collect();
+    warnings.sort_by_key(|warning| warning.line);
+    RisImport { items, warnings }
+}
+
+/// Tags read into CSL variables; anything else is kept in `custom`.
+const KNOWN: &[&str] = &[
+    "TY", "AU", "A1", "A2", "A3", "A4", "ED", "TI", "T1", "CT", "BT", "T2", "JO", "JF", "JA", "J2",
+    "T3", "PY", "Y1", "DA", "Y2", "VL", "IS", "CP", "SP", "EP", "DO", "UR", "L1", "L2", "AB", "N2",
+    "KW", "SN", "PB", "CY", "PP", "ET", "LA", "N1", "ID", "M3",
+];
+
+fn convert(record: &Record, warnings: &mut Vec<RisWarning>) -> DoiMetadata {
+    let mut warn = |message: String| {
+        warnings.push(RisWarning {
+            line: record.line,
+            message,
+        })
+    };
+    let ris_type = record.first(&["TY"]).unwrap_or_default();
+    let item_type = csl_type(&ris_type).unwrap_or_else(|| {
+        warn(format!("unknown reference type `{ris_type}`"));
+        CslItemType::Document
+    });
+    let names = |tags: &[&str]| -> Vec<CslName> { record.all(tags).map(CslName::parse).collect() };
+    let number = |tags: &[&str]| record.first(tags).map(CslValue::String);
+
+    let mut metadata = DoiMetadata {
+        item_type,
+        id: record.first(&["ID"]),
+        author: names(&["AU", "A1"]),
+        editor: names(&["ED", "A2"]),
+        collection_editor: names(&["A3"]),
+        translator: names(&["A4"]),
+        volume: number(&["VL"]),
+        issue: number(&["IS", "CP"]),
+        edition: number(&["ET"]),
+        publisher: record.first(&["PB"]),
+        publisher_place: record.first(&["CY", "PP"]),
+        language: record.first(&["LA"]),
+        note: record.first(&["N1"]),
+        ..Default::default()
+    };
+    metadata.citation_key = metadata.id.clone();
+
+    // Books put their own title in BT when TI is missing; everything else uses BT,
+    // T2, JO or JF for the container.
+    let is_book = matches!(metadata.item_type, CslItemType::Book);
+    metadata.title = record
+        .first(&["TI", "T1", "CT"])
+        .or_else(|| is_book.then(|| record.first(&["BT"])).flatten());
+    metadata.container_title = record
+        .first(&["T2", "JF", "JO", "BT"])
+        .filter(|container| !is_book || metadata.title.as_deref() != Some(container.as_str()));
+    metadata.container_title_short = record.first(&["J2", "JA"]);
+    metadata.collection_title = record.first(&["T3"]);
+
+    // DA, PY and Y1 can each hold the date, often at different precision
+    // (PY is frequently just the year), so keep the most complete one and
+    // prefer DA, then PY, on ties.
+    let dates: Vec<String> = ["DA", "PY", "Y1"]
+        .iter()
+        .filter_map(|tag| record.first(&[tag]))
+        .collect();
+    let mut parsed = Vec::new();
+    for value in &dates {
+        match parse_date(value) {
+            Some(date) => parsed.push(date),
+            None => warn(format!("unrecognized date `{value}`")),
+        }
+    }
+    metadata.issued = parsed
+        .into_iter()
+        .min_by_key(|date| Reverse(date.start().map(|parts| parts.precision())))
+        .or_else(|| dates.first().map(|value| CslDate::literal(value)));
+    metadata.accessed = record.first(&["Y2"]).and_then(|value| parse_date(&value));
+
+    metadata.page = match (record.first(&["SP"]), record.first(&["EP"])) {
+        (Some(start), Some(end)) if start != end && !start.contains(['-', '–']) => {
+            Some(CslValue::String(format!("{start}-{end}")))
+        }
+        (Some(start), _) => Some(CslValue::String(start)),
+        (None, Some(end)) => Some(CslValue::String(end)),
+        (None, None) => None,
+    };
+
+    metadata.doi = record
+        .first(&["DO"])
+        .or_else(|| {
+            record
+                .first(&["M3"])
+                .filter(|value| value.starts_with("10."))
+        })
+        .map(|doi| clean_doi(&doi));
+    metadata.url = record.first(&["UR", "L2", "L1"]);
+    metadata.abstract_text = record.first(&["AB", "N2"]);
+    let keywords: Vec<&str> = record.all(&["KW"]).collect();
+    if !keywords.is_empty() {
+        metadata.keyword = Some(keywords.join(", "));
+    }
+    for value in record.all(&["SN"]) {
+        for number in value
+            .split([';', ','])
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            let digits = number.chars().filter(char::is_ascii_alphanumeric).count();
+            if digits == 8 {
+                metadata.issn.push(number.to_string());
+            } else {
+                metadata.isbn.push(number.to_string());
+            }
+        }
+    }
+
+    let mut custom: BTreeMap<String, Value> = BTreeMap::new();
+    for (tag, value) in &record.tags {
+        if KNOWN.contains(&tag.as_str()) || value.is_empty() {
+            continue;
+        }
+        custom
+            .entry(tag.clone())
+            .and_modify(|existing| {
+                if let Value::String(existing) = existing {
+                    existing.push('\n');
+                    existing.push_str(value);
+                }
+            })
+            .or_insert_with(|| Value::String(value.clone()));
+    }
+    metadata.custom = custom;
+    metadata
+}
+
+/// Strip resolver prefixes from a DOI, keeping unparseable values as written.
+fn clean_doi(value: &str) -> String {
+    match Doi::parse(value) {
+        Ok(doi) => doi.as_str().to_string(),
+        Err(_) => value.trim().to_string(),
+    }
+}
+
+/// Parse `2020`, `2020/03/15/`, `2020/03//Spring` or `2020-03-15`.
+fn parse_date(value: &str) -> Option<CslDate> {
+    let value = value.trim();
+    if value.contains('-') && !value.contains('/') {
+        return DateParts::parse(value).map(CslDate::new);
+    }
+    let mut parts = value.split('/').map(str::trim);
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month = parts.next().and_then(|month| month.parse::<u8>().ok());
+    let day = parts.next().and_then(|day| day.parse::<u8>().ok());
+    let parts = match (month.filter(|month| (1..=12).contains(month)), day) {
+        (Some(month), Some(day)) if (1..=31).contains(&day) => DateParts::ymd(year, month, day),
+        (Some(month), _) => DateParts::year_month(year, month),
+        _ => DateParts::year_only(year),
+    };
+    Some(CslDate::new(parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Reads repeated tags, continuation lines, vendor variants and unknown tags.
+    fn parses_records() {
+        let input = "\u{feff}TY  - JOUR\r\n\
+            AU  - Smith, John A.\r\n\
+            AU  - Jones, Kate\r\n\
+            TI  - A long title that wraps\r\n\
+            onto a second line\r\n\
+            T2  - Journal of Studies\r\n\
+            J2  - J. Stud.\r\n\
+            PY  - 2020\r\n\
+            DA  - 2020/03/15/\r\n\
+            VL  - 12\r\n\
+            IS  - 3\r\n\
+            SP  - 45\r\n\
+            EP  - 67\r\n\
+            DO  - https://doi.org/10.1000/XYZ\r\n\
+            KW  - alpha\r\n\
+            KW  - beta\r\n\
+            SN  - 1234-5678\r\n\
+            AB  - First paragraph.\r\n\
+            Second paragraph.\r\n\
+            C1  - custom value\r\n\
+            ER  - \r\n\
+            \r\n\
+            TY - BOOK\n\
+            A1 - Doe, Jane\n\
+            BT - A Book\n\
+            Y1 - 1999///\n\
+            SN - 978-3-16-148410-0\n\
+            ER -\n";
+        let import = parse_ris(input);
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        let [article, book] = import.items.as_slice() else {
+            panic!("expected two records");
+        };
+        assert_eq!(article.item_type, CslItemType::ArticleJournal);
+        assert_eq!(article.author.len(), 2);
+        assert_eq!(article.author[0].given.as_deref(), Some("John A."));
+        assert_eq!(
+            article.title.as_deref(),
+            Some("A long title that wraps onto a second line")
+        );
+        assert_eq!(article.container_title_short.as_deref(), Some("J. Stud."));
+        assert_eq!(
+            article.issued.as_ref().and_then(CslDate::start),
+            Some(DateParts::ymd(2020, 3, 15))
+        );
+        assert_eq!(article.page, Some(CslValue::String("45-67".to_string())));
+        assert_eq!(article.doi.as_deref(), Some("10.1000/XYZ"));
+        assert_eq!(article.keyword.as_deref(), Some("alpha, beta"));
+        assert_eq!(article.issn, ["1234-5678"]);
+        assert_eq!(
+            article.abstract_text.as_deref(),
+            Some("First paragraph.\nSecond paragraph.")
+        );
+        assert_eq!(article.custom.get("C1"), Some(&Value::from("custom value")));
+
+        assert_eq!(book.item_type, CslItemType::Book);
+        assert_eq!(book.title.as_deref(), Some("A Book"));
+        assert_eq!(book.container_title, None);
+        assert_eq!(book.isbn, ["978-3-16-148410-0"]);
+        assert_eq!(
+            book.issued.as_ref().and_then(CslDate::start),
+            Some(DateParts::year_only(1999))
+        );
+    }
+
+    #[test]
+    /// Keeps the most precise of DA, PY and Y1 whichever tag holds it.
+    fn prefers_most_precise_date() {
+        let issued = |tags: &str| {
+            let import = parse_ris(&format!("TY  - JOUR\n{tags}ER  - \n"));
+            let item = &import.items[0];
+            (item.issued.clone(), import.warnings.len())
+        };
+        assert_eq!(
+            issued("PY  - 2020/03/15/\nDA  - 2020/03\n"),
+            (Some(CslDate::new(DateParts::ymd(2020, 3, 15))), 0)
+        );
+        assert_eq!(
+            issued("Y1  - 2020/03\nPY  - 2020\n"),
+            (Some(CslDate::new(DateParts::year_month(2020, 3))), 0)
+        );
+        assert_eq!(
+            issued("DA  - 2020/04\nPY  - 2020/05\n"),
+            (Some(CslDate::new(DateParts::year_month(2020, 4))), 0)
+        );
+        assert_eq!(
+            issued("DA  - Spring\nPY  - 2021\n"),
+            (Some(CslDate::new(DateParts::year_only(2021))), 1)
+        );
+        assert_eq!(
+            issued("DA  - in press\n"),
+            (Some(CslDate::literal("in press")), 1)
+        );
+    }
+
+    #[test]
+    /// Reports stray lines, unknown types and unterminated records.
+    fn warns_and_recovers() {
+        let input = "stray\nTY  - WHAT\nTI  - One\nTY  - JOUR\nTI  - Two\n";
+        let import = parse_ris(input);
+        let messages: Vec<String> = import.warnings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "line 1: text outside a record",
+                "line 2: record without `ER` tag",
+                "line 2: unknown reference type `WHAT`",
+                "line 4: record without `ER` tag",
+            ]
+        );
+        assert_eq!(import.items.len(), 2);
+        assert_eq!(import.items[1].title.as_deref(), Some("Two"));
+    }
+
+    #[test]
+    /// Strips resolver prefixes from DO and keeps unparseable values.
+    fn cleans_dois() {
+        let doi = |value: &str| {
+            let import = parse_ris(&format!("TY  - JOUR\nDO  - {value}\nER  - \n"));
+            import.items[0].doi.clone()
+        };
+        assert_eq!(
+            doi("https://dx.doi.org/10.1000/ABC").as_deref(),
+            Some("10.1000/ABC")
+        );
+        assert_eq!(doi("doi:10.1000/abc").as_deref(), Some("10.1000/abc"));
+        assert_eq!(doi("not a doi").as_deref(), Some("not a doi"));
+    }
+}
@@ -0,0 +1,79 @@
+//! RIS import and export.
+//!
+//! [`parse_ris`](import::parse_ris) reads RIS files written by EndNote, Zotero,
+//! Mendeley and others into CSL items, and [`write_ris`](export::write_ris)
+//! writes them back out.
+
+pub mod export;
+pub mod import;
+
+use crate::csl::CslItemType;
+
+/// RIS reference types and the CSL types they map to when read.
+const TYPES: &[(&str, CslItemType)] = &[
+    ("JOUR", CslItemType::ArticleJournal),
+    ("JFULL", CslItemType::ArticleJournal),
+    ("EJOUR", CslItemType::ArticleJournal),
+    ("INPR", CslItemType::ArticleJournal),
+    ("ABST", CslItemType::ArticleJournal),
+    ("MGZN", CslItemType::ArticleMagazine),
+    ("NEWS", CslItemType::ArticleNewspaper),
+    ("BOOK", CslItemType::Book),
+    ("EBOOK", CslItemType::Book),
+    ("EDBOOK", CslItemType::Book),
+    ("SER", CslItemType::Book),
+    ("CHAP", CslItemType::Chapter),
+    ("ECHAP", CslItemType::Chapter),
+    ("CONF", CslItemType::PaperConference),
+    ("CPAPER", CslItemType::PaperConference),
+    ("RPRT", CslItemType::Report),
+    ("THES", CslItemType::Thesis),
+    ("DATA", CslItemType::Dataset),
+    ("COMP", CslItemType::Software),
+    ("ELEC", CslItemType::Webpage),
+    ("WEB", CslItemType::Webpage),
+    ("BLOG", CslItemType::PostWeblog),
+    ("ENCYC", CslItemType::EntryEncyclopedia),
+    ("DICT", CslItemType::EntryDictionary),
+    ("PAT", CslItemType::Patent),
+    ("STAND", CslItemType::Standard),
+    ("UNPB", CslItemType::Manuscript),
+    ("MANSCPT", CslItemType::Manuscript),
+    ("PAMP", CslItemType::Pamphlet),
+    ("MAP", CslItemType::Map),
+    ("CASE", CslItemType::LegalCase),
+    ("BILL", CslItemType::Bill),
+    ("STAT", CslItemType::Legislation),
+    ("HEAR", CslItemType::Hearing),
+    ("PCOMM", CslItemType::PersonalCommunication),
+    ("VIDEO", CslItemType::MotionPicture),
+    ("MPCT", CslItemType::MotionPicture),
+    ("ART", CslItemType::Graphic),
+    ("FIGURE", CslItemType::Figure),
+    ("SOUND", CslItemType::Song),
+    ("MUSIC", CslItemType::MusicalScore),
+    ("GEN", CslItemType::Document),
+];
+
+/// Return the CSL type of a RIS reference type.
+fn csl_type(ris: &str) -> Option<CslItemType> {
+    TYPES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(ris))
+        .map(|(_, item_type)| item_type.clone())
+}
+
+/// Return the RIS reference type written for a CSL type.
+fn ris_type(item_type: &CslItemType) -> &'static str {
+    match item_type {
+        CslItemType::Article | CslItemType::Review | CslItemType::ReviewBook => "JOUR",
+        CslItemType::Collection | CslItemType::Classic => "BOOK",
+        CslItemType::Entry => "ENCYC",
+        CslItemType::Post => "BLOG",
+        CslItemType::Speech | CslItemType::Event | CslItemType::Performance => "CONF",
+        other => TYPES
+            .iter()
+            .find(|(_, candidate)| candidate == other)
+            .map_or("GEN", |(name, _)| name),
+    }
+}
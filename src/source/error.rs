@@ -0,0 +1,90 @@
+use snafu::Snafu;
+
+use crate::cache::error::CacheError;
+use crate::crossref::error::CrossrefError;
+use crate::datacite::error::DataCiteError;
+use crate::doi_org::error::DoiOrgError;
+use crate::source::resolver::Attempt;
+
+/// Errors returned by metadata sources.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum SourceError {
+    #[snafu(display("DOI not found in {source_name}: {doi}"))]
+    NotFound { source_name: String, doi: String },
+
+    #[snafu(display("{source_name} cannot return {accept} for {doi}"))]
+    NotAcceptable {
+        source_name: String,
+        doi: String,
+        accept: String,
+    },
+
+    #[snafu(display("doi.org source failed at {stage}: {source}"))]
+    DoiOrg {
+        stage: &'static str,
+        source: DoiOrgError,
+    },
+
+    #[snafu(display("Crossref source failed at {stage}: {source}"))]
+    Crossref {
+        stage: &'static str,
+        source: CrossrefError,
+    },
+
+    #[snafu(display("DataCite source failed at {stage}: {source}"))]
+    DataCite {
+        stage: &'static str,
+        source: DataCiteError,
+    },
+
+    #[snafu(display("Cached doi.org source failed at {stage}: {source}"))]
+    Cache {
+        stage: &'static str,
+        source: CacheError,
+    },
+
+    #[cfg(feature = "sqlite")]
+    #[snafu(display("SQLite store failed at {stage}: {source}"))]
+    Store {
+        stage: &'static str,
+        source: crate::store::error::StoreError,
+    },
+
+    #[snafu(display("Failed to merge records at {stage}: {source}"))]
+    Merge {
+        stage: &'static str,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("No source resolved {doi}: {}", summarize(attempts)))]
+    Exhausted { doi: String, attempts: Vec<Attempt> },
+
+    #[snafu(display("Source {source_name} failed at {stage}: {message}"))]
+    Other {
+        stage: &'static str,
+        source_name: String,
+        message: String,
+    },
+}
+
+impl SourceError {
+    /// Return true when the source answered that it does not know the DOI.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, SourceError::NotFound { .. })
+    }
+
+    /// Return true when the source cannot serve the DOI in its format (HTTP 406).
+    pub fn is_not_acceptable(&self) -> bool {
+        matches!(self, SourceError::NotAcceptable { .. })
+    }
+}
+
+/// Join attempts into one line for error messages.
+fn summarize(attempts: &[Attempt]) -> String {
+    attempts
+        .iter()
+        .map(Attempt::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
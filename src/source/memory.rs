@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use crate::Doi;
+use crate::csl::DoiMetadata;
+use crate::source::error::*;
+use crate::source::{MetadataSource, Record};
+
+/// An in-memory source, useful as a test double or a fixed overlay.
+///
+/// DOIs are matched case-insensitively.
+#[derive(Debug, Default)]
+pub struct MemorySource {
+    name: String,
+    records: RwLock<HashMap<String, DoiMetadata>>,
+}
+
+impl MemorySource {
+    /// Build an empty source with a name.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            records: RwLock::default(),
+        }
+    }
+
+    /// Add metadata for a DOI, builder style.
+    pub fn with(self, doi: &Doi, metadata: DoiMetadata) -> Self {
+        self.insert(doi, metadata);
+        self
+    }
+
+    /// Add or replace metadata for a DOI.
+    pub fn insert(&self, doi: &Doi, metadata: DoiMetadata) {
+        if let Ok(mut records) = self.records.write() {
+            records.insert(doi.canonical(), metadata);
+        }
+    }
+
+    /// Remove metadata for a DOI.
+    pub fn remove(&self, doi: &Doi) -> Option<DoiMetadata> {
+        self.records.write().ok()?.remove(&doi.canonical())
+    }
+}
+
+#[async_trait]
+impl MetadataSource for MemorySource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        let metadata = self
+            .records
+            .read()
+            .ok()
+            .and_then(|records| records.get(&doi.canonical()).cloned());
+        match metadata {
+            Some(metadata) => Ok(Record::new(metadata, &self.name)),
+            None => NotFoundSnafu {
+                source_name: self.name.clone(),
+                doi: doi.as_str().to_string(),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    /// Returns stored metadata case-insensitively through a trait object.
+    async fn memory_source_lookup() {
+        let doi = Doi::parse("10.5555/ABC").unwrap();
+        let metadata = DoiMetadata {
+            title: Some("Stored".to_string()),
+            ..Default::default()
+        };
+        let source: Arc<dyn MetadataSource> =
+            Arc::new(MemorySource::new("memory").with(&doi, metadata));
+
+        let lower = Doi::parse("10.5555/abc").unwrap();
+        let record = source.metadata(&lower).await.unwrap();
+        assert_eq!(record.source, "memory");
+        assert_eq!(record.metadata.title.as_deref(), Some("Stored"));
+
+        let missing = Doi::parse("10.5555/missing").unwrap();
+        let error = source.metadata(&missing).await.unwrap_err();
+        assert!(error.is_not_found());
+    }
+}
@@ -0,0 +1,373 @@
+//! Field-by-field merging of records from several sources.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use snafu::ResultExt;
+
+use crate::csl::DoiMetadata;
+use crate::source::Record;
+use crate::source::error::*;
+
+/// How a field is chosen among the candidate records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergePolicy {
+    /// Take the value from the highest-precedence source that has one.
+    FirstNonEmpty,
+    /// Take the value from the most recently fetched record.
+    MostRecent,
+    /// Take the longest value (by serialized length), e.g. for abstracts.
+    Longest,
+    /// Concatenate array values without duplicates, in precedence order.
+    Union,
+}
+
+/// Precedence and per-field policies for [`merge_records`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeRules {
+    precedence: Vec<String>,
+    default_policy: MergePolicy,
+    fields: BTreeMap<String, MergePolicy>,
+}
+
+impl Default for MergeRules {
+    /// Input order, first non-empty value, longest abstract and merged subjects.
+    fn default() -> Self {
+        Self::new()
+            .field("abstract", MergePolicy::Longest)
+            .field("subject", MergePolicy::Union)
+            .field("categories", MergePolicy::Union)
+    }
+}
+
+impl MergeRules {
+    /// Build rules with no field overrides, using input order as precedence.
+    pub fn new() -> Self {
+        Self {
+            precedence: Vec::new(),
+            default_policy: MergePolicy::FirstNonEmpty,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    /// Rank sources by name, highest first; unlisted sources follow in input order.
+    pub fn prefer(mut self, sources: &[&str]) -> Self {
+        self.precedence = sources.iter().map(|source| source.to_string()).collect();
+        self
+    }
+
+    /// Set the policy for fields without an override.
+    pub fn default_policy(mut self, policy: MergePolicy) -> Self {
+        self.default_policy = policy;
+        self
+    }
+
+    /// Override the policy of one CSL field, by its CSL-JSON name (e.g. `issued`).
+    pub fn field(mut self, field: &str, policy: MergePolicy) -> Self {
+        self.fields.insert(field.to_string(), policy);
+        self
+    }
+
+    /// Return the policy applied to a field.
+    pub fn policy(&self, field: &str) -> MergePolicy {
+        self.fields
+            .get(field)
+            .copied()
+            .unwrap_or(self.default_policy)
+    }
+
+    /// Return the precedence rank of a source (lower wins).
+    fn rank(&self, source: &str) -> usize {
+        self.precedence
+            .iter()
+            .position(|name| name == source)
+            .unwrap_or(self.precedence.len())
+    }
+}
+
+/// Where a merged field value came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FieldProvenance {
+    /// Name of the source.
+    pub source: String,
+    /// When that source's record was fetched.
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A value offered by one source for a conflicting field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictValue {
+    /// Name of the source.
+    pub source: String,
+    /// The value it offered.
+    pub value: Value,
+}
+
+/// A field on which sources disagreed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldConflict {
+    /// CSL-JSON field name.
+    pub field: String,
+    /// The policy used to settle it.
+    pub policy: MergePolicy,
+    /// Every distinct value, in precedence order.
+    pub values: Vec<ConflictValue>,
+}
+
+/// A best-of record with its audit trail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MergedRecord {
+    /// The merged metadata.
+    pub metadata: DoiMetadata,
+    /// The sources of each field, keyed by CSL-JSON field name.
+    pub provenance: BTreeMap<String, Vec<FieldProvenance>>,
+    /// Fields whose sources disagreed.
+    pub conflicts: Vec<FieldConflict>,
+}
+
+/// One candidate value during merging.
+struct Candidate<'a> {
+    record: &'a Record,
+    value: &'a Value,
+}
+
+/// Merge records for the same DOI field by field.
+///
+/// Strings are compared trimmed and case-insensitively when detecting conflicts.
+pub fn merge_records(records: &[Record], rules: &MergeRules) -> Result<MergedRecord, SourceError> {
+    let mut ordered: Vec<&Record> = records.iter().collect();
+    ordered.sort_by_key(|record| rules.rank(&record.source));
+
+    let mut objects = Vec::with_capacity(ordered.len());
+    for record in &ordered {
+        let value = serde_json::to_value(&record.metadata).context(MergeSnafu {
+            stage: "serialize-record",
+        })?;
+        if let Value::Object(object) = value {
+            objects.push((*record, object));
+        }
+    }
+
+    let mut fields: Vec<&String> = objects
+        .iter()
+        .flat_map(|(_, object)| object.keys())
+        .collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut merged = Map::new();
+    let mut provenance = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    for field in fields {
+        let candidates: Vec<Candidate<'_>> = objects
+            .iter()
+            .filter_map(|(record, object)| {
+                let value = object.get(field).filter(|value| !is_empty(value))?;
+                Some(Candidate { record, value })
+            })
+            .collect();
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let policy = rules.policy(field);
+        let (value, chosen) = choose(policy, &candidates);
+        provenance.insert(
+            field.clone(),
+            chosen
+                .iter()
+                .map(|candidate| FieldProvenance {
+                    source: candidate.record.source.clone(),
+                    fetched_at: candidate.record.fetched_at,
+                })
+                .collect(),
+        );
+        merged.insert(field.clone(), value);
+
+        let mut values: Vec<ConflictValue> = Vec::new();
+        for candidate in &candidates {
+            let normalized = normalize(candidate.value);
+            if !values
+                .iter()
+                .any(|seen| normalize(&seen.value) == normalized)
+            {
+                values.push(ConflictValue {
+                    source: candidate.record.source.clone(),
+                    value: candidate.value.clone(),
+                });
+            }
+        }
+        if values.len() > 1 && policy != MergePolicy::Union {
+            conflicts.push(FieldConflict {
+                field: field.clone(),
+                policy,
+                values,
+            });
+        }
+    }
+
+    let metadata = serde_json::from_value(Value::Object(merged)).context(MergeSnafu {
+        stage: "deserialize-merged",
+    })?;
+    Ok(MergedRecord {
+        metadata,
+        provenance,
+        conflicts,
+    })
+}
+
+/// Pick a value and the candidates that contributed to it.
+fn choose<'a, 'b>(
+    policy: MergePolicy,
+    candidates: &'b [Candidate<'a>],
+) -> (Value, Vec<&'b Candidate<'a>>) {
+    let single = |candidate: &'b Candidate<'a>| (candidate.value.clone(), vec![candidate]);
+    match policy {
+        MergePolicy::FirstNonEmpty => single(&candidates[0]),
+        MergePolicy::MostRecent => {
+            // `max_by_key` keeps the last maximum; iterate in reverse so ties go to precedence.
+            let newest = candidates
+                .iter()
+                .rev()
+                .max_by_key(|candidate| candidate.record.fetched_at)
+                .unwrap_or(&candidates[0]);
+            single(newest)
+        }
+        MergePolicy::Longest => {
+            let longest = candidates
+                .iter()
+                .rev()
+                .max_by_key(|candidate| value_len(candidate.value))
+                .unwrap_or(&candidates[0]);
+            single(longest)
+        }
+        MergePolicy::Union => {
+            if !candidates
+                .iter()
+                .all(|candidate| candidate.value.is_array())
+            {
+                return single(&candidates[0]);
+            }
+            let mut items: Vec<Value> = Vec::new();
+            let mut contributors = Vec::new();
+            for candidate in candidates {
+                let mut contributed = false;
+                for item in candidate.value.as_array().into_iter().flatten() {
+                    let normalized = normalize(item);
+                    if !items.iter().any(|seen| normalize(seen) == normalized) {
+                        items.push(item.clone());
+                        contributed = true;
+                    }
+                }
+                if contributed {
+                    contributors.push(candidate);
+                }
+            }
+            (Value::Array(items), contributors)
+        }
+    }
+}
+
+/// Return true for null, blank strings and empty arrays or objects.
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+/// Length used by [`MergePolicy::Longest`].
+fn value_len(value: &Value) -> usize {
+    match value {
+        Value::String(text) => text.chars().count(),
+        Value::Array(items) => items.len(),
+        other => other.to_string().len(),
+    }
+}
+
+/// Lowercase and trim strings recursively for comparison.
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::String(text) => Value::String(
+            text.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), normalize(value)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(source: &str, day: u32, json: &str) -> Record {
+        Record {
+            metadata: serde_json::from_str(json).unwrap(),
+            source: source.to_string(),
+            fetched_at: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    /// Merges by precedence, keeps provenance and reports conflicting fields.
+    fn merges_with_provenance_and_conflicts() {
+        let records = [
+            record(
+                "doi.org",
+                3,
+                r#"{"type":"article-journal","title":"A Study","abstract":"Short.",
+                "subject":["Biology"],"issued":{"date-parts":[[2020]]}}"#,
+            ),
+            record(
+                "crossref",
+                1,
+                r#"{"type":"article-journal","title":"A  study","page":"1-10",
+                "abstract":"A much longer abstract.","subject":["biology","Ecology"],
+                "issued":{"date-parts":[[2019]]}}"#,
+            ),
+        ];
+        let rules = MergeRules::default()
+            .prefer(&["crossref", "doi.org"])
+            .field("issued", MergePolicy::MostRecent);
+
+        let merged = merge_records(&records, &rules).unwrap();
+        assert_eq!(merged.metadata.title.as_deref(), Some("A  study"));
+        assert!(merged.metadata.page.is_some());
+        assert_eq!(
+            merged.metadata.abstract_text.as_deref(),
+            Some("A much longer abstract.")
+        );
+        assert_eq!(
+            merged.metadata.extra["subject"],
+            serde_json::json!(["biology", "Ecology"])
+        );
+        assert_eq!(merged.provenance["issued"][0].source, "doi.org");
+        assert_eq!(merged.provenance["page"][0].source, "crossref");
+
+        let conflicted: Vec<_> = merged
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.field.as_str())
+            .collect();
+        assert_eq!(conflicted, ["abstract", "issued"]);
+        assert_eq!(merged.conflicts[1].values[0].source, "crossref");
+    }
+}
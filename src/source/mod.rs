@@ -0,0 +1,74 @@
+//! Pluggable metadata sources.
+//!
+//! Call sites depend on [`MetadataSource`] instead of a concrete client, so
+//! services can swap in mocks, caches or composite sources.
+
+pub mod error;
+pub mod memory;
+pub mod merge;
+pub mod resolver;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Doi;
+use crate::csl::DoiMetadata;
+use crate::source::error::SourceError;
+
+/// Metadata for one DOI together with where and when it was fetched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Record {
+    /// The CSL-JSON metadata.
+    pub metadata: DoiMetadata,
+    /// Name of the source that produced the metadata (e.g. `crossref`).
+    pub source: String,
+    /// When the metadata was fetched.
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl Record {
+    /// Build a record fetched now.
+    pub fn new(metadata: DoiMetadata, source: &str) -> Self {
+        Self {
+            metadata,
+            source: source.to_string(),
+            fetched_at: Utc::now(),
+        }
+    }
+}
+
+/// A backend that returns metadata for a DOI.
+#[async_trait]
+pub trait MetadataSource: Send + Sync {
+    /// Return a short, stable name used in records and errors.
+    fn name(&self) -> &str;
+
+    /// Fetch metadata for a DOI.
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError>;
+}
+
+#[async_trait]
+impl<T: MetadataSource + ?Sized> MetadataSource for Arc<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        (**self).metadata(doi).await
+    }
+}
+
+#[async_trait]
+impl<T: MetadataSource + ?Sized> MetadataSource for Box<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        (**self).metadata(doi).await
+    }
+}
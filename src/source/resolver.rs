@@ -0,0 +1,592 @@
+//! A composite source that tries several backends in order.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::Doi;
+use crate::csl::DoiMetadata;
+use crate::doi_org::agency::RegistrationAgency;
+use crate::source::error::*;
+use crate::source::{MetadataSource, Record};
+
+/// Looks up the registration agency of a DOI.
+#[async_trait]
+pub trait AgencyLookup: Send + Sync {
+    /// Return the agency, or `None` when it is unknown.
+    async fn agency(&self, doi: &Doi) -> Result<Option<RegistrationAgency>, SourceError>;
+}
+
+/// A source in a [`FallbackResolver`] chain.
+#[derive(Clone)]
+pub struct ResolverSource {
+    source: Arc<dyn MetadataSource>,
+    agencies: Vec<RegistrationAgency>,
+    timeout: Option<Duration>,
+}
+
+impl ResolverSource {
+    /// Wrap a source that is tried for every DOI.
+    pub fn new(source: impl MetadataSource + 'static) -> Self {
+        Self::from_arc(Arc::new(source))
+    }
+
+    /// Wrap a shared source.
+    pub fn from_arc(source: Arc<dyn MetadataSource>) -> Self {
+        Self {
+            source,
+            agencies: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Only try this source for DOIs registered with the given agency.
+    ///
+    /// May be called several times; DOIs with an unknown agency are always tried.
+    pub fn only_for(mut self, agency: RegistrationAgency) -> Self {
+        self.agencies.push(agency);
+        self
+    }
+
+    /// Override the resolver's default timeout for this source.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Return the wrapped source's name.
+    pub fn name(&self) -> &str {
+        self.source.name()
+    }
+
+    /// Return true when the source should be tried for the agency.
+    fn accepts(&self, agency: &RegistrationAgency) -> bool {
+        self.agencies.is_empty() || self.agencies.contains(agency)
+    }
+}
+
+impl fmt::Debug for ResolverSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolverSource")
+            .field("source", &self.source.name())
+            .field("agencies", &self.agencies)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// Why a source did or did not answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "outcome", content = "detail")]
+pub enum AttemptOutcome {
+    /// The source returned complete metadata.
+    Answered,
+    /// The source is restricted to other agencies.
+    SkippedAgency(RegistrationAgency),
+    /// The source does not know the DOI.
+    NotFound,
+    /// The source cannot serve the DOI in the given media type (HTTP 406).
+    SkippedFormat(String),
+    /// The source did not answer within its timeout.
+    TimedOut,
+    /// The source answered, but the metadata failed the completeness check.
+    Incomplete,
+    /// The source failed with an error.
+    Failed(String),
+}
+
+impl fmt::Display for AttemptOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttemptOutcome::Answered => f.write_str("answered"),
+            AttemptOutcome::SkippedAgency(agency) => write!(f, "skipped, DOI is {agency}"),
+            AttemptOutcome::NotFound => f.write_str("not found"),
+            AttemptOutcome::SkippedFormat(accept) => write!(f, "skipped, no {accept}"),
+            AttemptOutcome::TimedOut => f.write_str("timed out"),
+            AttemptOutcome::Incomplete => f.write_str("incomplete"),
+            AttemptOutcome::Failed(message) => write!(f, "failed: {message}"),
+        }
+    }
+}
+
+/// One step of a resolution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    /// Name of the source.
+    pub source: String,
+    /// What happened.
+    #[serde(flatten)]
+    pub outcome: AttemptOutcome,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.outcome)
+    }
+}
+
+/// The result of [`FallbackResolver::resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    /// The record from the source that answered.
+    pub record: Record,
+    /// The registration agency, when it could be determined.
+    pub agency: Option<RegistrationAgency>,
+    /// Every source consulted, in order.
+    pub attempts: Vec<Attempt>,
+}
+
+/// Tries sources in order, routed by registration agency, until one answers.
+///
+/// When no source returns complete metadata, the first incomplete record is
+/// returned instead of an error.
+pub struct FallbackResolver {
+    sources: Vec<ResolverSource>,
+    lookup: Option<Arc<dyn AgencyLookup>>,
+    default_timeout: Duration,
+    is_complete: fn(&DoiMetadata) -> bool,
+}
+
+impl Default for FallbackResolver {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            lookup: None,
+            default_timeout: Duration::from_secs(10),
+            is_complete: has_core_fields,
+        }
+    }
+}
+
+impl FallbackResolver {
+    /// Build an empty resolver with a 10 second per-source timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a source to the chain.
+    pub fn source(mut self, source: ResolverSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Look up the registration agency before trying sources.
+    pub fn agency_lookup(mut self, lookup: impl AgencyLookup + 'static) -> Self {
+        self.lookup = Some(Arc::new(lookup));
+        self
+    }
+
+    /// Set the timeout for sources without their own.
+    pub fn default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Replace the completeness check (default: a title plus an author or issued date).
+    pub fn completeness(mut self, is_complete: fn(&DoiMetadata) -> bool) -> Self {
+        self.is_complete = is_complete;
+        self
+    }
+
+    /// Resolve a DOI, recording every attempt.
+    pub async fn resolve(&self, doi: &Doi) -> Result<Resolution, SourceError> {
+        // A failed lookup only disables routing; every source is still tried.
+        let agency = match &self.lookup {
+            Some(lookup) => lookup.agency(doi).await.ok().flatten(),
+            None => None,
+        };
+
+        let mut attempts = Vec::new();
+        let mut fallback: Option<Record> = None;
+        for entry in &self.sources {
+            let source = entry.name().to_string();
+            if let Some(agency) = agency.as_ref().filter(|agency| !entry.accepts(agency)) {
+                let outcome = AttemptOutcome::SkippedAgency(agency.clone());
+                attempts.push(Attempt { source, outcome });
+                continue;
+            }
+
+            let timeout = entry.timeout.unwrap_or(self.default_timeout);
+            let outcome = match tokio::time::timeout(timeout, entry.source.metadata(doi)).await {
+                Err(_) => AttemptOutcome::TimedOut,
+                Ok(Err(error)) if error.is_not_found() => AttemptOutcome::NotFound,
+                Ok(Err(SourceError::NotAcceptable { accept, .. })) => {
+                    AttemptOutcome::SkippedFormat(accept)
+                }
+                Ok(Err(error)) => AttemptOutcome::Failed(error.to_string()),
+                Ok(Ok(record)) if (self.is_complete)(&record.metadata) => {
+                    attempts.push(Attempt {
+                        source,
+                        outcome: AttemptOutcome::Answered,
+                    });
+                    return Ok(Resolution {
+                        record,
+                        agency,
+                        attempts,
+                    });
+                }
+                Ok(Ok(record)) => {
+                    fallback.get_or_insert(record);
+                    AttemptOutcome::Incomplete
+                }
+            };
+            attempts.push(Attempt { source, outcome });
+        }
+
+        if let Some(record) = fallback {
+            return Ok(Resolution {
+                record,
+                agency,
+                attempts,
+            });
+        }
+        let all_not_found = attempts.iter().all(|attempt| {
+            matches!(
+                attempt.outcome,
+                AttemptOutcome::NotFound
+                    | AttemptOutcome::SkippedAgency(_)
+                    | AttemptOutcome::SkippedFormat(_)
+            )
+        });
+        if all_not_found {
+            return NotFoundSnafu {
+                source_name: "fallback",
+                doi: doi.as_str(),
+            }
+            .fail();
+        }
+        ExhaustedSnafu {
+            doi: doi.as_str(),
+            attempts,
+        }
+        .fail()
+    }
+}
+
+#[async_trait]
+impl MetadataSource for FallbackResolver {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        self.resolve(doi).await.map(|resolution| resolution.record)
+    }
+}
+
+/// Default completeness check: a title plus an author or issued date.
+fn has_core_fields(metadata: &DoiMetadata) -> bool {
+    let has_title = metadata
+        .title
+        .as_deref()
+        .is_some_and(|title| !title.trim().is_empty());
+    has_title && (!metadata.author.is_empty() || metadata.issued.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::memory::MemorySource;
+
+    /// An agency lookup returning a fixed answer.
+    struct FixedAgency(Option<RegistrationAgency>);
+
+    #[async_trait]
+    impl AgencyLookup for FixedAgency {
+        async fn agency(&self, _doi: &Doi) -> Result<Option<RegistrationAgency>, SourceError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    /// A source that never answers in time.
+    struct SlowSource;
+
+    #[async_trait]
+    impl MetadataSource for SlowSource {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            NotFoundSnafu {
+                source_name: "slow",
+                doi: doi.as_str(),
+            }
+            .fail()
+        }
+    }
+
+    /// A source that always fails with the error it builds.
+    struct FailingSource(&'static str, fn(&Doi) -> SourceError);
+
+    #[async_trait]
+    impl MetadataSource for FailingSource {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+            Err((self.1)(doi))
+        }
+    }
+
+    /// An agency lookup that always fails.
+    struct BrokenLookup;
+
+    #[async_trait]
+    impl AgencyLookup for BrokenLookup {
+        async fn agency(&self, _doi: &Doi) -> Result<Option<RegistrationAgency>, SourceError> {
+            OtherSnafu {
+                stage: "agency",
+                source_name: "lookup",
+                message: "unreachable",
+            }
+            .fail()
+        }
+    }
+
+    fn not_acceptable(doi: &Doi) -> SourceError {
+        SourceError::NotAcceptable {
+            source_name: "doi.org".to_string(),
+            doi: doi.as_str().to_string(),
+            accept: "application/vnd.citationstyles.csl+json".to_string(),
+        }
+    }
+
+    fn broken(_doi: &Doi) -> SourceError {
+        SourceError::Other {
+            stage: "send-request",
+            source_name: "broken".to_string(),
+            message: "connection reset".to_string(),
+        }
+    }
+
+    fn outcomes(attempts: &[Attempt]) -> Vec<String> {
+        attempts.iter().map(ToString::to_string).collect()
+    }
+
+    fn titled(title: &str, complete: bool) -> DoiMetadata {
+        DoiMetadata {
+            title: Some(title.to_string()),
+            issued: complete.then(|| serde_json::from_str(r#"{"date-parts":[[2020]]}"#).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    /// Skips sources for other agencies, times out slow ones and records why.
+    async fn routes_by_agency_with_timeouts() {
+        let doi = Doi::parse("10.5281/zenodo.1").unwrap();
+        let crossref = MemorySource::new("crossref").with(&doi, titled("Wrong", true));
+        let datacite = MemorySource::new("datacite").with(&doi, titled("Dataset", true));
+        let resolver = FallbackResolver::new()
+            .agency_lookup(FixedAgency(Some(RegistrationAgency::DataCite)))
+            .source(ResolverSource::new(crossref).only_for(RegistrationAgency::Crossref))
+            .source(ResolverSource::new(SlowSource).timeout(Duration::from_millis(20)))
+            .source(ResolverSource::new(datacite).only_for(RegistrationAgency::DataCite));
+
+        let resolution = resolver.resolve(&doi).await.unwrap();
+        assert_eq!(resolution.record.source, "datacite");
+        assert_eq!(resolution.record.metadata.title.as_deref(), Some("Dataset"));
+        assert_eq!(resolution.agency, Some(RegistrationAgency::DataCite));
+        let outcomes: Vec<_> = resolution
+            .attempts
+            .iter()
+            .map(|attempt| attempt.to_string())
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                "crossref: skipped, DOI is DataCite",
+                "slow: timed out",
+                "datacite: answered"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    /// Falls through incomplete records and returns the first one when nothing is complete.
+    async fn incomplete_and_exhausted() {
+        let doi = Doi::parse("10.5555/partial").unwrap();
+        let partial = MemorySource::new("doi.org").with(&doi, titled("Partial", false));
+        let empty = MemorySource::new("crossref");
+        let resolver = FallbackResolver::new()
+            .source(ResolverSource::new(empty))
+            .source(ResolverSource::new(partial));
+
+        let resolution = resolver.resolve(&doi).await.unwrap();
+        assert_eq!(resolution.record.source, "doi.org");
+        assert_eq!(resolution.attempts[0].outcome, AttemptOutcome::NotFound);
+        assert_eq!(resolution.attempts[1].outcome, AttemptOutcome::Incomplete);
+
+        let missing = Doi::parse("10.5555/missing").unwrap();
+        assert!(
+            resolver
+                .metadata(&missing)
+                .await
+                .unwrap_err()
+                .is_not_found()
+        );
+
+        let resolver = FallbackResolver::new()
+            .default_timeout(Duration::from_millis(10))
+            .source(ResolverSource::new(SlowSource));
+        let error = resolver.resolve(&missing).await.unwrap_err();
+        assert!(matches!(error, SourceError::Exhausted { .. }));
+        assert!(error.to_string().contains("slow: timed out"));
+    }
+
+    #[tokio::test]
+    /// Records 406 answers as skips and errors as failures, then keeps going.
+    async fn records_skips_and_failures() {
+        let doi = Doi::parse("10.5555/thesis").unwrap();
+        let answer = MemorySource::new("bibtex").with(&doi, titled("Thesis", true));
+        let resolver = FallbackResolver::new()
+            .source(ResolverSource::new(FailingSource(
+                "doi.org",
+                not_acceptable,
+            )))
+            .source(ResolverSource::new(FailingSource("broken", broken)))
+            .source(ResolverSource::new(answer));
+
+        let resolution = resolver.resolve(&doi).await.unwrap();
+        assert_eq!(resolution.record.source, "bibtex");
+        assert_eq!(resolution.agency, None);
+        assert_eq!(
+            outcomes(&resolution.attempts),
+            [
+                "doi.org: skipped, no application/vnd.citationstyles.csl+json",
+                "broken: failed: Source broken failed at send-request: connection reset",
+                "bibtex: answered",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    /// Reports not found when every source was skipped or did not know the DOI.
+    async fn all_skipped_is_not_found() {
+        let doi = Doi::parse("10.5555/unknown").unwrap();
+        let resolver = FallbackResolver::new()
+            .agency_lookup(FixedAgency(Some(RegistrationAgency::Crossref)))
+            .source(ResolverSource::new(MemorySource::new("crossref")))
+            .source(
+                ResolverSource::new(MemorySource::new("datacite"))
+                    .only_for(RegistrationAgency::DataCite),
+            )
+            .source(ResolverSource::new(FailingSource(
+                "doi.org",
+                not_acceptable,
+            )));
+        assert!(resolver.resolve(&doi).await.unwrap_err().is_not_found());
+
+        let resolver = resolver.source(ResolverSource::new(FailingSource("broken", broken)));
+        let error = resolver.resolve(&doi).await.unwrap_err();
+        assert!(matches!(error, SourceError::Exhausted { .. }));
+    }
+
+    #[tokio::test]
+    /// Tries agency-restricted sources when the agency lookup fails.
+    async fn failed_lookup_disables_routing() {
+        let doi = Doi::parse("10.5281/zenodo.2").unwrap();
+        let datacite = MemorySource::new("datacite").with(&doi, titled("Dataset", true));
+        let resolver = FallbackResolver::new()
+            .agency_lookup(BrokenLookup)
+            .source(ResolverSource::new(datacite).only_for(RegistrationAgency::DataCite));
+
+        let resolution = resolver.resolve(&doi).await.unwrap();
+        assert_eq!(resolution.agency, None);
+        assert_eq!(outcomes(&resolution.attempts), ["datacite: answered"]);
+    }
+
+    #[tokio::test]
+    /// Applies a custom completeness check.
+    async fn custom_completeness() {
+        let doi = Doi::parse("10.5555/abstract").unwrap();
+        let bare = MemorySource::new("crossref").with(&doi, titled("Bare", true));
+        let mut rich = titled("Rich", true);
+        rich.abstract_text = Some("An abstract.".to_string());
+        let rich = MemorySource::new("datacite").with(&doi, rich);
+        let resolver = FallbackResolver::new()
+            .completeness(|metadata| metadata.abstract_text.is_some())
+            .source(ResolverSource::new(bare))
+            .source(ResolverSource::new(rich));
+
+        let resolution = resolver.resolve(&doi).await.unwrap();
+        assert_eq!(resolution.record.source, "datacite");
+        assert_eq!(
+            outcomes(&resolution.attempts),
+            ["crossref: incomplete", "datacite: answered"]
+        );
+    }
+
+    #[test]
+    /// Serializes attempts with a kebab-case outcome tag and its detail.
+    fn attempt_json() {
+        let attempt = Attempt {
+            source: "doi.org".to_string(),
+            outcome: AttemptOutcome::SkippedFormat("application/x-bibtex".to_string()),
+        };
+        let value = serde_json::to_value(&attempt).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "source": "doi.org",
+                "outcome": "skipped-format",
+                "detail": "application/x-bibtex"
+            })
+        );
+        assert_eq!(serde_json::from_value::<Attempt>(value).unwrap(), attempt);
+    }
+
+    #[tokio::test]
+    /// Falls back from doi.org CSL-JSON to doi.org BibTeX when CSL-JSON is not acceptable.
+    async fn doi_org_bibtex_after_406() {
+        use crate::{DoiOrgBibtexSource, DoiOrgClient, DoiOrgConfig};
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/thesis"))
+            .and(header("accept", DoiOrgClient::CSL_JSON_ACCEPT))
+            .respond_with(ResponseTemplate::new(406))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/10.5555/thesis"))
+            .and(header("accept", DoiOrgBibtexSource::ACCEPT))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "@phdthesis{Doe_2019, title={On things}, author={Doe, Jane},                  school={Example University}, year={2019}}",
+            ))
+            .mount(&server)
+            .await;
+        let client = Arc::new(
+            DoiOrgClient::new(DoiOrgConfig {
+                base_url: server.uri(),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        let resolver = FallbackResolver::new()
+            .source(ResolverSource::from_arc(client.clone()))
+            .source(ResolverSource::new(DoiOrgBibtexSource::from_arc(client)));
+
+        let doi = Doi::parse("10.5555/thesis").unwrap();
+        let resolution = resolver.resolve(&doi).await.unwrap();
+        assert_eq!(
+            resolution.record.metadata.title.as_deref(),
+            Some("On things")
+        );
+        assert_eq!(
+            outcomes(&resolution.attempts),
+            [
+                "doi.org: skipped, no application/vnd.citationstyles.csl+json",
+                "doi.org-bibtex: answered",
+            ]
+        );
+    }
+}
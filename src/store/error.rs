@@ -0,0 +1,27 @@
+use snafu::Snafu;
+
+/// Errors returned by the local metadata store.
+#[derive(Snafu, Debug)]
+#[snafu(visibility(pub(crate)))]
+pub enum StoreError {
+    #[snafu(display("SQLite failed at {stage}: {source}"))]
+    Sqlite {
+        stage: &'static str,
+        source: rusqlite::Error,
+    },
+
+    #[snafu(display("Failed to encode CSL-JSON at {stage}: {source}"))]
+    Json {
+        stage: &'static str,
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Record has no DOI"))]
+    MissingDoi,
+
+    #[snafu(display("Record has an invalid DOI: {source}"))]
+    InvalidDoi { source: crate::DoiParseError },
+
+    #[snafu(display("Store lock poisoned"))]
+    Poisoned,
+}
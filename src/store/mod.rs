@@ -0,0 +1,4 @@
+//! Persistent local storage of metadata records.
+
+pub mod error;
+pub mod sqlite;
@@ -0,0 +1,541 @@
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+use snafu::{OptionExt, ResultExt};
+
+use crate::Doi;
+use crate::csl::{CslName, DoiMetadata};
+use crate::source::error::{NotFoundSnafu, SourceError, StoreSnafu};
+use crate::source::{MetadataSource, Record};
+use crate::store::error::*;
+
+const SCHEMA: &str = r#"
+PRAGMA foreign_keys = ON;
+CREATE TABLE IF NOT EXISTS works (
+    id INTEGER PRIMARY KEY,
+    doi TEXT NOT NULL UNIQUE,
+    item_type TEXT NOT NULL,
+    title TEXT,
+    container_title TEXT,
+    issued_year INTEGER,
+    abstract TEXT,
+    csl TEXT NOT NULL,
+    source TEXT,
+    fetched_at TEXT,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS authors (
+    work_id INTEGER NOT NULL REFERENCES works(id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    family TEXT,
+    given TEXT,
+    literal TEXT,
+    orcid TEXT,
+    PRIMARY KEY (work_id, role, position)
+);
+CREATE TABLE IF NOT EXISTS identifiers (
+    work_id INTEGER NOT NULL REFERENCES works(id) ON DELETE CASCADE,
+    scheme TEXT NOT NULL,
+    value TEXT NOT NULL,
+    normalized TEXT NOT NULL,
+    PRIMARY KEY (work_id, scheme, normalized)
+);
+CREATE INDEX IF NOT EXISTS identifiers_normalized ON identifiers (normalized);
+CREATE TABLE IF NOT EXISTS work_references (
+    work_id INTEGER NOT NULL REFERENCES works(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    doi TEXT,
+    unstructured TEXT,
+    PRIMARY KEY (work_id, position)
+);
+CREATE INDEX IF NOT EXISTS work_references_doi ON work_references (doi);
+CREATE VIRTUAL TABLE IF NOT EXISTS works_fts USING fts5 (title, abstract);
+"#;
+
+/// A SQLite library of CSL records in normalized tables.
+///
+/// The full CSL-JSON is kept in `works.csl`; authors, identifiers and
+/// references are split out for querying, and titles and abstracts are
+/// indexed with FTS5.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open or create a database file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let conn = Connection::open(path).context(SqliteSnafu { stage: "open" })?;
+        Self::with_connection(conn)
+    }
+
+    /// Open a private in-memory database.
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        let conn = Connection::open_in_memory().context(SqliteSnafu { stage: "open" })?;
+        Self::with_connection(conn)
+    }
+
+    /// Create the schema on an existing connection.
+    pub fn with_connection(conn: Connection) -> Result<Self, StoreError> {
+        conn.execute_batch(SCHEMA)
+            .context(SqliteSnafu { stage: "schema" })?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Insert or replace metadata by DOI, returning the work id.
+    pub fn upsert(&self, metadata: &DoiMetadata) -> Result<i64, StoreError> {
+        self.upsert_with(metadata, None)
+    }
+
+    /// Insert or replace a record, keeping its source and fetch time.
+    pub fn upsert_record(&self, record: &Record) -> Result<i64, StoreError> {
+        self.upsert_with(&record.metadata, Some(record))
+    }
+
+    /// Return the metadata stored for a DOI.
+    pub fn get(&self, doi: &Doi) -> Result<Option<DoiMetadata>, StoreError> {
+        let conn = self.lock()?;
+        let csl: Option<String> = conn
+            .query_row(
+                "SELECT csl FROM works WHERE doi = ?1",
+                [doi.canonical()],
+                |row| row.get(0),
+            )
+            .optional()
+            .context(SqliteSnafu { stage: "get" })?;
+        csl.map(|csl| decode(&csl)).transpose()
+    }
+
+    /// Return the record stored for a DOI with its source and fetch time.
+    ///
+    /// Works stored with [`upsert`](Self::upsert) have no source; they are
+    /// reported as `sqlite`, fetched when they were last written.
+    pub fn get_record(&self, doi: &Doi) -> Result<Option<Record>, StoreError> {
+        let conn = self.lock()?;
+        let row: Option<(String, Option<String>, String)> = conn
+            .query_row(
+                "SELECT csl, source, COALESCE(fetched_at, updated_at) FROM works WHERE doi = ?1",
+                [doi.canonical()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .context(SqliteSnafu {
+                stage: "get-record",
+            })?;
+        row.map(|(csl, source, fetched_at)| {
+            Ok(Record {
+                metadata: decode(&csl)?,
+                source: source.unwrap_or_else(|| "sqlite".to_string()),
+                fetched_at: DateTime::parse_from_rfc3339(&fetched_at)
+                    .map_or_else(|_| Utc::now(), |time| time.with_timezone(&Utc)),
+            })
+        })
+        .transpose()
+    }
+
+    /// Find works by any identifier (DOI, ISSN, ISBN, PMID, PMCID or URL).
+    ///
+    /// Matching ignores case, spaces and hyphens, so `0028-0836` finds `00280836`.
+    pub fn find_by_identifier(&self, identifier: &str) -> Result<Vec<DoiMetadata>, StoreError> {
+        self.query_csl(
+            "SELECT DISTINCT works.csl FROM works \
+             JOIN identifiers ON identifiers.work_id = works.id \
+             WHERE identifiers.normalized = ?1 ORDER BY works.id",
+            &normalize_identifier(identifier),
+        )
+    }
+
+    /// Full-text search on title and abstract, best matches first.
+    ///
+    /// Each word of `query` must match; FTS5 operators are not interpreted.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<DoiMetadata>, StoreError> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.lock()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT works.csl FROM works_fts JOIN works ON works.id = works_fts.rowid \
+                 WHERE works_fts MATCH ?1 ORDER BY bm25(works_fts) LIMIT ?2",
+            )
+            .context(SqliteSnafu { stage: "search" })?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = statement
+            .query_map(params![terms.join(" "), limit], |row| {
+                row.get::<_, String>(0)
+            })
+            .context(SqliteSnafu { stage: "search" })?;
+        collect_csl(rows)
+    }
+
+    /// Delete a work, returning true when it existed.
+    pub fn remove(&self, doi: &Doi) -> Result<bool, StoreError> {
+        let mut conn = self.lock()?;
+        let tx = conn
+            .transaction()
+            .context(SqliteSnafu { stage: "remove" })?;
+        let id: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM works WHERE doi = ?1",
+                [doi.canonical()],
+                |row| row.get(0),
+            )
+            .optional()
+            .context(SqliteSnafu { stage: "remove" })?;
+        if let Some(id) = id {
+            tx.execute("DELETE FROM works_fts WHERE rowid = ?1", [id])
+                .context(SqliteSnafu { stage: "remove" })?;
+            tx.execute("DELETE FROM works WHERE id = ?1", [id])
+                .context(SqliteSnafu { stage: "remove" })?;
+        }
+        tx.commit().context(SqliteSnafu { stage: "commit" })?;
+        Ok(id.is_some())
+    }
+
+    /// Return the number of stored works.
+    pub fn len(&self) -> Result<usize, StoreError> {
+        let conn = self.lock()?;
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM works", [], |row| row.get(0))
+            .context(SqliteSnafu { stage: "count" })?;
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
+    /// Return true when no works are stored.
+    pub fn is_empty(&self) -> Result<bool, StoreError> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Return every stored item, ordered by DOI.
+    pub fn export(&self) -> Result<Vec<DoiMetadata>, StoreError> {
+        let conn = self.lock()?;
+        let mut statement = conn
+            .prepare("SELECT csl FROM works ORDER BY doi")
+            .context(SqliteSnafu { stage: "export" })?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .context(SqliteSnafu { stage: "export" })?;
+        collect_csl(rows)
+    }
+
+    /// Return every stored item as a CSL-JSON array.
+    pub fn export_csl_json(&self) -> Result<String, StoreError> {
+        serde_json::to_string_pretty(&self.export()?).context(JsonSnafu { stage: "export" })
+    }
+
+    /// Run a single-parameter query returning `csl` values.
+    fn query_csl(&self, sql: &str, param: &str) -> Result<Vec<DoiMetadata>, StoreError> {
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(sql).context(SqliteSnafu { stage: "query" })?;
+        let rows = statement
+            .query_map([param], |row| row.get::<_, String>(0))
+            .context(SqliteSnafu { stage: "query" })?;
+        collect_csl(rows)
+    }
+
+    fn upsert_with(
+        &self,
+        metadata: &DoiMetadata,
+        record: Option<&Record>,
+    ) -> Result<i64, StoreError> {
+        let doi = metadata
+            .doi
+            .as_deref()
+            .map(str::trim)
+            .filter(|doi| !doi.is_empty())
+            .context(MissingDoiSnafu)?;
+        let doi = Doi::parse(doi).context(InvalidDoiSnafu)?.canonical();
+        let csl = serde_json::to_string(metadata).context(JsonSnafu { stage: "encode" })?;
+
+        let mut conn = self.lock()?;
+        let tx = conn
+            .transaction()
+            .context(SqliteSnafu { stage: "upsert" })?;
+        let id: i64 = tx
+            .query_row(
+                "INSERT INTO works (doi, item_type, title, container_title, issued_year, \
+                 abstract, csl, source, fetched_at, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) \
+                 ON CONFLICT (doi) DO UPDATE SET item_type = excluded.item_type, \
+                 title = excluded.title, container_title = excluded.container_title, \
+                 issued_year = excluded.issued_year, abstract = excluded.abstract, \
+                 csl = excluded.csl, source = excluded.source, \
+                 fetched_at = excluded.fetched_at, updated_at = excluded.updated_at \
+                 RETURNING id",
+                params![
+                    doi,
+                    metadata.item_type.as_str(),
+                    metadata.title,
+                    metadata.container_title,
+                    metadata.issued.as_ref().and_then(|issued| issued.year()),
+                    metadata.abstract_text,
+                    csl,
+                    record.map(|record| record.source.as_str()),
+                    record.map(|record| record.fetched_at.to_rfc3339()),
+                    Utc::now().to_rfc3339(),
+                ],
+                |row| row.get(0),
+            )
+            .context(SqliteSnafu { stage: "upsert" })?;
+
+        replace_children(&tx, id, &doi, metadata).context(SqliteSnafu {
+            stage: "upsert-children",
+        })?;
+        tx.commit().context(SqliteSnafu { stage: "commit" })?;
+        Ok(id)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>, StoreError> {
+        self.conn.lock().ok().context(PoisonedSnafu)
+    }
+}
+
+/// Rewrite the author, identifier, reference and FTS rows of a work.
+fn replace_children(
+    tx: &Transaction<'_>,
+    id: i64,
+    doi: &str,
+    metadata: &DoiMetadata,
+) -> rusqlite::Result<()> {
+    for table in ["authors", "identifiers", "work_references"] {
+        tx.execute(&format!("DELETE FROM {table} WHERE work_id = ?1"), [id])?;
+    }
+    tx.execute("DELETE FROM works_fts WHERE rowid = ?1", [id])?;
+
+    let roles: [(&str, &Vec<CslName>); 6] = [
+        ("author", &metadata.author),
+        ("editor", &metadata.editor),
+        ("translator", &metadata.translator),
+        ("container-author", &metadata.container_author),
+        ("collection-editor", &metadata.collection_editor),
+        ("contributor", &metadata.contributor),
+    ];
+    let mut insert_author = tx.prepare(
+        "INSERT INTO authors (work_id, role, position, family, given, literal, orcid) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for (role, names) in roles {
+        for (position, name) in names.iter().enumerate() {
+            insert_author.execute(params![
+                id,
+                role,
+                position as i64,
+                name.family,
+                name.given,
+                name.literal,
+                name.orcid
+            ])?;
+        }
+    }
+
+    let mut identifiers = vec![("doi", doi)];
+    identifiers.extend(metadata.issn.iter().map(|issn| ("issn", issn.as_str())));
+    identifiers.extend(metadata.isbn.iter().map(|isbn| ("isbn", isbn.as_str())));
+    identifiers.extend(metadata.pmid.as_deref().map(|pmid| ("pmid", pmid)));
+    identifiers.extend(metadata.pmcid.as_deref().map(|pmcid| ("pmcid", pmcid)));
+    identifiers.extend(metadata.url.as_deref().map(|url| ("url", url)));
+    let mut insert_identifier = tx.prepare(
+        "INSERT OR IGNORE INTO identifiers (work_id, scheme, value, normalized) \
+         VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (scheme, value) in identifiers {
+        let normalized = normalize_identifier(value);
+        if !normalized.is_empty() {
+            insert_identifier.execute(params![id, scheme, value.trim(), normalized])?;
+        }
+    }
+
+    let mut insert_reference = tx.prepare(
+        "INSERT INTO work_references (work_id, position, key, doi, unstructured) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (position, reference) in metadata.reference.iter().enumerate() {
+        insert_reference.execute(params![
+            id,
+            position as i64,
+            reference.key,
+            reference.doi.as_deref().map(str::to_lowercase),
+            reference.unstructured
+        ])?;
+    }
+
+    tx.execute(
+        "INSERT INTO works_fts (rowid, title, abstract) VALUES (?1, ?2, ?3)",
+        params![id, metadata.title, metadata.abstract_text],
+    )?;
+    Ok(())
+}
+
+/// Lowercase an identifier and drop spaces and hyphens.
+fn normalize_identifier(value: &str) -> String {
+    value
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn decode(csl: &str) -> Result<DoiMetadata, StoreError> {
+    serde_json::from_str(csl).context(JsonSnafu { stage: "decode" })
+}
+
+fn collect_csl(
+    rows: impl Iterator<Item = rusqlite::Result<String>>,
+) -> Result<Vec<DoiMetadata>, StoreError> {
+    rows.map(|row| decode(&row.context(SqliteSnafu { stage: "read-row" })?))
+        .collect()
+}
+
+#[async_trait]
+impl MetadataSource for SqliteStore {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn metadata(&self, doi: &Doi) -> Result<Record, SourceError> {
+        match self
+            .get_record(doi)
+            .context(StoreSnafu { stage: "metadata" })?
+        {
+            Some(record) => Ok(record),
+            None => NotFoundSnafu {
+                source_name: MetadataSource::name(self),
+                doi: doi.as_str(),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(json: &str) -> DoiMetadata {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn thermometry() -> DoiMetadata {
+        item(
+            r#"{"type":"article-journal","DOI":"10.1038/Nature12373","title":"Nanometre-scale thermometry",
+            "abstract":"Quantum sensing in living cells.","ISSN":["0028-0836","1476-4687"],
+            "author":[{"family":"Kucsko","given":"G."},{"family":"Maurer","given":"P. C."}],
+            "reference":[{"key":"ref1","DOI":"10.1000/REF"}]}"#,
+        )
+    }
+
+    fn handbook() -> DoiMetadata {
+        item(
+            r#"{"type":"book","DOI":"10.1000/182","title":"The DOI Handbook","ISBN":["978-1-234"]}"#,
+        )
+    }
+
+    #[test]
+    /// Replaces a work stored under the same canonical DOI and rejects bad DOIs.
+    fn store_upsert_replaces() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let first = thermometry();
+        let id = store.upsert(&first).unwrap();
+
+        let mut updated = first.clone();
+        updated.title = Some("Nanometre-scale thermometry in a living cell".to_string());
+        updated.doi = Some("https://doi.org/10.1038/NATURE12373".to_string());
+        assert_eq!(store.upsert(&updated).unwrap(), id);
+        assert_eq!(store.len().unwrap(), 1);
+        let doi = Doi::parse("10.1038/nature12373").unwrap();
+        assert_eq!(store.get(&doi).unwrap(), Some(updated));
+
+        assert!(matches!(
+            store.upsert(&DoiMetadata::default()),
+            Err(StoreError::MissingDoi)
+        ));
+        let mut invalid = handbook();
+        invalid.doi = Some("not a doi".to_string());
+        assert!(matches!(
+            store.upsert(&invalid),
+            Err(StoreError::InvalidDoi { .. })
+        ));
+        assert!(store.remove(&doi).unwrap());
+        assert!(!store.remove(&doi).unwrap());
+        assert!(store.is_empty().unwrap());
+    }
+
+    #[test]
+    /// Finds works by ISSN and ISBN regardless of hyphens.
+    fn store_find_by_identifier() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.upsert(&thermometry()).unwrap();
+        store.upsert(&handbook()).unwrap();
+
+        assert_eq!(
+            store.find_by_identifier("00280836").unwrap(),
+            [thermometry()]
+        );
+        assert_eq!(store.find_by_identifier("1476-4687").unwrap().len(), 1);
+        assert_eq!(store.find_by_identifier("9781234").unwrap(), [handbook()]);
+        assert!(store.find_by_identifier("0000-0000").unwrap().is_empty());
+    }
+
+    #[test]
+    /// Searches titles and abstracts, and forgets removed works.
+    fn store_search() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.upsert(&thermometry()).unwrap();
+        store.upsert(&handbook()).unwrap();
+
+        assert_eq!(
+            store.search("living thermometry", 10).unwrap(),
+            [thermometry()]
+        );
+        assert_eq!(store.search("handbook \"", 10).unwrap(), [handbook()]);
+        assert!(store.search("   ", 10).unwrap().is_empty());
+
+        let doi = Doi::parse("10.1038/nature12373").unwrap();
+        assert!(store.remove(&doi).unwrap());
+        assert!(store.search("living", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    /// Exports every stored work as CSL-JSON that reads back unchanged.
+    fn store_export_round_trip() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        store.upsert(&thermometry()).unwrap();
+        store.upsert(&handbook()).unwrap();
+
+        let exported: Vec<DoiMetadata> =
+            serde_json::from_str(&store.export_csl_json().unwrap()).unwrap();
+        assert_eq!(exported, [handbook(), thermometry()]);
+        assert_eq!(store.export().unwrap(), exported);
+    }
+
+    #[tokio::test]
+    /// Reports the stored source and fetch time instead of `sqlite`.
+    async fn store_keeps_record_source() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let mut record = Record::new(thermometry(), "crossref");
+        record.fetched_at = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        store.upsert_record(&record).unwrap();
+        store.upsert(&handbook()).unwrap();
+
+        let doi = Doi::parse("10.1038/nature12373").unwrap();
+        let stored = store.metadata(&doi).await.unwrap();
+        assert_eq!(stored.source, "crossref");
+        assert_eq!(stored.fetched_at, record.fetched_at);
+        assert_eq!(stored.metadata, thermometry());
+
+        let doi = Doi::parse("10.1000/182").unwrap();
+        assert_eq!(store.metadata(&doi).await.unwrap().source, "sqlite");
+    }
+}